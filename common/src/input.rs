@@ -0,0 +1,114 @@
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::io::{self, ErrorKind, Read};
+use std::path::{Path, PathBuf};
+
+/// Name of the environment variable that points at a directory of puzzle inputs.
+pub const INPUTS_ENV: &str = "AOC_INPUTS";
+
+/// Where puzzle inputs are read from at runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    /// A single file, used for whichever day is being solved.
+    File(PathBuf),
+    /// Everything on standard input, used for whichever day is being solved.
+    Stdin,
+    /// A directory laid out like this repository: `<dir>/day07/input.txt`, `<dir>/day07/example.txt`.
+    Directory(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputError {
+    Missing(PathBuf),
+    Unreadable(PathBuf, ErrorKind),
+    Stdin(ErrorKind),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Missing(path) => write!(f, "Input file {} does not exist.", path.display()),
+            InputError::Unreadable(path, kind) => write!(f, "Could not read input file {}: {}.", path.display(), kind),
+            InputError::Stdin(kind) => write!(f, "Could not read input from stdin: {}.", kind),
+        }
+    }
+}
+
+impl Input {
+    /// The directory in `AOC_INPUTS` if it's set, otherwise the current working directory.
+    pub fn from_env() -> Input {
+        match env::var_os(INPUTS_ENV) {
+            Some(dir) => Input::Directory(PathBuf::from(dir)),
+            None => Input::Directory(PathBuf::from(".")),
+        }
+    }
+
+    /// Turns a command line value into an input: `-` is stdin, anything else is a file.
+    pub fn from_arg(value: &str) -> Input {
+        match value {
+            "-" => Input::Stdin,
+            path => Input::File(PathBuf::from(path)),
+        }
+    }
+
+    /// Reads the input for a day. The file name, usually `input.txt` or `example.txt`, is only used
+    /// when reading from a directory.
+    pub fn read(&self, day: u8, file_name: &str) -> Result<String, InputError> {
+        match self {
+            Input::File(path) => read_file(path),
+            Input::Stdin => {
+                let mut contents = String::new();
+                io::stdin()
+                    .read_to_string(&mut contents)
+                    .map_err(|e| InputError::Stdin(e.kind()))?;
+
+                Ok(contents)
+            }
+            Input::Directory(dir) => read_file(&day_path(dir, day, file_name)),
+        }
+    }
+}
+
+/// Where a day's file lives inside an inputs directory.
+pub fn day_path(dir: &Path, day: u8, file_name: &str) -> PathBuf {
+    dir.join(format!("day{:02}", day)).join(file_name)
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    std::fs::read_to_string(path).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => InputError::Missing(path.to_path_buf()),
+        kind => InputError::Unreadable(path.to_path_buf(), kind),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_path() {
+        assert_eq!(
+            day_path(Path::new("inputs"), 7, "example.txt"),
+            PathBuf::from("inputs/day07/example.txt")
+        );
+    }
+
+    #[test]
+    fn test_from_arg() {
+        assert_eq!(Input::from_arg("-"), Input::Stdin);
+        assert_eq!(Input::from_arg("day15/tinyexample.txt"), Input::File(PathBuf::from("day15/tinyexample.txt")));
+    }
+
+    #[test]
+    fn test_read_directory() {
+        let dir = env::temp_dir().join(format!("aoc-input-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("day03")).unwrap();
+        std::fs::write(dir.join("day03").join("input.txt"), "mul(2,4)").unwrap();
+
+        let input = Input::Directory(dir.clone());
+        assert_eq!(input.read(3, "input.txt"), Ok("mul(2,4)".to_string()));
+        assert_eq!(input.read(4, "input.txt"), Err(InputError::Missing(dir.join("day04").join("input.txt"))));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
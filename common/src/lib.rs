@@ -0,0 +1,9 @@
+mod answer;
+mod input;
+mod parse;
+mod solution;
+
+pub use answer::Answer;
+pub use input::{day_path, Input, InputError, INPUTS_ENV};
+pub use parse::{column_of, parse_number, ParseError};
+pub use solution::{Solution, SolutionError};
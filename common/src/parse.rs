@@ -0,0 +1,118 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+
+/// Returned by every day's parser when the input is not what the puzzle promised. Line and column
+/// are 1-based, so they match what an editor would show. Problems that are not tied to a single
+/// place, like a map without a guard on it, have no location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    location: Option<(usize, usize)>,
+    message: String,
+}
+
+impl ParseError {
+    pub fn at(line: usize, column: usize, message: impl Into<String>) -> ParseError {
+        ParseError { location: Some((line, column)), message: message.into() }
+    }
+
+    pub fn new(message: impl Into<String>) -> ParseError {
+        ParseError { location: None, message: message.into() }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        self.location.map(|(line, _)| line)
+    }
+
+    pub fn column(&self) -> Option<usize> {
+        self.location.map(|(_, column)| column)
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The error, followed by the offending line of the input with a marker under the column.
+    pub fn diagnostic(&self, input: &str) -> String {
+        let (line, column) = match self.location {
+            Some(l) => l,
+            None => return self.to_string(),
+        };
+
+        let source = match input.lines().nth(line - 1) {
+            Some(s) => s,
+            None => return self.to_string(),
+        };
+
+        let gutter = " ".repeat(line.to_string().len());
+        let marker = " ".repeat(source.chars().take(column - 1).count());
+
+        format!("{}\n{} |\n{} | {}\n{} | {}^", self, gutter, line, source, gutter, marker)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.location {
+            Some((line, column)) => write!(f, "line {}, column {}: {}", line, column, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Where `part` starts inside `line`, as a 1-based column. `part` has to be a slice of `line`, which
+/// is what `split`, `trim` and friends hand back.
+pub fn column_of(line: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize).saturating_sub(line.as_ptr() as usize);
+
+    line[..offset.min(line.len())].chars().count() + 1
+}
+
+/// Parses `part`, a slice of `line`, into a number. If it's not one, the error points at `part`.
+pub fn parse_number<T>(line_number: usize, line: &str, part: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Debug,
+{
+    part.trim().parse::<T>().map_err(|_| {
+        ParseError::at(line_number, column_of(line, part), format!("expected a number, found '{}'", part))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_number() {
+        let line = "83: 17 x5";
+        let parts = line.split_whitespace().collect::<Vec<&str>>();
+
+        assert_eq!(parse_number::<u64>(3, line, parts[1]), Ok(17));
+        assert_eq!(
+            parse_number::<u64>(3, line, parts[2]),
+            Err(ParseError::at(3, 8, "expected a number, found 'x5'"))
+        );
+    }
+
+    #[test]
+    fn test_diagnostic() {
+        let input = "190: 10 19\n83: 17 x5\n";
+        let error = ParseError::at(2, 8, "expected a number, found 'x5'");
+
+        assert_eq!(error.diagnostic(input), "\
+line 2, column 8: expected a number, found 'x5'
+  |
+2 | 83: 17 x5
+  |        ^");
+    }
+
+    #[test]
+    fn test_no_location() {
+        let error = ParseError::new("no guard on the map");
+
+        assert_eq!(error.to_string(), "no guard on the map");
+        assert_eq!(error.diagnostic("...."), "no guard on the map");
+    }
+}
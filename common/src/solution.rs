@@ -0,0 +1,61 @@
+use std::fmt::{Display, Formatter};
+use crate::{Answer, ParseError};
+
+/// Every day implements this, so the runner, benchmarks and tests can treat them the same way.
+/// Both parts get the full puzzle input, and are expected to do their own parsing. Input that does
+/// not look like the puzzle's comes back as a `ParseError` rather than a panic.
+pub trait Solution {
+    /// Only parses the input and throws the result away. The parts parse the input themselves, this
+    /// is here so the benchmarks can tell how much of their time goes into parsing.
+    fn parse(input: &str) -> Result<(), ParseError>;
+
+    fn part1(input: &str) -> Result<Answer, SolutionError>;
+
+    fn part2(input: &str) -> Result<Answer, SolutionError>;
+
+    /// Part 1 on the puzzle's example rather than on a real input. Only days where the example is
+    /// set up differently, like a smaller map, need to do anything else.
+    fn example_part1(input: &str) -> Result<Answer, SolutionError> {
+        Self::part1(input)
+    }
+
+    /// Same as `example_part1`, for part 2.
+    fn example_part2(input: &str) -> Result<Answer, SolutionError> {
+        Self::part2(input)
+    }
+}
+
+/// Why a part has no answer: either the input couldn't be parsed, or it could, but solving it went
+/// wrong, like a number getting too big to work with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolutionError {
+    Parse(ParseError),
+    Solve(String),
+}
+
+impl SolutionError {
+    /// Same as `ParseError::diagnostic` for parse errors, just the message otherwise.
+    pub fn diagnostic(&self, input: &str) -> String {
+        match self {
+            SolutionError::Parse(e) => e.diagnostic(input),
+            SolutionError::Solve(message) => message.clone(),
+        }
+    }
+}
+
+impl From<ParseError> for SolutionError {
+    fn from(e: ParseError) -> Self {
+        SolutionError::Parse(e)
+    }
+}
+
+impl Display for SolutionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolutionError::Parse(e) => write!(f, "{}", e),
+            SolutionError::Solve(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SolutionError {}
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::fs;
+use common::{parse_number, Answer, ParseError, Solution, SolutionError};
 
 pub struct Day01 {
     list_left: Vec<i32>,
@@ -12,20 +12,28 @@ impl Day01 {
             return String::from("the list of location IDs is bad :(");
         }
 
+        format!("Total difference is {}", self.total_difference())
+    }
+
+    pub fn solve_two(&self) -> String {
+        if self.list_right.len() != self.list_left.len() {
+            return String::from("the list of location IDs is bad :(");
+        }
+
+        format!("The total similarity score is {}", self.similarity_score())
+    }
+
+    fn total_difference(&self) -> i32 {
         let mut total = 0;
 
         for (i, x) in self.list_left.iter().enumerate() {
             total += (x - self.list_right[i]).abs();
         }
 
-        format!("Total difference is {}", total)
+        total
     }
 
-    pub fn solve_two(&self) -> String {
-        if self.list_right.len() != self.list_left.len() {
-            return String::from("the list of location IDs is bad :(");
-        }
-
+    fn similarity_score(&self) -> i32 {
         let mut total = 0;
 
         // let's create a hash map from the right
@@ -39,44 +47,70 @@ impl Day01 {
             total += y * map.get(y).unwrap_or(&0);
         }
 
-        format!("The total similarity score is {}", total)
+        total
     }
 
-    pub fn new() -> Day01 {
-        let file_contents = fs::read_to_string("./day01/input.txt").unwrap_or("".to_string());
-        let trimmed = file_contents.trim();
-
-        let boo: Vec<i32> = trimmed
-            .split("\n")
-            .flat_map(|line: &str| -> Vec<i32> {
-                line.split("   ")
-                    .map(|x: &str| -> i32 { x.parse::<i32>().unwrap_or(i32::MAX) })
-                    .collect::<Vec<i32>>()
-            })
-            .collect();
-
+    pub fn parse(input: &str) -> Result<Day01, ParseError> {
         let mut left_list: Vec<i32> = Vec::new();
         let mut right_list: Vec<i32> = Vec::new();
-        for (i, x) in boo.into_iter().enumerate() {
-            if i % 2 == 0 {
-                left_list.push(x);
-            } else {
-                right_list.push(x);
+
+        for (i, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
             }
+
+            let ids = line.split_whitespace().collect::<Vec<&str>>();
+            if ids.len() != 2 {
+                return Err(ParseError::at(
+                    i + 1,
+                    1,
+                    format!("expected two location IDs, found {}", ids.len()),
+                ));
+            }
+
+            left_list.push(parse_number(i + 1, line, ids[0])?);
+            right_list.push(parse_number(i + 1, line, ids[1])?);
         }
 
         left_list.sort();
         right_list.sort();
 
-        Day01 {
+        Ok(Day01 {
             list_left: left_list,
             list_right: right_list,
-        }
+        })
+    }
+}
+
+impl Solution for Day01 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        Day01::parse(input).map(|_| ())
+    }
+
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(Day01::parse(input)?.total_difference().into())
+    }
+
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(Day01::parse(input)?.similarity_score().into())
     }
 }
 
-impl Default for Day01 {
-    fn default() -> Self {
-        Self::new()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bad_id() {
+        let result = Day01::parse("3   4\n4   x3\n");
+
+        assert_eq!(result.err(), Some(ParseError::at(2, 5, "expected a number, found 'x3'")));
+    }
+
+    #[test]
+    fn test_parse_missing_id() {
+        let result = Day01::parse("3   4\n4\n");
+
+        assert_eq!(result.err(), Some(ParseError::at(2, 1, "expected two location IDs, found 1")));
     }
 }
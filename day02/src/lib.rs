@@ -1,27 +1,30 @@
+use common::{parse_number, Answer, ParseError, Solution, SolutionError};
+
 pub struct Day02 {
     list: Vec<Vec<i32>>,
 }
 
 
 impl Day02 {
-    pub fn new() -> Day02 {
-        let input = include_str!("../input.txt");
+    pub fn parse(input: &str) -> Result<Day02, ParseError> {
+        let mut vex: Vec<Vec<i32>> = Vec::new();
 
-        let lines = input.lines().collect::<Vec<&str>>();
+        for (i, line) in input.lines().enumerate() {
+            let report = line.split_whitespace()
+                .map(|word| parse_number::<i32>(i + 1, line, word))
+                .collect::<Result<Vec<i32>, ParseError>>()?;
 
-        let vex = lines
-            .iter()
-            .map(|line: &&str| {
-                line.split_whitespace()
-                    .map(|word| word.parse::<i32>().unwrap())
-                    .collect::<Vec<i32>>()
-            })
-            .collect();
+            vex.push(report);
+        }
 
-        Day02 { list: vex }
+        Ok(Day02 { list: vex })
     }
 
     pub fn part1(&self) -> String {
+        format!("The number of safe reports is {}", self.count_safe())
+    }
+
+    fn count_safe(&self) -> i32 {
         let mut safe_list = 0;
 
         for l in &self.list {
@@ -33,7 +36,7 @@ impl Day02 {
             }
         }
 
-        format!("The number of safe reports is {}", safe_list)
+        safe_list
     }
 
     pub fn part2(&self) -> (i32, String) {
@@ -69,9 +72,19 @@ impl Day02 {
     }
 }
 
-impl Default for Day02 {
-    fn default() -> Self {
-        Self::new()
+impl Solution for Day02 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        Day02::parse(input).map(|_| ())
+    }
+
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(Day02::parse(input)?.count_safe().into())
+    }
+
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        let (safe, _) = Day02::parse(input)?.part2();
+
+        Ok(safe.into())
     }
 }
 
@@ -144,42 +157,49 @@ mod tests {
 
     #[test]
     fn test_check_increasing_good() {
-        assert_eq!(true, check_increasing(vec![1, 2, 3, 5, 8]))
+        assert!(check_increasing(vec![1, 2, 3, 5, 8]))
     }
 
     #[test]
     fn test_check_increasing_eq_bad() {
-        assert_eq!(false, check_increasing(vec![1, 2, 3, 3, 4, 5]))
+        assert!(!check_increasing(vec![1, 2, 3, 3, 4, 5]))
     }
 
     #[test]
     fn test_check_increasing_lower() {
-        assert_eq!(false, check_increasing(vec![1, 2, 3, 4, 3]))
+        assert!(!check_increasing(vec![1, 2, 3, 4, 3]))
     }
 
     #[test]
     fn test_check_increasing_gap() {
-        assert_eq!(false, check_increasing(vec![1, 2, 3, 4, 8]))
+        assert!(!check_increasing(vec![1, 2, 3, 4, 8]))
     }
 
     #[test]
     fn test_check_decreasing_good() {
-        assert_eq!(true, check_decreasing(vec![8, 5, 3, 2, 1]))
+        assert!(check_decreasing(vec![8, 5, 3, 2, 1]))
     }
 
     #[test]
     fn test_check_decreasing_eq_bad() {
-        assert_eq!(false, check_decreasing(vec![5, 4, 3, 3, 2, 1]))
+        assert!(!check_decreasing(vec![5, 4, 3, 3, 2, 1]))
     }
 
     #[test]
     fn test_check_decreasing_higher_bad() {
-        assert_eq!(false, check_decreasing(vec![5, 4, 3, 4, 3, 2, 1]))
+        assert!(!check_decreasing(vec![5, 4, 3, 4, 3, 2, 1]))
     }
 
     #[test]
     fn test_check_decreasing_gap_bad() {
-        assert_eq!(false, check_decreasing(vec![8, 4, 3, 2, 1]))
+        assert!(!check_decreasing(vec![8, 4, 3, 2, 1]))
+    }
+
+    #[test]
+    fn test_parse_bad_level() {
+        let result = Day02::parse("7 6 4 2 1\n1 2 7 B 9\n");
+
+        assert_eq!(result.err(), Some(ParseError::at(2, 7, "expected a number, found 'B'")));
     }
 
     #[test]
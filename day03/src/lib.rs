@@ -1,3 +1,4 @@
+use common::{Answer, ParseError, Solution, SolutionError};
 use regex::{Match, Regex};
 
 const MUL_REGEX_STRING: &str = r"mul\(\d{1,3},\d{1,3}\)";
@@ -19,17 +20,15 @@ impl Mul {
     }
 }
 
-pub struct Day03 {
-    input: &'static str,
+pub struct Day03<'a> {
+    input: &'a str,
     all_muls: Vec<Mul>,
     dos: Vec<(usize, usize)>,
     donts: Vec<(usize, usize)>,
 }
 
-impl Day03 {
-    pub fn new() -> Day03 {
-        let contents = include_str!("../input.txt");
-
+impl<'a> Day03<'a> {
+    pub fn parse(contents: &'a str) -> Day03<'a> {
         Day03 {
             all_muls: Regex::new(MUL_REGEX_STRING)
                 .unwrap()
@@ -79,7 +78,11 @@ impl Day03 {
         let mut enabled: String = "".to_owned();
         // start regex
         let re_start = Regex::new(r"(?s)^.*?don't\(\)").unwrap();
-        let beginning = re_start.find(self.input).unwrap().as_str();
+        let beginning = match re_start.find(self.input) {
+            Some(m) => m.as_str(),
+            // nothing ever gets disabled.
+            None => return self.input.to_owned(),
+        };
 
         enabled.push_str(beginning);
 
@@ -91,12 +94,11 @@ impl Day03 {
             enabled.push_str(l);
         }
 
-        let last_do = self.dos.last().unwrap();
         let last_dont = self.donts.last().unwrap();
 
         // if the last thing is not a do, then it's a don't at which point we can ignore the end of
         // the string.
-        if last_do.1 > last_dont.1 {
+        if let Some(last_do) = self.dos.last().filter(|d| d.1 > last_dont.1) {
             // the last thing we have is a do, so let's add the end from there to the end of the
             // input
 
@@ -107,9 +109,21 @@ impl Day03 {
     }
 }
 
-impl Default for Day03 {
-    fn default() -> Day03 {
-        Day03::new()
+impl Solution for Day03<'_> {
+    // Anything that isn't a well formed instruction is corrupted memory, which the puzzle says to
+    // skip, so there is nothing here that can fail to parse.
+    fn parse(input: &str) -> Result<(), ParseError> {
+        Day03::parse(input);
+
+        Ok(())
+    }
+
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(Day03::parse(input).part1().into())
+    }
+
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(Day03::parse(input).part2().into())
     }
 }
 
@@ -125,3 +139,15 @@ fn parse_mul(input: &Match) -> Mul {
 
     Mul::new(numbers[0], numbers[1])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part2_without_switches() {
+        assert_eq!(Day03::parse("xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)").part2(), 33);
+        assert_eq!(Day03::parse("mul(2,4)do()mul(5,5)").part2(), 33);
+        assert_eq!(Day03::parse("mul(2,4)don't()mul(5,5)").part2(), 8);
+    }
+}
@@ -1,7 +1,5 @@
-use std::collections::HashMap;
-
-/// Coordinate is always <-> (width) first, and then height (up-down) next.
-type Coordinate = (i32, i32);
+use common::{Answer, ParseError, Solution, SolutionError};
+use grid::{Coordinate, Grid};
 
 const CHECKMAS: &str = "MAS";
 const BAD_CHAR: &char = &'.';
@@ -10,7 +8,7 @@ const SM: &str = "SM";
 
 
 pub struct Day04 {
-    word_grid: HashMap<Coordinate, char>,
+    word_grid: Grid<char>,
     x_list: Vec<Coordinate>,
     a_list: Vec<Coordinate>,
 }
@@ -26,46 +24,52 @@ enum Direction {
     NorthWest,
 }
 
-pub fn part1() -> String{
-    let contents = include_str!("../input.txt");
-    let d4 = Day04::new(contents);
+pub fn solve_part1(input: &str) -> Result<i32, ParseError> {
+    Ok(Day04::new(input)?.how_many_xmas())
+}
 
-    format!("The word XMAS can be found {} times.", d4.how_many_xmas())
+pub fn solve_part2(input: &str) -> Result<i32, ParseError> {
+    Ok(Day04::new(input)?.how_many_x_mas_part2())
 }
 
-pub fn part2() -> String{
-    let contents = include_str!("../input.txt");
-    let d4 = Day04::new(contents);
+impl Solution for Day04 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        Day04::new(input).map(|_| ())
+    }
+
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input)?.into())
+    }
 
-    format!("The word XMAS can be found {} times.", d4.how_many_x_mas_part2())
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input)?.into())
+    }
 }
 
 impl Day04 {
-    pub fn new(input: &str) -> Day04 {
-        let lines = input.trim().lines().collect::<Vec<&str>>();
+    /// Every character is a letter on the word search, even the ones that aren't X, M, A or S, but
+    /// the lines all have to be the same length.
+    pub fn new(input: &str) -> Result<Day04, ParseError> {
+        let word_grid = Grid::parse(input, Some)?;
 
-        let mut word_grid = HashMap::<(i32, i32), char>::new();
         let mut x_list = Vec::<Coordinate>::new();
         let mut a_list = Vec::<Coordinate>::new();
 
-        for (height, line) in lines.iter().enumerate() {
-            for (width, character) in line.chars().enumerate() {
-                word_grid.insert((width as i32, height as i32), character);
-                if character == 'X' {
-                    x_list.push((width as i32, height as i32));
-                }
+        for (coordinate, character) in word_grid.iter() {
+            if *character == 'X' {
+                x_list.push(coordinate);
+            }
 
-                if character == 'A' {
-                    a_list.push((width as i32, height as i32));
-                }
+            if *character == 'A' {
+                a_list.push(coordinate);
             }
         }
 
-        Day04 {
+        Ok(Day04 {
             word_grid,
             x_list,
             a_list,
-        }
+        })
     }
 
     pub fn how_many_xmas(&self) -> i32 {
@@ -98,7 +102,11 @@ impl Day04 {
         let mut x_mas = 0;
         // for all A character
         for a in self.a_list.iter() {
-            let coords = generate_coordinate_pairs_part2(a);
+            // an A on the edge can't be the middle of an X
+            let Some(coords) = generate_coordinate_pairs_part2(a) else {
+                continue;
+            };
+
             if self.is_this_x_mas(&coords) {
                 x_mas += 1;
             }
@@ -108,12 +116,13 @@ impl Day04 {
     }
 
     /// Grab the characters at the vecs, and compare if the resulting parts is "MAS". We started at
-    /// X, so we already know the first character to be X.
+    /// X, so we already know the first character to be X. Coordinates that would be off the top or
+    /// the left of the grid are not in the vec, so those come up short.
     fn is_this_xmas(&self, vecs: Vec<Coordinate>) -> bool {
         let mut mas = "".to_string();
 
         for coordinate in vecs.iter() {
-            mas.push(self.word_grid.get(coordinate).unwrap_or(BAD_CHAR).to_owned());
+            mas.push(self.word_grid.get(*coordinate).unwrap_or(BAD_CHAR).to_owned());
         }
 
         mas == CHECKMAS
@@ -127,7 +136,7 @@ impl Day04 {
             let mut mas = "".to_string();
 
             for coordinate in coord_pair {
-                mas.push(self.word_grid.get(coordinate).unwrap_or(BAD_CHAR).to_owned());
+                mas.push(self.word_grid.get(*coordinate).unwrap_or(BAD_CHAR).to_owned());
             }
 
             if mas != MS && mas != SM {
@@ -139,81 +148,40 @@ impl Day04 {
     }
 }
 
+/// The three coordinates after the origin in the given direction, as long as they don't go off the
+/// top or the left edge.
 fn generate_coordinates_part1(origin: &Coordinate, direction: &Direction) -> Vec<Coordinate> {
-    match direction {
-        Direction::North => {
-            vec![
-                (origin.0, origin.1-1),
-                (origin.0, origin.1-2),
-                (origin.0, origin.1-3),
-            ]
-        }
-        Direction::NorthEast => {
-            vec![
-                (origin.0+1, origin.1-1),
-                (origin.0+2, origin.1-2),
-                (origin.0+3, origin.1-3),
-            ]
-        }
-        Direction::East => {
-            vec![
-                (origin.0+1, origin.1),
-                (origin.0+2, origin.1),
-                (origin.0+3, origin.1),
-            ]
-        }
-        Direction::SouthEast => {
-            vec![
-                (origin.0+1, origin.1+1),
-                (origin.0+2, origin.1+2),
-                (origin.0+3, origin.1+3),
-            ]
-        }
-        Direction::South => {
-            vec![
-                (origin.0, origin.1+1),
-                (origin.0, origin.1+2),
-                (origin.0, origin.1+3),
-            ]
-        }
-        Direction::SouthWest => {
-            vec![
-                (origin.0-1, origin.1+1),
-                (origin.0-2, origin.1+2),
-                (origin.0-3, origin.1+3),
-            ]
-        }
-        Direction::West => {
-            vec![
-                (origin.0-1, origin.1),
-                (origin.0-2, origin.1),
-                (origin.0-3, origin.1),
-            ]
-        }
-        Direction::NorthWest => {
-            vec![
-                (origin.0-1, origin.1-1),
-                (origin.0-2, origin.1-2),
-                (origin.0-3, origin.1-3),
-            ]
-        }
-    }
+    let (horizontal, vertical) = match direction {
+        Direction::North => (0, -1),
+        Direction::NorthEast => (1, -1),
+        Direction::East => (1, 0),
+        Direction::SouthEast => (1, 1),
+        Direction::South => (0, 1),
+        Direction::SouthWest => (-1, 1),
+        Direction::West => (-1, 0),
+        Direction::NorthWest => (-1, -1),
+    };
+
+    (1..=3)
+        .filter_map(|step| origin.offset(horizontal * step, vertical * step))
+        .collect()
 }
 
 /// Return type is a 2-part that groups the relevant pairs of a 2 part array
-/// (bottom left - top right) and (top left - bottom right) for x shape.
-fn generate_coordinate_pairs_part2(origin: &Coordinate) -> [[Coordinate; 2]; 2] {
-   [
-       // x shape
-       [
-           (origin.0-1, origin.1-1), // top left
-           (origin.0+1, origin.1+1) // bottom right
-       ],
-       [
-           (origin.0+1, origin.1-1), // top right
-           (origin.0-1, origin.1+1) // bottom left
-       ]
-   ]
+/// (bottom left - top right) and (top left - bottom right) for x shape. None if the origin is on the
+/// top or the left edge.
+fn generate_coordinate_pairs_part2(origin: &Coordinate) -> Option<[[Coordinate; 2]; 2]> {
+    Some([
+        // x shape
+        [
+            origin.offset(-1, -1)?, // top left
+            origin.offset(1, 1)?, // bottom right
+        ],
+        [
+            origin.offset(1, -1)?, // top right
+            origin.offset(-1, 1)?, // bottom left
+        ]
+    ])
 }
 
 #[cfg(test)]
@@ -222,13 +190,13 @@ mod tests {
 
     #[test]
     fn test_generate_coordinates_north() {
-        let origin: Coordinate = (3, 3);
+        let origin = Coordinate::new(3, 3);
         let direction = Direction::North;
         let result = generate_coordinates_part1(&origin, &direction);
         let expected = vec![
-            (origin.0, origin.1-1),
-            (origin.0, origin.1-2),
-            (origin.0, origin.1-3)
+            Coordinate::new(3, 2),
+            Coordinate::new(3, 1),
+            Coordinate::new(3, 0)
         ];
 
         assert_eq!(result, expected);
@@ -236,13 +204,13 @@ mod tests {
 
     #[test]
     fn test_generate_coordinates_north_east() {
-        let origin: Coordinate = (3, 3);
+        let origin = Coordinate::new(3, 3);
         let direction = Direction::NorthEast;
         let result = generate_coordinates_part1(&origin, &direction);
         let expected = vec![
-            (origin.0+1, origin.1-1),
-            (origin.0+2, origin.1-2),
-            (origin.0+3, origin.1-3)
+            Coordinate::new(4, 2),
+            Coordinate::new(5, 1),
+            Coordinate::new(6, 0)
         ];
 
         assert_eq!(result, expected);
@@ -250,13 +218,13 @@ mod tests {
 
     #[test]
     fn test_generate_coordinates_east() {
-        let origin: Coordinate = (3, 3);
+        let origin = Coordinate::new(3, 3);
         let direction = Direction::East;
         let result = generate_coordinates_part1(&origin, &direction);
         let expected = vec![
-            (origin.0+1, origin.1),
-            (origin.0+2, origin.1),
-            (origin.0+3, origin.1)
+            Coordinate::new(4, 3),
+            Coordinate::new(5, 3),
+            Coordinate::new(6, 3)
         ];
 
         assert_eq!(result, expected);
@@ -264,13 +232,13 @@ mod tests {
 
     #[test]
     fn test_generate_coordinates_south_east() {
-        let origin: Coordinate = (3, 3);
+        let origin = Coordinate::new(3, 3);
         let direction = Direction::SouthEast;
         let result = generate_coordinates_part1(&origin, &direction);
         let expected = vec![
-            (origin.0+1, origin.1+1),
-            (origin.0+2, origin.1+2),
-            (origin.0+3, origin.1+3)
+            Coordinate::new(4, 4),
+            Coordinate::new(5, 5),
+            Coordinate::new(6, 6)
         ];
 
         assert_eq!(result, expected);
@@ -278,13 +246,13 @@ mod tests {
 
     #[test]
     fn test_generate_coordinates_south() {
-        let origin: Coordinate = (3, 3);
+        let origin = Coordinate::new(3, 3);
         let direction = Direction::South;
         let result = generate_coordinates_part1(&origin, &direction);
         let expected = vec![
-            (origin.0, origin.1+1),
-            (origin.0, origin.1+2),
-            (origin.0, origin.1+3)
+            Coordinate::new(3, 4),
+            Coordinate::new(3, 5),
+            Coordinate::new(3, 6)
         ];
 
         assert_eq!(result, expected);
@@ -292,13 +260,13 @@ mod tests {
 
     #[test]
     fn test_generate_coordinates_south_west() {
-        let origin: Coordinate = (3, 3);
+        let origin = Coordinate::new(3, 3);
         let direction = Direction::SouthWest;
         let result = generate_coordinates_part1(&origin, &direction);
         let expected = vec![
-            (origin.0-1, origin.1+1),
-            (origin.0-2, origin.1+2),
-            (origin.0-3, origin.1+3)
+            Coordinate::new(2, 4),
+            Coordinate::new(1, 5),
+            Coordinate::new(0, 6)
         ];
 
         assert_eq!(result, expected);
@@ -306,13 +274,13 @@ mod tests {
 
     #[test]
     fn test_generate_coordinates_west() {
-        let origin: Coordinate = (3, 3);
+        let origin = Coordinate::new(3, 3);
         let direction = Direction::West;
         let result = generate_coordinates_part1(&origin, &direction);
         let expected = vec![
-            (origin.0-1, origin.1),
-            (origin.0-2, origin.1),
-            (origin.0-3, origin.1)
+            Coordinate::new(2, 3),
+            Coordinate::new(1, 3),
+            Coordinate::new(0, 3)
         ];
 
         assert_eq!(result, expected);
@@ -320,18 +288,27 @@ mod tests {
 
     #[test]
     fn test_generate_coordinates_north_west() {
-        let origin: Coordinate = (3, 3);
+        let origin = Coordinate::new(3, 3);
         let direction = Direction::NorthWest;
         let result = generate_coordinates_part1(&origin, &direction);
         let expected = vec![
-            (origin.0-1, origin.1-1),
-            (origin.0-2, origin.1-2),
-            (origin.0-3, origin.1-3)
+            Coordinate::new(2, 2),
+            Coordinate::new(1, 1),
+            Coordinate::new(0, 0)
         ];
 
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_generate_coordinates_off_the_edge() {
+        let origin = Coordinate::new(1, 3);
+        let result = generate_coordinates_part1(&origin, &Direction::NorthWest);
+
+        assert_eq!(result, vec![Coordinate::new(0, 2)]);
+        assert_eq!(generate_coordinate_pairs_part2(&Coordinate::new(0, 2)), None);
+    }
+
     #[test]
     fn test_finds_xmas_down() {
         let input = concat!(
@@ -341,7 +318,7 @@ mod tests {
         "S...\n"
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_xmas();
 
         assert_eq!(xmases, 1);
@@ -356,7 +333,7 @@ mod tests {
         "X...\n"
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_xmas();
 
         assert_eq!(xmases, 1);
@@ -371,7 +348,7 @@ mod tests {
         "....\n"
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_xmas();
 
         assert_eq!(xmases, 1);
@@ -386,7 +363,7 @@ mod tests {
         "....\n"
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_xmas();
 
         assert_eq!(xmases, 1);
@@ -401,7 +378,7 @@ mod tests {
         "...S\n"
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_xmas();
 
         assert_eq!(xmases, 1);
@@ -416,7 +393,7 @@ mod tests {
         "S...\n"
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_xmas();
 
         assert_eq!(xmases, 1);
@@ -431,7 +408,7 @@ mod tests {
             "...X\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_xmas();
 
         assert_eq!(xmases, 1);
@@ -446,7 +423,7 @@ mod tests {
             "X...\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_xmas();
 
         assert_eq!(xmases, 1);
@@ -461,7 +438,7 @@ mod tests {
         "XMAS\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_xmas();
 
         assert_eq!(xmases, 3);
@@ -482,7 +459,7 @@ mod tests {
         "..........\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_x_mas_part2();
 
         assert_eq!(xmases, 9);
@@ -496,7 +473,7 @@ mod tests {
         ".S.\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_x_mas_part2();
 
         assert_eq!(xmases, 0);
@@ -510,7 +487,7 @@ mod tests {
         ".M.\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_x_mas_part2();
 
         assert_eq!(xmases, 0);
@@ -524,7 +501,7 @@ mod tests {
         ".S.\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_x_mas_part2();
 
         assert_eq!(xmases, 0);
@@ -539,7 +516,7 @@ mod tests {
         ".M.\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_x_mas_part2();
 
         assert_eq!(xmases, 0);
@@ -553,7 +530,7 @@ mod tests {
         "MSS\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_x_mas_part2();
 
         assert_eq!(xmases, 1);
@@ -567,7 +544,7 @@ mod tests {
         "M.S\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_x_mas_part2();
 
         assert_eq!(xmases, 1);
@@ -581,7 +558,7 @@ mod tests {
         "M.S\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_x_mas_part2();
 
         assert_eq!(xmases, 0);
@@ -595,7 +572,7 @@ mod tests {
         "S.M\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_x_mas_part2();
 
         assert_eq!(xmases, 1);
@@ -609,7 +586,7 @@ mod tests {
         "M.S\n",
         );
 
-        let d4 = Day04::new(&input);
+        let d4 = Day04::new(input).unwrap();
         let xmases = d4.how_many_x_mas_part2();
 
         assert_eq!(xmases, 1);
@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use common::{parse_number, Answer, ParseError, Solution, SolutionError};
 
 #[derive(Debug, PartialEq, Eq)]
 enum RuleOrder {
@@ -8,73 +9,103 @@ enum RuleOrder {
     Unknown
 }
 
+/// For every page, how each page it has a rule with should be ordered relative to it.
+type Rules = HashMap<i32, HashMap<i32, RuleOrder>>;
 
+pub struct Day05;
 
-pub fn part1() -> i32 {
-    solve_part1(include_str!("../input.txt"))
-}
+impl Solution for Day05 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        parse_input(input).map(|_| ())
+    }
 
-pub fn part2() -> i32 {
-    solve_part2(include_str!("../input.txt"))
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input)?.into())
+    }
+
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input)?.into())
+    }
 }
 
-fn solve_part1(data: &str) -> i32 {
-    let content: Vec<&str> = data.split("\n\n").collect();
-    let rules = parse_rules(content[0]);
+pub fn solve_part1(data: &str) -> Result<i32, ParseError> {
+    let (rules, updates) = parse_input(data)?;
 
     let mut middles = 0;
 
-    for line in content[1].lines() {
-        let print = parse_print(line);
+    for update in updates {
+        let print = parse_print(&update);
         if check_print(&print, &rules) {
-            middles += get_middle(line);
+            middles += get_middle(&update);
         }
     }
 
-    middles
+    Ok(middles)
 }
 
-fn solve_part2(data: &str) -> i32 {
-    let content: Vec<&str> = data.split("\n\n").collect();
-    let rules = parse_rules(content[0]);
+pub fn solve_part2(data: &str) -> Result<i32, ParseError> {
+    let (rules, updates) = parse_input(data)?;
 
     let mut middles = 0;
 
-    for line in content[1].lines() {
-        let print = parse_print(line);
+    for update in updates {
+        let print = parse_print(&update);
         if !check_print(&print, &rules) {
-            let fixed = fix_print(line, &rules);
-            middles += get_middle(fixed.as_str());
+            let fixed = fix_print(&update, &rules);
+            middles += get_middle(&fixed);
         }
     }
 
-    middles
+    Ok(middles)
 }
 
-fn parse_rules(data: &str) -> HashMap<i32, HashMap<i32, RuleOrder>> {
-    let mut rules:HashMap<i32, HashMap<i32, RuleOrder>> = HashMap::new();
+/// Splits the input into the ordering rules and the list of updates, which are separated by an
+/// empty line.
+fn parse_input(data: &str) -> Result<(Rules, Vec<Vec<i32>>), ParseError> {
+    let (rules_section, updates_section) = data.split_once("\n\n")
+        .ok_or(ParseError::new("expected an empty line between the rules and the updates"))?;
 
-    for line in data.lines() {
-        let split: Vec<&str> = line.split("|").collect();
-        let before = split[0].parse().unwrap();
-        let after = split[1].parse().unwrap();
+    let rules = parse_rules(rules_section)?;
+
+    // the updates start after the rules and the empty line.
+    let first_update = rules_section.lines().count() + 2;
+    let mut updates = Vec::new();
+
+    for (i, line) in updates_section.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+
+        updates.push(parse_into_numbers(first_update + i, line)?);
+    }
+
+    Ok((rules, updates))
+}
+
+fn parse_rules(data: &str) -> Result<Rules, ParseError> {
+    let mut rules: Rules = HashMap::new();
+
+    for (i, line) in data.lines().enumerate() {
+        let (before, after) = line.split_once("|")
+            .ok_or(ParseError::at(i + 1, 1, format!("expected a rule like 47|53, found '{}'", line)))?;
+        let before = parse_number(i + 1, line, before)?;
+        let after = parse_number(i + 1, line, after)?;
 
         rules.entry(before).or_default().insert(after, RuleOrder::After);
         rules.entry(after).or_default().insert(before, RuleOrder::Before);
     }
 
-    rules
+    Ok(rules)
 }
 
-fn parse_into_numbers(data: &str) -> Vec<i32> {
-    data.split(",").map(|part| -> i32 {
-        part.parse().unwrap()
-    }).collect::<Vec<i32>>()
+fn parse_into_numbers(line_number: usize, data: &str) -> Result<Vec<i32>, ParseError> {
+    data.split(",")
+        .map(|part| parse_number(line_number, data, part))
+        .collect::<Result<Vec<i32>, ParseError>>()
 }
 
-fn parse_print(data: &str) -> HashMap<i32, HashMap<i32, RuleOrder>> {
+fn parse_print(parts: &[i32]) -> HashMap<i32, HashMap<i32, RuleOrder>> {
     let mut local_hm: HashMap<i32, HashMap<i32, RuleOrder>> = HashMap::new();
-    let parts = parse_into_numbers(data);
 
     for (current, number) in parts.iter().enumerate() {
         for (other_index, _) in parts.iter().enumerate() {
@@ -97,9 +128,7 @@ fn parse_print(data: &str) -> HashMap<i32, HashMap<i32, RuleOrder>> {
     local_hm
 }
 
-fn get_middle(data: &str) -> i32 {
-    let parts = parse_into_numbers(data);
-
+fn get_middle(parts: &[i32]) -> i32 {
     parts[(parts.len()-1)/2]
 }
 
@@ -122,17 +151,16 @@ fn check_print(print: &HashMap<i32, HashMap<i32, RuleOrder>>, rules: &HashMap<i3
     true
 }
 
-fn fix_print(data: &str, rules: &HashMap<i32, HashMap<i32, RuleOrder>>) -> String {
-    let nums = parse_into_numbers(data);
+fn fix_print(nums: &[i32], rules: &HashMap<i32, HashMap<i32, RuleOrder>>) -> Vec<i32> {
 
     let empty: HashMap<i32, RuleOrder> = HashMap::new();
 
     let mut weights: HashMap<i32, Vec<&RuleOrder>> = HashMap::new();
 
     // let's see what the relations of all numbers in the print to all other numbers
-    for first in nums.clone() {
+    for &first in nums {
         weights.insert(first, Vec::new());
-        for second in nums.clone() {
+        for &second in nums {
             if first == second {
                 continue
             }
@@ -162,14 +190,12 @@ fn fix_print(data: &str, rules: &HashMap<i32, HashMap<i32, RuleOrder>>) -> Strin
 
     k.sort();
 
-    let mut sorted:Vec<&i32> = Vec::new();
+    let mut sorted:Vec<i32> = Vec::new();
     for key in k {
-        sorted.push(orders.get(key).unwrap());
+        sorted.push(*orders.get(key).unwrap());
     }
 
-    let s = sorted.iter().map(|x| format!("{}", x)).collect::<Vec<String>>().join(",");
-
-    s
+    sorted
 }
 
 #[cfg(test)]
@@ -178,7 +204,7 @@ mod tests {
 
     #[test]
     fn test_parse_rules_single() {
-        let hm = parse_rules("31|42");
+        let hm = parse_rules("31|42").unwrap();
         let expected: HashMap<i32, HashMap<i32, RuleOrder>> = HashMap::from([
             (31, HashMap::from([(42, RuleOrder::After)])),
             (42, HashMap::from([(31, RuleOrder::Before)]))
@@ -189,7 +215,7 @@ mod tests {
 
     #[test]
     fn test_parse_rules_multiline_no_overlap() {
-        let hm = parse_rules("31|42\n99|11");
+        let hm = parse_rules("31|42\n99|11").unwrap();
         let expected: HashMap<i32, HashMap<i32, RuleOrder>> = HashMap::from([
             (31, HashMap::from([(42, RuleOrder::After)])),
             (99, HashMap::from([(11, RuleOrder::After)])),
@@ -202,7 +228,7 @@ mod tests {
 
     #[test]
     fn test_parse_rules_multiline_overlaps() {
-        let hm = parse_rules("31|42\n99|31\n31|11");
+        let hm = parse_rules("31|42\n99|31\n31|11").unwrap();
         let expected: HashMap<i32, HashMap<i32, RuleOrder>> = HashMap::from([
             (31, HashMap::from([
                 (42, RuleOrder::After),
@@ -225,7 +251,7 @@ mod tests {
 
     #[test]
     fn test_parse_prints() {
-        let input = "1,2,3,4";
+        let input = [1, 2, 3, 4];
 
         let expected: HashMap<i32, HashMap<i32, RuleOrder>> = HashMap::from([
             (1, HashMap::from([
@@ -250,33 +276,33 @@ mod tests {
             ])),
         ]);
 
-        let parsed = parse_print(input);
+        let parsed = parse_print(&input);
 
         assert_eq!(parsed, expected);
     }
 
     #[test]
     fn test_check_print_valid() {
-        let rules = parse_rules("1|2\n2|4");
-        let print = parse_print("1,2,3,4");
+        let rules = parse_rules("1|2\n2|4").unwrap();
+        let print = parse_print(&[1, 2, 3, 4]);
         let result = check_print(&print, &rules);
 
-        assert_eq!(result, true);
+        assert!(result);
     }
 
     #[test]
     fn test_check_print_invalid() {
-        let rules = parse_rules("1|2\n2|4");
-        let print = parse_print("4,2,1,3");
+        let rules = parse_rules("1|2\n2|4").unwrap();
+        let print = parse_print(&[4, 2, 1, 3]);
         let result = check_print(&print, &rules);
 
-        assert_eq!(result, false);
+        assert!(!result);
     }
 
     #[test]
     fn test_get_middle() {
-        let input = "1,2,3,4,5";
-        let result = get_middle(input);
+        let input = [1, 2, 3, 4, 5];
+        let result = get_middle(&input);
 
         assert_eq!(result, 3);
     }
@@ -311,7 +337,23 @@ mod tests {
 
         let solution = solve_part1(input);
 
-        assert_eq!(143, solution);
+        assert_eq!(Ok(143), solution);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_rules("47|53\n97-13").err(),
+            Some(ParseError::at(2, 1, "expected a rule like 47|53, found '97-13'"))
+        );
+        assert_eq!(
+            solve_part1("47|53\n97|13\n\n75,47\n97,6l,53").err(),
+            Some(ParseError::at(5, 4, "expected a number, found '6l'"))
+        );
+        assert_eq!(
+            solve_part1("47|53\n75,47").err(),
+            Some(ParseError::new("expected an empty line between the rules and the updates"))
+        );
     }
 
     #[test]
@@ -324,16 +366,16 @@ mod tests {
 2|4
 3|2
 3|4";
-        let print_input = "1,2,3,4,5";
+        let print_input = [1, 2, 3, 4, 5];
 
 
-        let rules = parse_rules(rules_input);
-        let print = parse_print(print_input);
+        let rules = parse_rules(rules_input).unwrap();
+        let print = parse_print(&print_input);
 
-        assert_eq!(check_print(&print, &rules), false);
+        assert!(!check_print(&print, &rules));
 
         // assert_eq!(1, 0);
 
-        assert_eq!(fix_print(print_input, &rules), "1,3,5,2,4");
+        assert_eq!(fix_print(&print_input, &rules), vec![1, 3, 5, 2, 4]);
     }
 }
\ No newline at end of file
@@ -0,0 +1,85 @@
+//! Draws the guard's walk on the map.
+//!
+//!     cargo run --release -p day06 --example render -- day06/example.txt --path --obstructions
+//!     cargo run --release -p day06 --example render -- day06/input.txt --ansi | less -R
+//!     cargo run --release -p day06 --example render -- day06/example.txt --loops
+
+use std::process::ExitCode;
+use day06::{loops, render, render_loop, Marks, Output, RenderOptions};
+
+const USAGE: &str = "\
+Usage: render <INPUT> [--path] [--ansi] [--obstructions | --loops]
+
+Marks every tile the guard was on with an X. --path draws her path with |, - and + instead, --ansi
+colours the map in for a terminal, and --obstructions adds an O everywhere a new obstruction would
+trap her in a loop. --loops draws a map for each of those obstructions with the loop it makes.";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let Some((input, flags)) = args.split_first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let mut options = RenderOptions { marks: Marks::Visited, output: Output::Plain, obstructions: false };
+    let mut each_loop = false;
+    for flag in flags {
+        match flag.as_str() {
+            "--loops" => each_loop = true,
+            "--path" => options.marks = Marks::Path,
+            "--ansi" => options.output = Output::Ansi,
+            "--obstructions" => options.obstructions = true,
+            _ => {
+                eprintln!("{}", USAGE);
+                return ExitCode::from(2);
+            }
+        }
+    }
+
+    let text = match std::fs::read_to_string(input) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Could not read {}: {}", input, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let rendered = match each_loop {
+        true => render_loops(&text, options.output),
+        false => render(&text, &options),
+    };
+
+    match rendered {
+        Ok(map) => {
+            print!("{}", map);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Could not parse {}: {}", input, e.diagnostic(&text));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Every obstruction, where the guard leaves her path because of it, and the loop she ends up in.
+fn render_loops(text: &str, output: Output) -> Result<String, common::ParseError> {
+    let mut s = String::new();
+
+    for report in loops(text)? {
+        let (at, facing) = report.leaves_path_at;
+        let (start, _) = report.cycle[0];
+
+        s += &format!(
+            "Obstruction at {},{}: runs into it at {},{} going {:?}, the loop starts {} steps into the walk at {},{} and is {} steps long\n",
+            report.obstruction.horizontal, report.obstruction.vertical,
+            at.horizontal, at.vertical, facing,
+            report.lead_in.len(),
+            start.horizontal, start.vertical,
+            report.cycle.len(),
+        );
+        s += &render_loop(text, &report, output)?;
+        s.push('\n');
+    }
+
+    Ok(s)
+}
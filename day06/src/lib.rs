@@ -1,13 +1,17 @@
 #![allow(dead_code)]
 
 mod part2;
-mod part1;
+mod render;
+mod rules;
 
 use std::cmp::{PartialEq};
 use std::fmt::{Display, Formatter};
-use std::hash::Hash;
+use common::{Answer, ParseError, Solution, SolutionError};
+use grid::{Coordinate, Direction, Grid};
 
-type Coordinate = (i32, i32);
+pub use part2::{loops, LoopReport};
+pub use render::{render, render_loop, Marks, Output, RenderOptions};
+pub use rules::{Lab, Rules, Turn};
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 enum Tile {
@@ -19,7 +23,9 @@ enum Tile {
     GuardRight,
     Visited,
     Outside,
-    Paradox
+    Paradox,
+    /// One end of a portal, the digit it's drawn with.
+    Portal(u8),
 }
 
 impl Display for Tile {
@@ -34,109 +40,89 @@ impl Display for Tile {
             Tile::Visited => write!(f, "X"),
             Tile::Outside => write!(f, "~"),
             Tile::Paradox => write!(f, "O"),
+            Tile::Portal(n) => write!(f, "{}", n),
         }
     }
 }
 
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-enum Direction {
-    Up,
-    Left,
-    Down,
-    Right,
+fn parse_char_to_tile(c: char) -> Option<Tile> {
+    match c {
+        '#' => Some(Tile::Obstacle),
+        '.' => Some(Tile::Floor),
+        '^' => Some(Tile::GuardUp),
+        '>' => Some(Tile::GuardRight),
+        '<' => Some(Tile::GuardLeft),
+        'v' => Some(Tile::GuardDown),
+        'X' => Some(Tile::Visited),
+        _ => None,
+    }
 }
 
-impl Direction {
-    fn turn90(&self) -> Self {
-        match *self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
-    }
+fn parse_map(data: &str) -> Result<Grid<Tile>, ParseError> {
+    Grid::parse(data, parse_char_to_tile)
 }
 
-struct OutOfMapError;
+/// Where the guard starts, and which way she's facing.
+fn find_guard(map: &Grid<Tile>) -> Result<(Coordinate, Direction), ParseError> {
+    map.iter()
+        .find_map(|(coordinate, tile)| {
+            let direction = match tile {
+                Tile::GuardUp => Direction::Up,
+                Tile::GuardRight => Direction::Right,
+                Tile::GuardDown => Direction::Down,
+                Tile::GuardLeft => Direction::Left,
+                _ => return None,
+            };
+
+            Some((coordinate, direction))
+        })
+        .ok_or(ParseError::new("there is no guard on the map"))
+}
 
-impl Display for OutOfMapError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Guard left the map")
+pub struct Day06;
+
+impl Solution for Day06 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        find_guard(&parse_map(input)?).map(|_| ())
     }
-}
 
-fn next_coord(coordinate: &Coordinate, dir: &Direction) -> Coordinate {
-    match dir {
-        Direction::Up => {
-            (coordinate.0-1, coordinate.1)
-        }
-        Direction::Left => {
-            (coordinate.0, coordinate.1-1)
-        }
-        Direction::Down => {
-            (coordinate.0+1, coordinate.1)
-        }
-        Direction::Right => {
-            (coordinate.0, coordinate.1+1)
-        }
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input)?.into())
     }
-}
 
-fn parse_char_to_tile(c: char) -> Tile {
-    match c.to_string().as_str() {
-        "#" => Tile::Obstacle,
-        "." => Tile::Floor,
-        "^" => Tile::GuardUp,
-        ">" => Tile::GuardRight,
-        "<" => Tile::GuardLeft,
-        "v" => Tile::GuardDown,
-        "X" => Tile::Visited,
-        _ => {
-            panic!("Unknown character: {}", c);
-        }
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input)?.into())
     }
 }
 
-pub fn solve_part1_example() -> i32 {
-    let data: &str = concat!(
-        "....#.....\n",
-        ".........#\n",
-        "..........\n",
-        "..#.......\n",
-        ".......#..\n",
-        "..........\n",
-        ".#..^.....\n",
-        "........#.\n",
-        "#.........\n",
-        "......#...\n"
-    );
-
-    part1::part1(data)
+/// The guard walks by the puzzle's rules, and a map she never leaves counts the tiles she goes
+/// round rather than walking forever.
+pub fn solve_part1(input: &str) -> Result<i32, ParseError> {
+    Ok(Lab::parse(input, Rules::PUZZLE)?.visited().len() as i32)
 }
 
-pub fn solve_part1() -> i32 {
-    part1::part1(include_str!("../input.txt"))
+pub fn solve_part2(input: &str) -> Result<i32, ParseError> {
+    part2::part2(input)
 }
 
-pub fn solve_part2_example() -> i32 {
-    let data: &str = concat!(
-        "....#.....\n",
-        ".........#\n",
-        "..........\n",
-        "..#.......\n",
-        ".......#..\n",
-        "..........\n",
-        ".#..^.....\n",
-        "........#.\n",
-        "#.........\n",
-        "......#...\n"
-    );
-
-    part2::part2(data)
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            solve_part1("..#.\n.^.#\n#.@.\n").err(),
+            Some(ParseError::at(3, 3, "unexpected character '@' on the map"))
+        );
+        assert_eq!(solve_part2("..#.\n...#\n").err(), Some(ParseError::new("there is no guard on the map")));
+    }
 
-pub fn solve_part2() -> i32 {
-    part2::part2(include_str!("../input.txt"))
+    #[test]
+    fn test_trapped_guard() {
+        // she turns back and forth between the walls forever
+        assert_eq!(solve_part1("####\n#^.#\n####\n"), Ok(2));
+        assert_eq!(solve_part1(include_str!("../example.txt")), Ok(41));
+    }
 }
-
@@ -1,312 +1,261 @@
-use std::collections::{HashMap, HashSet};
-use std::fmt::{Display, Formatter};
-use crate::{next_coord, parse_char_to_tile, Coordinate, Direction, Tile, OutOfMapError};
-
-struct LoopError;
-
-impl Display for LoopError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Guard entered a loop")
+use std::collections::HashSet;
+use std::thread;
+use common::ParseError;
+use grid::{Coordinate, Direction, Grid};
+use crate::{Lab, Rules, Tile};
+
+/// Stands in for the tile the guard stops on when nothing stops her before the edge of the map.
+const LEAVES: u32 = u32::MAX;
+
+fn index_of(direction: Direction) -> usize {
+    match direction {
+        Direction::Up => 0,
+        Direction::Right => 1,
+        Direction::Down => 2,
+        Direction::Left => 3,
     }
 }
 
-enum WalkError {
-    OutOfMapError,
-    LoopError
+/// The lab as a flat list of tiles, row by row, and for every tile and direction, the tile the
+/// guard stops on in front of the next obstacle if she walks straight ahead from there. That way
+/// she can be moved from one obstacle to the next in a single lookup rather than tile by tile.
+struct JumpTable {
+    width: usize,
+    obstacles: Vec<bool>,
+    jumps: Vec<[u32; 4]>,
 }
 
+impl JumpTable {
+    fn new(map: &Grid<Tile>) -> JumpTable {
+        let width = map.width();
+        let obstacles = map.iter().map(|(_, t)| *t == Tile::Obstacle).collect::<Vec<bool>>();
+        let mut jumps = vec![[LEAVES; 4]; obstacles.len()];
+
+        // the tile ahead has to be worked out before the one behind it, so going up or left starts
+        // at the top left, going down or right starts at the bottom right
+        for direction in Direction::ALL {
+            let d = index_of(direction);
+            let order: Box<dyn Iterator<Item = usize>> = match direction {
+                Direction::Up | Direction::Left => Box::new(0..obstacles.len()),
+                Direction::Down | Direction::Right => Box::new((0..obstacles.len()).rev()),
+            };
 
-#[derive(Debug)]
-struct Candidate {
-    place_obstacle_at: Coordinate,
-    while_going: Direction,
-    at_coordinate: Coordinate
-}
-
-struct P2Day06 {
-    map: HashMap<Coordinate, Tile>,
-    visited: HashSet<Coordinate>,
-    visited_with_direction: HashMap<(Coordinate, Direction), Tile>,
-    visited_with_direction_vec: Vec<(Coordinate, Direction)>, // so we have this in order
-    current_coordinate: Coordinate,
-    starting_coordinate: Coordinate,
-    direction: Direction,
-    logs: Vec<String>,
-}
+            for i in order {
+                jumps[i][d] = match map.step(Coordinate::new(i % width, i / width), direction) {
+                    None => LEAVES,
+                    Some(ahead) => {
+                        let ahead = ahead.vertical * width + ahead.horizontal;
 
-impl P2Day06 {
-    fn new(data: &str) -> P2Day06 {
-        let mut map: HashMap<Coordinate, Tile> = HashMap::new();
-        let mut starting_coordinate: Coordinate = (0, 0);
-        let mut starting_direction: Direction = Direction::Up;
-
-        for (height, line) in data.lines().enumerate() {
-            for (width, character) in line.chars().enumerate() {
-                let coord = (height as i32, width as i32);
-                let tile = parse_char_to_tile(character);
-
-                match tile {
-                    Tile::GuardDown => {
-                        starting_coordinate = coord;
-                        starting_direction = Direction::Down;
-                    }
-                    Tile::GuardLeft => {
-                        starting_coordinate = coord;
-                        starting_direction = Direction::Left;
-                    }
-                    Tile::GuardRight => {
-                        starting_coordinate = coord;
-                        starting_direction = Direction::Right;
+                        match obstacles[ahead] {
+                            true => i as u32,
+                            false => jumps[ahead][d],
+                        }
                     }
-                    Tile::GuardUp => {
-                        starting_coordinate = coord;
-                        starting_direction = Direction::Up;
-                    }
-                    _ => {}
-                }
-
-                map.insert(coord, tile);
+                };
             }
         }
 
-        P2Day06 {
-            map,
-            current_coordinate: starting_coordinate,
-            starting_coordinate,
-            direction: starting_direction,
-            visited: HashSet::new(),
-            visited_with_direction: HashMap::from([(
-                (starting_coordinate, starting_direction),
-                Tile::Visited,
-            )]),
-            visited_with_direction_vec: vec![(starting_coordinate, starting_direction)],
-            logs: vec![format!(
-                "Starting at coordinate {:?} with guard facing {:?}",
-                starting_coordinate, starting_direction
-            )],
-        }
+        JumpTable { width, obstacles, jumps }
     }
 
-    fn next(&mut self) -> Result<(), OutOfMapError> {
-        let new_coordinate: Coordinate = next_coord(&self.current_coordinate, &self.direction);
-
-        // Record where we've just been
-        self.visited_with_direction.insert((self.current_coordinate, self.direction), Tile::Visited);
-        self.visited_with_direction_vec.push((self.current_coordinate, self.direction));
-        self.visited.insert(self.current_coordinate);
-
-        let new_tile = self.map.get(&new_coordinate).unwrap_or(&Tile::Outside);
-        match *new_tile {
-            Tile::Outside => {
-                return Err(OutOfMapError);
-            }
-            Tile::Obstacle => {
-                // rotate 90deg to the right, stay in place
-                self.direction = self.direction.turn90();
-
-                self.logs
-                    .push(format!("Obstacle at {:?}, turning right", new_coordinate))
-            }
-            _ => {
-                // move to that tile, do not change orientation
-                self.current_coordinate = new_coordinate;
-                self.logs.push(format!(
-                    "Floor / Visited / Guard at {:?}, going forward, marking visited",
-                    new_coordinate
-                ))
-            }
-        }
-
-        Ok(())
-    }
-
-    fn walk(&mut self) {
-        loop {
-            if self.next().is_err() {
-                break;
-            }
+    /// How many steps it takes to get from `from` to `to` walking in `direction`, if `to` is
+    /// straight ahead.
+    fn steps_between(&self, from: usize, to: usize, direction: Direction) -> Option<usize> {
+        let (fh, fv) = (from % self.width, from / self.width);
+        let (th, tv) = (to % self.width, to / self.width);
+
+        match direction {
+            Direction::Up => (fh == th && tv < fv).then(|| fv - tv),
+            Direction::Down => (fh == th && tv > fv).then(|| tv - fv),
+            Direction::Left => (fv == tv && th < fh).then(|| fh - th),
+            Direction::Right => (fv == tv && th > fh).then(|| th - fh),
         }
     }
 
-    fn next_check_loop(&mut self) -> Result<(), WalkError> {
-        let nc = next_coord(&self.current_coordinate, &self.direction);
-        let new_tile = self.map.get(&nc).unwrap_or(&Tile::Outside);
+    /// Where the guard stops walking from `from` in `direction`, with an extra obstacle on the
+    /// `extra` tile. `None` if she walks off the map.
+    fn jump(&self, from: usize, direction: Direction, extra: usize) -> Option<usize> {
+        let to = self.jumps[from][index_of(direction)];
 
-        self.visited_with_direction.insert((self.current_coordinate, self.direction), Tile::Visited);
-        self.visited_with_direction_vec.push((self.current_coordinate, self.direction));
+        // the extra obstacle only matters if it's in the way before she'd stop anyway
+        if let Some(steps) = self.steps_between(from, extra, direction) {
+            let stop = match to {
+                LEAVES => usize::MAX,
+                to => self.steps_between(from, to as usize, direction).unwrap_or(0),
+            };
 
-        match *new_tile {
-            Tile::Outside => {
-                return Err(WalkError::OutOfMapError)
-            }
-            Tile::Obstacle => {
-                // rotate 90deg to the right, stay in place
-                self.direction = self.direction.turn90();
+            if steps <= stop {
+                let (h, v) = direction.delta();
+                let back = (steps as isize - 1) * (h + v * self.width as isize);
 
-                self.logs
-                    .push(format!("Obstacle at {:?}, turning right", nc))
-            }
-            _ => {
-                // first let's see whether we've been where we want to move
-                if self.visited_with_direction.contains_key(&(nc, self.direction)) {
-                    self.logs.push(format!(
-                        "We have already visited the tile at {:?} going {:?}", nc, self.direction)
-                    );
-                    // this is what we want
-                    return Err(WalkError::LoopError)
-                }
-
-                // move to that tile, do not change orientation, mark it as visited
-                self.current_coordinate = nc;
-                self.logs.push(format!(
-                    "Floor / Visited / Guard at {:?}, going forward, marking visited",
-                    nc
-                ))
+                return Some((from as isize + back) as usize);
             }
         }
 
-        Ok(())
+        (to != LEAVES).then_some(to as usize)
     }
 
-    fn walk_loop(&mut self) -> Result<(), LoopError> {
+    /// Whether the guard ends up going round in circles walking from `from` in `direction`, with
+    /// an extra obstacle on the `extra` tile. `seen` is a stamp per tile and direction, any that's
+    /// `stamp` is somewhere she's already been on this walk.
+    fn loops(&self, mut from: usize, mut direction: Direction, extra: usize, seen: &mut [u32], stamp: u32) -> bool {
         loop {
-            if let Err(e) = self.next_check_loop() {
-                return match e {
-                    WalkError::LoopError => {
-                        Err(LoopError)
-                    },
-                    WalkError::OutOfMapError => {
-                        Ok(())
-                    }
-                }
+            let state = from * 4 + index_of(direction);
+            if seen[state] == stamp {
+                return true;
             }
-        }
-    }
+            seen[state] = stamp;
 
-    fn what_happened(&self) -> String {
-        self.logs.join("\n")
-    }
-
-    fn does_it_hit_obstacle(&self, coord: Coordinate, dir: Direction) ->  bool {
-        let mut new_coord = coord;
-
-        loop {
-            new_coord = next_coord(&new_coord, &dir);
-            let nt = self.map.get(&new_coord).unwrap_or(&Tile::Outside);
-
-            match nt {
-                Tile::Outside => {
-                    return false
-                },
-                Tile::Obstacle => {
-                    return true
-                }
-                _ => {
-                    // do nothing, this should always break because going the same direction will
-                    // always either hit an obstacle, or at some point we're going to go off the map
+            match self.jump(from, direction, extra) {
+                None => return false,
+                Some(to) => {
+                    from = to;
+                    direction = direction.turn_right();
                 }
             }
         }
     }
 }
 
-fn should_skip_next_tile(day: &P2Day06, coordinate: &Coordinate) -> bool {
-    let next_tile = day.map.get(coordinate).unwrap_or(&Tile::Outside);
-    if *next_tile == Tile::Outside
-        || *next_tile == Tile::Obstacle
-        || *next_tile == Tile::GuardUp {
-        // if the next tile is an obstacle already, or outside, or a guard, then I won't
-        // be able to place a paradox object here.
-        return true;
-    }
-
-    false
+/// An obstruction to try, and where the guard is just before she'd first walk into it.
+#[derive(Debug, Copy, Clone)]
+struct Candidate {
+    place_obstacle_at: usize,
+    at: usize,
+    while_going: Direction,
 }
 
+/// Every tile the guard walks onto after the start, in the order she first gets there, together with
+/// where she was and which way she was going when she did.
+fn candidates(lab: &Lab, start: Coordinate, direction: Direction) -> Vec<Candidate> {
+    let width = lab.map().width();
+    let mut first_visit = vec![false; width * lab.map().height()];
+    let mut candidates = Vec::new();
 
-pub fn part2(data: &str) -> i32 {
-    let mut day = P2Day06::new(data);
-    // walk the first time, as usual
-    day.walk();
+    first_visit[start.vertical * width + start.horizontal] = true;
 
-    let mut candidates : Vec<Candidate> = Vec::new();
-    let mut checked: HashSet<Coordinate> = HashSet::new();
-
-    for (c, d) in day.visited_with_direction_vec.iter() {
-        // store current coordinate in a new "visited" vec that we're growing. This is used to check
-        // whether we'd try to put an obstacle onto a path we have been on which would mean the
-        // guard would not reach the floor she's on at the moment because her path would have been
-        // broken earlier.
-        checked.insert(*c);
-
-        let nc = next_coord(c, d);
-        if should_skip_next_tile(&day, &nc) {
+    // the guard leaves the map in every input, but a map where she doesn't shouldn't hang
+    for pair in lab.walk(start, direction, None).states.windows(2) {
+        let [(at, going), (ahead, _)] = [pair[0], pair[1]];
+        if ahead == at {
             continue;
         }
 
-        if checked.contains(&nc) {
-            continue
-        }
-
-        if day.does_it_hit_obstacle(*c, d.turn90()) {
-            let cand = Candidate{
-                place_obstacle_at: nc, // new obstacle is here
-                while_going: *d, // starting direction is here
-                at_coordinate: *c, // starting coordinate is here
-            };
-            candidates.push(cand);
+        let i = ahead.vertical * width + ahead.horizontal;
+        if !first_visit[i] {
+            first_visit[i] = true;
+            candidates.push(Candidate {
+                place_obstacle_at: i,
+                at: at.vertical * width + at.horizontal,
+                while_going: going,
+            });
         }
     }
 
-    let mut unique_placements:HashSet<Coordinate> = HashSet::new();
-
-    for candidate in candidates {
-        let mut m = day.map.clone();
+    candidates
+}
 
-        match m.insert(candidate.place_obstacle_at, Tile::Obstacle) {
-            None => print!("replacing floor to obstacle at {:?} failed", candidate.place_obstacle_at),
-            Some(old) => {
-                if old != Tile::Floor {
-                    println!("something very wrong happened, we're updating the tile to an obstacle, but the old one is a {:?}", old)
-                }
-            }
-        }
+/// Where the guard is, and which way she's facing.
+pub type State = (Coordinate, Direction);
+
+/// The loop a new obstruction traps the guard in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopReport {
+    pub obstruction: Coordinate,
+    /// Where the guard was and which way she was facing when she first ran into the obstruction,
+    /// which is where she leaves the path she'd take without it. This is in `cycle` if the loop
+    /// brings her back to the obstruction the same way, and in `lead_in` if it goes round somewhere
+    /// else.
+    pub leaves_path_at: State,
+    /// Every state of her walk from where she starts, up to the first one she's in for a second
+    /// time.
+    pub lead_in: Vec<State>,
+    /// Every place and way she faces going round the loop once, starting with the first state of
+    /// her walk she's in for a second time. A turn on the spot is a place of its own.
+    pub cycle: Vec<State>,
+}
 
-        // create a copy candidate of the original.
-        let mut candidate_day = P2Day06 {
-            map: m,
-            visited_with_direction: HashMap::new(),
-            visited_with_direction_vec: Vec::new(),
-            visited: HashSet::new(),
-            current_coordinate: candidate.at_coordinate,
-            starting_coordinate: candidate.at_coordinate,
-            direction: candidate.while_going,
-            logs: vec![],
-        };
-
-        if candidate_day.walk_loop().is_err() {
-            unique_placements.insert(candidate.place_obstacle_at);
-        }
-    }
+/// Walks the guard from `from` with an extra obstacle, and splits where she goes into the bit
+/// before the loop and the loop itself. `None` if she leaves the map after all.
+fn find_cycle(lab: &Lab, from: State, extra: Coordinate) -> Option<(Vec<State>, Vec<State>)> {
+    let walk = lab.walk(from.0, from.1, Some(extra));
+    let mut states = walk.states;
+    let cycle = states.split_off(walk.loops_back_to?);
 
-    unique_placements.remove(&(day.starting_coordinate.0, day.starting_coordinate.1));
+    Some((states, cycle))
+}
 
-    unique_placements.len() as i32
+/// Every obstruction part 2 finds, with the loop it traps the guard in, top to bottom and left to
+/// right.
+pub fn loops(data: &str) -> Result<Vec<LoopReport>, ParseError> {
+    let lab = Lab::parse(data, Rules::PUZZLE)?;
+    let (start, direction) = lab.guard();
+    let placements = placements(data)?;
+    let width = lab.map().width();
+
+    let mut reports = candidates(&lab, start, direction)
+        .into_iter()
+        .map(|c| (Coordinate::new(c.place_obstacle_at % width, c.place_obstacle_at / width), c))
+        .filter(|(obstruction, _)| placements.contains(obstruction))
+        .filter_map(|(obstruction, c)| {
+            let leaves_path_at = (Coordinate::new(c.at % width, c.at / width), c.while_going);
+            let (lead_in, cycle) = find_cycle(&lab, (start, direction), obstruction)?;
+
+            Some(LoopReport { obstruction, leaves_path_at, lead_in, cycle })
+        })
+        .collect::<Vec<LoopReport>>();
+
+    reports.sort_by_key(|r| (r.obstruction.vertical, r.obstruction.horizontal));
+
+    Ok(reports)
 }
 
-fn direction_to_guard(dir: &Direction) -> Tile {
-    match dir {
-        Direction::Up => {Tile::GuardUp}
-        Direction::Left => {Tile::GuardLeft}
-        Direction::Down => {Tile::GuardDown}
-        Direction::Right => {Tile::GuardRight}
-    }
+pub fn part2(data: &str) -> Result<i32, ParseError> {
+    Ok(placements(data)?.len() as i32)
 }
 
+/// Every tile where a new obstruction would get the guard stuck in a loop. An obstruction can only
+/// change anything on a tile she walks onto, and it changes nothing before she first gets there,
+/// so each of those is tried starting from just in front of it. The tries are shared out between
+/// as many threads as there are cores.
+pub(crate) fn placements(data: &str) -> Result<HashSet<Coordinate>, ParseError> {
+    let lab = Lab::parse(data, Rules::PUZZLE)?;
+    let (start, direction) = lab.guard();
+
+    let table = JumpTable::new(lab.map());
+    let candidates = candidates(&lab, start, direction);
+
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = candidates.len().div_ceil(threads).max(1);
+
+    let looping: Vec<usize> = thread::scope(|scope| {
+        let handles = candidates.chunks(chunk_size)
+            .map(|chunk| {
+                let table = &table;
+
+                scope.spawn(move || {
+                    let mut seen = vec![0; table.obstacles.len() * 4];
+
+                    chunk.iter()
+                        .enumerate()
+                        .filter(|(i, c)| table.loops(c.at, c.while_going, c.place_obstacle_at, &mut seen, *i as u32 + 1))
+                        .map(|(_, c)| c.place_obstacle_at)
+                        .collect::<Vec<usize>>()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles.into_iter().flat_map(|h| h.join().unwrap_or_else(|e| std::panic::resume_unwind(e))).collect()
+    });
+
+    Ok(looping.into_iter().map(|i| Coordinate::new(i % table.width, i / table.width)).collect())
+}
 
 #[cfg(test)]
 mod tests {
+    use crate::parse_map;
     use super::*;
+
     #[test]
     fn test_day6_loops() {
         let data: &str = concat!(
@@ -316,9 +265,112 @@ mod tests {
         "..#.\n",
         );
 
-        let mut d6 = P2Day06::new(data);
-        let res = d6.walk_loop();
+        let map = parse_map(data).unwrap();
+        let table = JumpTable::new(&map);
+        let mut seen = vec![0; 16 * 4];
+
+        // the obstacle doesn't matter if it's out of the way
+        assert!(table.loops(5, Direction::Up, 15, &mut seen, 1));
+    }
+
+    #[test]
+    fn test_jump() {
+        let map = parse_map(include_str!("../example.txt")).unwrap();
+        let table = JumpTable::new(&map);
+        let at = |h: usize, v: usize| v * 10 + h;
+
+        // the guard walks up to just under the obstacle at 4,0
+        assert_eq!(table.jump(at(4, 6), Direction::Up, at(0, 0)), Some(at(4, 1)));
+        // unless there's one in the way before that
+        assert_eq!(table.jump(at(4, 6), Direction::Up, at(4, 3)), Some(at(4, 4)));
+        // right in front of her, she doesn't move
+        assert_eq!(table.jump(at(4, 6), Direction::Up, at(4, 5)), Some(at(4, 6)));
+        // and off the map, or into the extra obstacle on the way there
+        assert_eq!(table.jump(at(1, 8), Direction::Down, at(0, 0)), None);
+        assert_eq!(table.jump(at(1, 8), Direction::Down, at(1, 9)), Some(at(1, 8)));
+    }
+
+    #[test]
+    fn test_loops() {
+        let reports = loops(include_str!("../example.txt")).unwrap();
+        let obstructions = reports.iter().map(|r| r.obstruction).collect::<HashSet<Coordinate>>();
+
+        assert_eq!(obstructions, placements(include_str!("../example.txt")).unwrap());
+
+        // the first one in the puzzle, right next to where the guard starts. She runs into it going
+        // left, turns up and is right back where she started, so the loop starts at the start.
+        let first = &reports[0];
+        assert_eq!(first.obstruction, Coordinate::new(3, 6));
+        assert_eq!(first.leaves_path_at, (Coordinate::new(4, 6), Direction::Left));
+        assert_eq!(first.cycle[0], (Coordinate::new(4, 6), Direction::Up));
+        assert!(first.lead_in.is_empty());
+        // up to the obstacle at 4,0, right, down to the one at 8,7, then left back to the start
+        assert_eq!(first.cycle.len(), 22);
+
+        // she runs into the one at 6,7 going down, and turns left along the row she's already
+        // walked along once, from the tile she runs into it from
+        let second = &reports[1];
+        assert_eq!(second.obstruction, Coordinate::new(6, 7));
+        assert_eq!(second.leaves_path_at, (Coordinate::new(6, 6), Direction::Down));
+        assert_eq!(second.cycle[0], (Coordinate::new(6, 6), Direction::Left));
+        assert_eq!(second.lead_in.first(), Some(&(Coordinate::new(4, 6), Direction::Up)));
+        assert_eq!(second.lead_in.last(), Some(&(Coordinate::new(7, 6), Direction::Left)));
+        // left, up, right and back down the column she ran into it on
+        assert_eq!(second.cycle.len(), 16);
+
+        for r in reports.iter() {
+            assert!(r.lead_in.contains(&r.leaves_path_at) || r.cycle.contains(&r.leaves_path_at));
+        }
+
+        // each step of the cycle follows from the one before, and the last one leads back to the
+        // first
+        let map = parse_map(include_str!("../example.txt")).unwrap();
+        for r in reports.iter() {
+            for (i, &(c, d)) in r.cycle.iter().enumerate() {
+                let ahead = map.step(c, d).unwrap();
+                let next = match map[ahead] == Tile::Obstacle || ahead == r.obstruction {
+                    true => (c, d.turn_right()),
+                    false => (ahead, d),
+                };
+
+                assert_eq!(next, r.cycle[(i + 1) % r.cycle.len()]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_loop_away_from_the_obstruction() {
+        // an obstruction at 0,1 turns her right along the top of the box the other four obstacles
+        // make, and she goes round that without ever coming back to the obstruction
+        let data = "\
+........
+..#.....
+......#.
+^.......
+.#......
+.....#..
+";
+        let reports = loops(data).unwrap();
+        let report = reports.iter().find(|r| r.obstruction == Coordinate::new(0, 1)).unwrap();
+
+        assert_eq!(report.leaves_path_at, (Coordinate::new(0, 2), Direction::Up));
+        assert!(!report.cycle.contains(&report.leaves_path_at));
+        assert_eq!(report.lead_in[..3], [
+            (Coordinate::new(0, 3), Direction::Up),
+            (Coordinate::new(0, 2), Direction::Up),
+            (Coordinate::new(0, 2), Direction::Right),
+        ]);
+        assert_eq!(report.lead_in.len(), 4);
+        assert_eq!(report.cycle[0], (Coordinate::new(2, 2), Direction::Right));
+        // along to 5,2, down to 5,4, back to 2,4 and up again, with a turn on each corner
+        assert_eq!(report.cycle.len(), 14);
+    }
+
+    #[test]
+    fn test_placements() {
+        let placements = placements(include_str!("../example.txt")).unwrap();
+        let expected = [(3, 6), (6, 7), (7, 7), (1, 8), (3, 8), (7, 9)];
 
-        assert!(res.is_err());
+        assert_eq!(placements, expected.iter().map(|&(h, v)| Coordinate::new(h, v)).collect());
     }
 }
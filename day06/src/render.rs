@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+use common::ParseError;
+use grid::{Coordinate, Direction, Grid};
+use crate::{part2, Lab, Rules, Tile};
+use crate::part2::LoopReport;
+
+/// How the tiles the guard walked over are drawn.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Marks {
+    /// An `X` on every tile she's been on, like the picture in part 1.
+    Visited,
+    /// `|` where she only went up and down, `-` where she only went sideways, and `+` where she
+    /// turned or crossed her own path, like the pictures in part 2.
+    Path,
+}
+
+/// Whether the map is plain text, or coloured in for a terminal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Output {
+    Plain,
+    Ansi,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub marks: Marks,
+    pub output: Output,
+    /// Whether to also work out every place a new obstruction would trap the guard in a loop, as
+    /// part 2 does, and draw an `O` on them.
+    pub obstructions: bool,
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// Which ways the guard has gone across a tile, and whether she turned on it.
+#[derive(Debug, Default, Copy, Clone)]
+struct Crossing {
+    vertical: bool,
+    horizontal: bool,
+    turned: bool,
+}
+
+impl Crossing {
+    fn mark(&self) -> char {
+        match (self.vertical, self.horizontal, self.turned) {
+            (true, false, false) => '|',
+            (false, true, false) => '-',
+            _ => '+',
+        }
+    }
+}
+
+/// How the guard crossed each tile, going through the states in order. If the walk is `closed`
+/// the last state leads back into the first one.
+fn crossings(states: &[(Coordinate, Direction)], closed: bool) -> HashMap<Coordinate, Crossing> {
+    let mut crossings: HashMap<Coordinate, Crossing> = HashMap::new();
+
+    for (i, &(coordinate, direction)) in states.iter().enumerate() {
+        let next = match (states.get(i + 1), closed) {
+            (Some(next), _) => Some(next),
+            (None, true) => states.first(),
+            (None, false) => None,
+        };
+
+        let crossing = crossings.entry(coordinate).or_default();
+        match direction.is_horizontal() {
+            true => crossing.horizontal = true,
+            false => crossing.vertical = true,
+        }
+        if next.is_some_and(|n| n.0 == coordinate) {
+            crossing.turned = true;
+        }
+    }
+
+    crossings
+}
+
+/// Draws the map with the guard's path on it. Her starting tile keeps showing which way she was
+/// facing at the start.
+pub fn render(input: &str, options: &RenderOptions) -> Result<String, ParseError> {
+    let lab = Lab::parse(input, Rules::PUZZLE)?;
+    let (start, direction) = lab.guard();
+    let crossings = crossings(&lab.walk(start, direction, None).states, false);
+    let obstructions = match options.obstructions {
+        true => part2::placements(input)?,
+        false => HashSet::new(),
+    };
+
+    Ok(draw(lab.map(), start, &crossings, &obstructions, options))
+}
+
+/// Draws the map with a single obstruction on it, and the loop it traps the guard in.
+pub fn render_loop(input: &str, report: &LoopReport, output: Output) -> Result<String, ParseError> {
+    let lab = Lab::parse(input, Rules::PUZZLE)?;
+    let (start, _) = lab.guard();
+    let crossings = crossings(&report.cycle, true);
+    let options = RenderOptions { marks: Marks::Path, output, obstructions: true };
+
+    Ok(draw(lab.map(), start, &crossings, &HashSet::from([report.obstruction]), &options))
+}
+
+fn draw(
+    map: &Grid<Tile>,
+    start: Coordinate,
+    crossings: &HashMap<Coordinate, Crossing>,
+    obstructions: &HashSet<Coordinate>,
+    options: &RenderOptions,
+) -> String {
+    let mut s = String::with_capacity((map.width() + 1) * map.height());
+
+    for (c, tile) in map.iter() {
+        if c.horizontal == 0 && c.vertical > 0 {
+            s.push('\n');
+        }
+
+        let (mark, colour) = if obstructions.contains(&c) {
+            (Tile::Paradox.to_string(), "\x1b[1;31m")
+        } else if c == start {
+            (tile.to_string(), "\x1b[1;32m")
+        } else if let Some(crossing) = crossings.get(&c) {
+            let mark = match options.marks {
+                Marks::Visited => Tile::Visited.to_string(),
+                Marks::Path => crossing.mark().to_string(),
+            };
+
+            (mark, "\x1b[33m")
+        } else if *tile == Tile::Obstacle {
+            (tile.to_string(), "\x1b[2m")
+        } else {
+            (tile.to_string(), "")
+        };
+
+        match (options.output, colour) {
+            (Output::Ansi, colour) if !colour.is_empty() => s += &format!("{}{}{}", colour, mark, RESET),
+            _ => s += &mark,
+        }
+    }
+
+    s.push('\n');
+
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    #[test]
+    fn test_render_path() {
+        let options = RenderOptions { marks: Marks::Path, output: Output::Plain, obstructions: true };
+
+        // the picture from the puzzle with all six obstructions on it
+        assert_eq!(render(EXAMPLE, &options).unwrap(), "\
+....#.....
+....+---+#
+....|...|.
+..#.|...|.
+..+-+-+#|.
+..|.|.|.|.
+.#+O^-+-+.
+.+----OO#.
+#O-O--+|..
+......#O..
+");
+        assert_eq!(render(EXAMPLE, &options).unwrap().matches('O').count(), 6);
+    }
+
+    #[test]
+    fn test_render_visited() {
+        let options = RenderOptions { marks: Marks::Visited, output: Output::Plain, obstructions: false };
+        let rendered = render(EXAMPLE, &options).unwrap();
+
+        assert_eq!(rendered.lines().nth(1), Some("....XXXXX#"));
+        // the guard's tile is drawn as the guard, not as visited
+        assert_eq!(rendered.matches('X').count() + 1, 41);
+    }
+
+    #[test]
+    fn test_render_loop() {
+        let reports = part2::loops(EXAMPLE).unwrap();
+
+        assert_eq!(render_loop(EXAMPLE, &reports[0], Output::Plain).unwrap(), "\
+....#.....
+....+---+#
+....|...|.
+..#.|...|.
+....|..#|.
+....|...|.
+.#.O^---+.
+........#.
+#.........
+......#...
+");
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let options = RenderOptions { marks: Marks::Visited, output: Output::Ansi, obstructions: false };
+        let rendered = render("#..\n.^.\n", &options).unwrap();
+
+        assert_eq!(rendered, "\x1b[2m#\x1b[0m\x1b[33mX\x1b[0m.\n.\x1b[1;32m^\x1b[0m.\n");
+    }
+}
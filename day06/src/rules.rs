@@ -0,0 +1,294 @@
+use std::collections::{HashMap, HashSet};
+use common::ParseError;
+use grid::{Coordinate, Direction, Grid};
+use crate::{parse_char_to_tile, Tile};
+
+/// Which way a guard turns when there's an obstacle in front of her.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Turn {
+    Right,
+    Left,
+}
+
+/// How guards walk around the lab. Every guard on the map walks, and the tiles any of them were on
+/// count as visited.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub turn: Turn,
+    /// Whether the map can have portals on it. A portal is a digit, and there have to be exactly
+    /// two of each. Stepping onto one puts the guard on the other one, still facing the same way.
+    pub portals: bool,
+}
+
+impl Rules {
+    /// The rules of the puzzle.
+    pub const PUZZLE: Rules = Rules { turn: Turn::Right, portals: false };
+
+    fn turn(&self, direction: Direction) -> Direction {
+        match self.turn {
+            Turn::Right => direction.turn_right(),
+            Turn::Left => direction.turn_left(),
+        }
+    }
+
+    /// What a character on the map is under these rules.
+    fn tile(&self, c: char) -> Option<Tile> {
+        parse_char_to_tile(c).or(match c {
+            '0'..='9' if self.portals => Some(Tile::Portal(c as u8 - b'0')),
+            _ => None,
+        })
+    }
+}
+
+/// Everything a single guard did, until she either left the map or started going round in circles.
+pub(crate) struct Walk {
+    /// Where she was and which way she was facing, in order. None of them are in there twice.
+    pub(crate) states: Vec<(Coordinate, Direction)>,
+    /// The portals she stepped into, which she's on for a moment before she's somewhere else.
+    pub(crate) portals: HashSet<Coordinate>,
+    /// If she goes round in circles, the index of the state she comes back to after the last one.
+    pub(crate) loops_back_to: Option<usize>,
+}
+
+impl Walk {
+    pub(crate) fn loops(&self) -> bool {
+        self.loops_back_to.is_some()
+    }
+}
+
+/// The lab, the guards in it, and the rules they walk by.
+pub struct Lab {
+    map: Grid<Tile>,
+    guards: Vec<(Coordinate, Direction)>,
+    portals: HashMap<Coordinate, Coordinate>,
+    rules: Rules,
+}
+
+impl Lab {
+    pub fn parse(input: &str, rules: Rules) -> Result<Lab, ParseError> {
+        let map = Grid::parse(input, |c| rules.tile(c))?;
+
+        let guards = map.iter()
+            .filter_map(|(c, tile)| match tile {
+                Tile::GuardUp => Some((c, Direction::Up)),
+                Tile::GuardRight => Some((c, Direction::Right)),
+                Tile::GuardDown => Some((c, Direction::Down)),
+                Tile::GuardLeft => Some((c, Direction::Left)),
+                _ => None,
+            })
+            .collect::<Vec<(Coordinate, Direction)>>();
+
+        if guards.is_empty() {
+            return Err(ParseError::new("there is no guard on the map"));
+        }
+
+        let mut ends: HashMap<u8, Vec<Coordinate>> = HashMap::new();
+        for (c, tile) in map.iter() {
+            if let Tile::Portal(n) = tile {
+                ends.entry(*n).or_default().push(c);
+            }
+        }
+
+        let mut portals = HashMap::new();
+        for (n, ends) in ends {
+            let [a, b] = ends[..] else {
+                return Err(ParseError::new(format!("portal {} has {} ends instead of 2", n, ends.len())));
+            };
+
+            portals.insert(a, b);
+            portals.insert(b, a);
+        }
+
+        Ok(Lab { map, guards, portals, rules })
+    }
+
+    /// Where a guard is after her next move, with an extra obstacle on `extra`, or `None` if she
+    /// walks off the map. The second value is the portal she went through to get there, if any.
+    fn step(&self, at: Coordinate, facing: Direction, extra: Option<Coordinate>) -> Option<((Coordinate, Direction), Option<Coordinate>)> {
+        let ahead = self.map.step(at, facing)?;
+
+        if self.map[ahead] == Tile::Obstacle || Some(ahead) == extra {
+            return Some(((at, self.rules.turn(facing)), None));
+        }
+
+        match self.portals.get(&ahead) {
+            Some(&other_end) => Some(((other_end, facing), Some(ahead))),
+            None => Some(((ahead, facing), None)),
+        }
+    }
+
+    /// The map, without any of the guards having moved.
+    pub(crate) fn map(&self) -> &Grid<Tile> {
+        &self.map
+    }
+
+    /// The first guard on the map, reading it row by row. The puzzle only ever has the one.
+    pub(crate) fn guard(&self) -> (Coordinate, Direction) {
+        self.guards[0]
+    }
+
+    /// Walks a guard from `from` with an extra obstacle on `extra`, until she leaves the map or is
+    /// somewhere she's already been facing the same way.
+    pub(crate) fn walk(&self, from: Coordinate, facing: Direction, extra: Option<Coordinate>) -> Walk {
+        let mut seen: HashMap<(Coordinate, Direction), usize> = HashMap::new();
+        let mut walk = Walk { states: Vec::new(), portals: HashSet::new(), loops_back_to: None };
+        let mut state = (from, facing);
+
+        loop {
+            if let Some(&i) = seen.get(&state) {
+                walk.loops_back_to = Some(i);
+                return walk;
+            }
+            seen.insert(state, walk.states.len());
+            walk.states.push(state);
+
+            match self.step(state.0, state.1, extra) {
+                None => return walk,
+                Some((next, portal)) => {
+                    walk.portals.extend(portal);
+                    state = next;
+                }
+            }
+        }
+    }
+
+    /// Every tile any of the guards was on.
+    pub fn visited(&self) -> HashSet<Coordinate> {
+        let mut visited = HashSet::new();
+
+        for &(at, facing) in self.guards.iter() {
+            let walk = self.walk(at, facing, None);
+
+            visited.extend(walk.states.iter().map(|(c, _)| *c));
+            visited.extend(walk.portals);
+        }
+
+        visited
+    }
+
+    /// Every tile where a new obstacle would get at least one of the guards stuck going round in
+    /// circles. Same as in part 2, the obstacle can't go where a guard is standing at the start,
+    /// and it can't go on a portal either. Guards who go round in circles without any help are
+    /// left out.
+    pub fn obstructions(&self) -> HashSet<Coordinate> {
+        let starts = self.guards.iter().map(|(c, _)| *c).collect::<HashSet<Coordinate>>();
+        let mut obstructions = HashSet::new();
+
+        for &(at, facing) in self.guards.iter() {
+            let walk = self.walk(at, facing, None);
+            if walk.loops() {
+                continue;
+            }
+
+            // an obstacle changes nothing before she first gets to it, so each one is tried from
+            // right in front of it
+            let mut reached: HashSet<Coordinate> = HashSet::new();
+
+            for &(c, d) in walk.states.iter() {
+                reached.insert(c);
+
+                let Some(ahead) = self.map.step(c, d) else {
+                    continue;
+                };
+                if self.map[ahead] != Tile::Floor || starts.contains(&ahead) || !reached.insert(ahead) {
+                    continue;
+                }
+
+                if self.walk(c, d, Some(ahead)).loops() {
+                    obstructions.insert(ahead);
+                }
+            }
+        }
+
+        obstructions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solve_part2;
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    /// The map the other way round, left to right.
+    fn mirrored(input: &str) -> String {
+        input.lines().map(|l| l.chars().rev().collect::<String>() + "\n").collect()
+    }
+
+    #[test]
+    fn test_puzzle_rules() {
+        let lab = Lab::parse(EXAMPLE, Rules::PUZZLE).unwrap();
+
+        assert_eq!(lab.visited().len(), 41);
+        assert_eq!(lab.obstructions().len() as i32, solve_part2(EXAMPLE).unwrap());
+    }
+
+    #[test]
+    fn test_left_turns_on_mirrored_map() {
+        // turning left on the map in the mirror is the same walk as turning right on the map
+        let lab = Lab::parse(&mirrored(EXAMPLE), Rules { turn: Turn::Left, portals: false }).unwrap();
+
+        assert_eq!(lab.visited().len(), 41);
+        assert_eq!(lab.obstructions().len(), 6);
+    }
+
+    #[test]
+    fn test_several_guards() {
+        // one goes straight up, the other straight down, on different columns
+        let lab = Lab::parse("....\n.^..\n..v.\n....\n", Rules::PUZZLE).unwrap();
+        let visited = lab.visited();
+
+        assert_eq!(visited.len(), 4);
+        assert!(visited.contains(&Coordinate::new(1, 0)));
+        assert!(visited.contains(&Coordinate::new(2, 3)));
+    }
+
+    // straight up and off the map. An obstacle at 2,1 turns her right into the portal, which puts
+    // her back on the same row on the other side, forever.
+    const PORTALS: &str = "\
+.....
+.....
+1...1
+.....
+..^..
+";
+
+    /// Tries an obstacle on every tile, walking the guards all the way from the start each time.
+    fn brute_force(lab: &Lab) -> HashSet<Coordinate> {
+        let starts = lab.guards.iter().map(|(c, _)| *c).collect::<HashSet<Coordinate>>();
+
+        lab.map.iter()
+            .filter(|(c, tile)| **tile == Tile::Floor && !starts.contains(c))
+            .filter(|(c, _)| lab.guards.iter().any(|&(at, facing)| {
+                !lab.walk(at, facing, None).loops() && lab.walk(at, facing, Some(*c)).loops()
+            }))
+            .map(|(c, _)| c)
+            .collect()
+    }
+
+    #[test]
+    fn test_portals() {
+        let rules = Rules { turn: Turn::Right, portals: true };
+
+        // up into portal 1, out of the other end, on up to the obstacle and right off the map
+        let lab = Lab::parse("...#.\n.1...\n...1.\n.^...\n", rules).unwrap();
+        assert_eq!(lab.visited(), HashSet::from([
+            Coordinate::new(1, 3),
+            Coordinate::new(1, 2),
+            Coordinate::new(1, 1),
+            Coordinate::new(3, 2),
+            Coordinate::new(3, 1),
+            Coordinate::new(4, 1),
+        ]));
+
+        // every obstruction found has to be one that trying every tile in turn finds as well
+        let lab = Lab::parse(PORTALS, rules).unwrap();
+        let obstructions = lab.obstructions();
+        assert_eq!(obstructions, brute_force(&lab));
+        assert_eq!(obstructions, HashSet::from([Coordinate::new(2, 1)]));
+
+        assert!(Lab::parse("1.^.\n", rules).is_err());
+        assert!(Lab::parse("1.^.1\n", Rules::PUZZLE).is_err());
+    }
+}
@@ -1,15 +1,7 @@
 mod part1;
 mod part2;
 
-const EXAMPLE: &str = "190: 10 19
-3267: 81 40 27
-83: 17 5
-156: 15 6
-7290: 6 8 6 15
-161011: 16 10 13
-192: 17 8 14
-21037: 9 7 18 13
-292: 11 6 16 20";
+use common::{parse_number, Answer, ParseError, Solution, SolutionError};
 
 #[derive(Debug)]
 struct Line {
@@ -17,18 +9,97 @@ struct Line {
     parts: Vec<u64>,
 }
 
-pub fn solve_part1_example() -> u64 {
-    part1::solve(EXAMPLE)
+/// Every line is a test value, a colon, and at least one number to combine into it.
+fn parse_lines(data: &str) -> Result<Vec<Line>, ParseError> {
+    let mut lines = Vec::new();
+
+    for (i, x) in data.lines().enumerate() {
+        if x.trim().is_empty() {
+            continue;
+        }
+
+        let (target, parts) = x.split_once(":")
+            .ok_or(ParseError::at(i + 1, 1, "expected a test value followed by ':'"))?;
+
+        let parts = parts.split_whitespace()
+            .map(|part| parse_number(i + 1, x, part))
+            .collect::<Result<Vec<u64>, ParseError>>()?;
+
+        if parts.is_empty() {
+            return Err(ParseError::at(i + 1, x.chars().count() + 1, "expected at least one number after ':'"));
+        }
+
+        lines.push(Line { target: parse_number(i + 1, x, target)?, parts });
+    }
+
+    Ok(lines)
 }
 
-pub fn solve_part1() -> u64 {
-    part1::solve(include_str!("../input.txt"))
+/// Adds up the test values of the lines that can be made true.
+fn total_of(lines: impl Iterator<Item = Line>) -> Result<u64, SolutionError> {
+    lines.into_iter().try_fold(0u64, |acc, line| {
+        acc.checked_add(line.target)
+            .ok_or(SolutionError::Solve("the test values add up to more than fits in 64 bits".to_string()))
+    })
 }
 
-pub fn solve_part2_example() -> u64 {
-    part2::solve(EXAMPLE)
+pub struct Day07;
+
+impl Solution for Day07 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        parse_lines(input).map(|_| ())
+    }
+
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input)?.into())
+    }
+
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input)?.into())
+    }
 }
 
-pub fn solve_part2() -> u64 {
-    part2::solve(include_str!("../input.txt"))
-}
\ No newline at end of file
+pub fn solve_part1(input: &str) -> Result<u64, SolutionError> {
+    part1::solve(input)
+}
+
+pub fn solve_part2(input: &str) -> Result<u64, SolutionError> {
+    part2::solve(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines() {
+        let lines = parse_lines("190: 10 19\n3267: 81 40 27\n").unwrap();
+
+        assert_eq!(lines[1].target, 3267);
+        assert_eq!(lines[1].parts, vec![81, 40, 27]);
+    }
+
+    #[test]
+    fn test_parse_lines_errors() {
+        assert_eq!(
+            parse_lines("190: 10 19\n83 17 5\n").err(),
+            Some(ParseError::at(2, 1, "expected a test value followed by ':'"))
+        );
+        assert_eq!(
+            parse_lines("190: 10 19\n83: 17 x5\n").err(),
+            Some(ParseError::at(2, 8, "expected a number, found 'x5'"))
+        );
+        assert_eq!(
+            parse_lines("190:\n").err(),
+            Some(ParseError::at(1, 5, "expected at least one number after ':'"))
+        );
+    }
+
+    #[test]
+    fn test_total_too_big() {
+        let input = "18446744073709551615: 18446744073709551615\n1: 1\n";
+
+        assert!(matches!(solve_part1(input), Err(SolutionError::Solve(_))));
+        assert!(matches!(solve_part2(input), Err(SolutionError::Solve(_))));
+    }
+}
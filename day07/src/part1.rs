@@ -1,68 +1,88 @@
-use crate::{Line};
+use common::SolutionError;
+use crate::{parse_lines, total_of, Line};
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone)]
 enum Operators {
     Plus,
     Product
 }
 
-pub(crate) fn solve(data: &str) -> u64 {
-    let lines: Vec<Line> = data.trim().lines().map(|x| -> Line {
-        let sides = x.split(":").collect::<Vec<&str>>();
+pub(crate) fn solve(data: &str) -> Result<u64, SolutionError> {
+    let lines = parse_lines(data)?;
 
-        Line {
-            target: sides[0].parse::<u64>().unwrap(),
-            parts: sides[1].split_whitespace().map(|x| x.parse::<u64>().unwrap()).collect()
-        }
-    }).collect();
-
-    lines
-        .into_iter()
-        .filter(is_valid)
-        .fold(0, |acc, line| {
-            acc + line.target
-        })
+    total_of(lines.into_iter().filter(is_valid))
 }
 
 fn is_valid(l: &Line) -> bool {
-    // dumb, use a bitmask to denote whether we're adding or multiplying
-    let len = l.parts.len();
-    let binary_end = 2u32.pow((len-1) as u32)-1; // this is for the mask
-
-    'outer: for mask in 0..=binary_end {
-        let mut cases:Vec<Operators> = Vec::new();
+    // every combination of operators, counting up like a binary number with the first gap as the
+    // lowest digit, so it doesn't matter how many gaps there are
+    let mut cases = vec![Operators::Plus; l.parts.len() - 1];
 
-        for test in 0..len-1 {
-            if mask & (1<<test) == 0 {
-                cases.push(Operators::Plus);
-            } else {
-                cases.push(Operators::Product);
-            }
+    loop {
+        if evaluate(l, &cases) == Some(l.target) {
+            return true;
         }
 
-        let mut total = l.parts[0];
-        for i in 1..l.parts.len() {
-            total = operate(total, &cases[i-1], l.parts[i]);
-            if total > l.target {
-                continue 'outer;
-            }
+        if !next_cases(&mut cases) {
+            return false;
         }
+    }
+}
 
-        if total == l.target {
-            return true;
+/// The numbers with the operators in between them, or `None` as soon as that goes over the target.
+/// Neither operator makes a number smaller, so once it's over it stays over.
+fn evaluate(l: &Line, cases: &[Operators]) -> Option<u64> {
+    let mut total = l.parts[0];
+
+    for (operator, &part) in cases.iter().zip(l.parts[1..].iter()) {
+        total = operate(total, operator, part).filter(|&t| t <= l.target)?;
+    }
+
+    Some(total)
+}
+
+/// Moves on to the next combination. Returns false once they've all been tried.
+fn next_cases(cases: &mut [Operators]) -> bool {
+    for case in cases.iter_mut() {
+        match case {
+            Operators::Plus => {
+                *case = Operators::Product;
+                return true;
+            }
+            Operators::Product => *case = Operators::Plus,
         }
     }
 
     false
 }
 
-fn operate(left_number: u64, operator: &Operators, right_number: u64) -> u64 {
+/// `None` if the result doesn't fit in 64 bits, which is bigger than any test value can be.
+fn operate(left_number: u64, operator: &Operators, right_number: u64) -> Option<u64> {
     match operator {
         Operators::Plus => {
-            left_number + right_number
+            left_number.checked_add(right_number)
         }
         Operators::Product => {
-            left_number * right_number
+            left_number.checked_mul(right_number)
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_big_numbers() {
+        // anything more than the biggest test value there can be is too big
+        assert_eq!(solve("18446744073709551615: 18446744073709551615 2\n"), Ok(0));
+        assert_eq!(solve("18446744073709551615: 9223372036854775807 2 1\n"), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn test_many_numbers() {
+        // more gaps than there are bits in a u32, all of them plus
+        let ones = vec!["1"; 40].join(" ");
+        assert_eq!(solve(&format!("40: {}\n", ones)), Ok(40));
+    }
+}
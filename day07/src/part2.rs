@@ -1,6 +1,7 @@
-use crate::Line;
+use common::SolutionError;
+use crate::{parse_lines, total_of, Line};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Copy, Clone)]
 enum P2Operators {
     Add,
     Multiply,
@@ -8,77 +9,73 @@ enum P2Operators {
 }
 
 
-pub(crate) fn solve(input: &str) -> u64 {
-    let lines: Vec<Line> = input.trim().lines().map(|x| -> Line {
-        let sides = x.split(":").collect::<Vec<&str>>();
+pub(crate) fn solve(input: &str) -> Result<u64, SolutionError> {
+    let lines = parse_lines(input)?;
 
-        Line {
-            target: sides[0].parse::<u64>().unwrap(),
-            parts: sides[1].split_whitespace().map(|x| x.parse::<u64>().unwrap()).collect()
-        }
-    }).collect();
-
-    lines
-        .into_iter()
-        .filter(is_valid)
-        .fold(0, |acc, line| {
-            acc + line.target
-        })
+    total_of(lines.into_iter().filter(is_valid))
 }
 
 fn is_valid(l: &Line) -> bool {
-    let gaps = l.parts.len()-1;
-
-    'outer: for i in 0..3u32.pow(gaps as u32) {
-        let variant = variants_to_base3(i as u64, gaps);
+    let mut variant = vec![P2Operators::Add; l.parts.len() - 1];
 
-        let mut total = l.parts[0];
-        for (j, _) in variant.iter().enumerate().take(l.parts.len()-1) {
-            total = operate(total, &variant[j], l.parts[j+1]);
-            if total > l.target {
-                continue 'outer;
-            }
-        }
-
-        if total == l.target {
+    loop {
+        if evaluate(l, &variant) == Some(l.target) {
             return true;
         }
 
+        if !next_variant(&mut variant) {
+            return false;
+        }
     }
-    false
 }
 
+/// The numbers with the operators in between them, or `None` as soon as that goes over the target.
+/// None of the operators makes a number smaller, so once it's over it stays over.
+fn evaluate(l: &Line, variant: &[P2Operators]) -> Option<u64> {
+    let mut total = l.parts[0];
 
-fn variants_to_base3(code: u64, len: usize) -> Vec<P2Operators>{
-    let mut variants: Vec<P2Operators> = Vec::new();
-    let mut steps = code;
+    for (operator, &part) in variant.iter().zip(l.parts[1..].iter()) {
+        total = operate(total, operator, part).filter(|&t| t <= l.target)?;
+    }
 
-    for _i in 0..len {
-        let  remainder = steps.wrapping_rem(3);
-        match remainder {
-            0 => variants.push(P2Operators::Add),
-            1 => variants.push(P2Operators::Multiply),
-            2 => variants.push(P2Operators::Concatenate),
-            _ => unreachable!()
-        }
+    Some(total)
+}
 
-        steps = steps.checked_div(3).unwrap();
+/// Each gap between two numbers can hold one of three operators. This counts through all of them
+/// like a number in base 3, with the first gap as the lowest digit: Add is 0, Multiply 1 and
+/// Concatenate 2. Returns false once they've all been tried.
+fn next_variant(variant: &mut [P2Operators]) -> bool {
+    for operator in variant.iter_mut() {
+        match operator {
+            P2Operators::Add => {
+                *operator = P2Operators::Multiply;
+                return true;
+            }
+            P2Operators::Multiply => {
+                *operator = P2Operators::Concatenate;
+                return true;
+            }
+            P2Operators::Concatenate => *operator = P2Operators::Add,
+        }
     }
 
-    variants
+    false
 }
 
 
-fn operate(left_number: u64, operator: &P2Operators, right_number: u64) -> u64 {
+/// `None` if the result doesn't fit in 64 bits, which is bigger than any test value can be.
+fn operate(left_number: u64, operator: &P2Operators, right_number: u64) -> Option<u64> {
     match operator {
         P2Operators::Add=> {
-            left_number + right_number
+            left_number.checked_add(right_number)
         }
         P2Operators::Multiply => {
-            left_number * right_number
+            left_number.checked_mul(right_number)
         }
         P2Operators::Concatenate => {
-            format!("{}{}", left_number, right_number).parse::<u64>().unwrap()
+            let digits = right_number.checked_ilog10().unwrap_or(0) + 1;
+
+            left_number.checked_mul(10u64.checked_pow(digits)?)?.checked_add(right_number)
         }
     }
 }
@@ -87,36 +84,70 @@ fn operate(left_number: u64, operator: &P2Operators, right_number: u64) -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    /// The variant `code` steps after all adds.
+    fn nth_variant(code: u64, len: usize) -> Vec<P2Operators> {
+        let mut variant = vec![P2Operators::Add; len];
+        for _ in 0..code {
+            next_variant(&mut variant);
+        }
+
+        variant
+    }
+
     #[test]
-    fn test_variants_to_base3() {
+    fn test_next_variant() {
         // Add is 0
         // Multiply is 1
         // Concatenate is 2
-        assert_eq!(variants_to_base3(0, 1), vec![P2Operators::Add], "0 /1 should be just an add");
-        assert_eq!(variants_to_base3(1, 2), vec![P2Operators::Multiply, P2Operators::Add], "1 / 2 should be a mul, add");
-        assert_eq!(variants_to_base3(2, 3 ), vec![
+        assert_eq!(nth_variant(0, 1), vec![P2Operators::Add], "0 /1 should be just an add");
+        assert_eq!(nth_variant(1, 2), vec![P2Operators::Multiply, P2Operators::Add], "1 / 2 should be a mul, add");
+        assert_eq!(nth_variant(2, 3 ), vec![
             P2Operators::Concatenate,
             P2Operators::Add,
             P2Operators::Add
         ], "2 / 3 should be concatenate, add, add");
-        assert_eq!(variants_to_base3(21, 4), vec![
+        assert_eq!(nth_variant(21, 4), vec![
             P2Operators::Add,
             P2Operators::Multiply,
             P2Operators::Concatenate,
             P2Operators::Add
-        ], "21 / 4 should be just concatenate");
+        ], "21 / 4 should be add, mul, concatenate, add");
     }
 
     #[test]
-    fn test_gaps_to_variants() {
-        assert_eq!(gaps_to_variants(&0), 1);
-        assert_eq!(gaps_to_variants(&4), 81);
+    fn test_number_of_variants() {
+        let count = |gaps: usize| {
+            let mut variant = vec![P2Operators::Add; gaps];
+            let mut count = 1;
+            while next_variant(&mut variant) {
+                count += 1;
+            }
+
+            count
+        };
+
+        assert_eq!(count(0), 1);
+        assert_eq!(count(4), 81);
     }
 
     #[test]
     fn test_operate() {
-        assert_eq!(operate(32, &P2Operators::Add, 98), 130);
-        assert_eq!(operate(32, &P2Operators::Multiply, 98), 3136);
-        assert_eq!(operate(32, &P2Operators::Concatenate, 98), 3298);
+        assert_eq!(operate(32, &P2Operators::Add, 98), Some(130));
+        assert_eq!(operate(32, &P2Operators::Multiply, 98), Some(3136));
+        assert_eq!(operate(32, &P2Operators::Concatenate, 98), Some(3298));
+        assert_eq!(operate(32, &P2Operators::Concatenate, 0), Some(320));
+
+        assert_eq!(operate(u64::MAX, &P2Operators::Add, 1), None);
+        assert_eq!(operate(u64::MAX, &P2Operators::Multiply, 2), None);
+        assert_eq!(operate(2, &P2Operators::Concatenate, u64::MAX), None);
+        assert_eq!(operate(1, &P2Operators::Concatenate, 8446744073709551615), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_many_numbers() {
+        // more gaps than 3^gaps fits in a u32 for, all of them add
+        let ones = vec!["1"; 40].join(" ");
+        assert_eq!(solve(&format!("40: {}\n", ones)), Ok(40));
     }
-}
\ No newline at end of file
+}
@@ -1,55 +1,30 @@
 mod part1;
 mod part2;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-struct Coordinate {
-    height: i32,
-    width: i32,
-}
+use common::{Answer, ParseError, Solution, SolutionError};
 
+pub struct Day08;
 
-pub fn solve_part1_example() -> usize{
-    let input = "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............";
+impl Solution for Day08 {
+    // Any character other than . is an antenna frequency, the lines only have to be the same
+    // length.
+    fn parse(input: &str) -> Result<(), ParseError> {
+        part1::Day08::new(input).map(|_| ())
+    }
 
-    part1::solve(input)
-}
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input)?.into())
+    }
 
-pub fn solve_part1() -> usize {
-    let input = include_str!("../input.txt");
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input)?.into())
+    }
+}
 
+pub fn solve_part1(input: &str) -> Result<usize, ParseError> {
     part1::solve(input)
 }
 
-pub fn solve_part2_example() -> usize {
-    let input = "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............";
-
+pub fn solve_part2(input: &str) -> Result<usize, ParseError> {
     part2::solve(input)
 }
-
-pub fn solve_part2() -> usize {
-    let input = include_str!("../input.txt");
-
-    part2::solve(input)
-}
\ No newline at end of file
@@ -1,51 +1,32 @@
 use std::collections::{HashMap, HashSet};
-use crate::Coordinate;
+use common::ParseError;
+use grid::{Coordinate, Grid};
 
+/// Anything that is not an antenna.
+pub(crate) const FIELD: char = '.';
 
-struct Day08 {
-    last_h_idx: usize,
-    last_w_idx: usize,
+pub(crate) struct Day08 {
+    map: Grid<char>,
     antennae: HashMap<char, Vec<Coordinate>>,
     antinodes: HashMap<char, Vec<Coordinate>>
 }
 
 impl Day08 {
-    fn new(input: &str) -> Day08 {
-        let field: char = ".".parse().unwrap();
+    pub(crate) fn new(input: &str) -> Result<Day08, ParseError> {
+        let map = Grid::parse(input, Some)?;
 
-        let mut antennae = HashMap::new();
-        let mut last_h_idx:usize = 0;
-        let mut last_w_idx = 0;
-
-        for (height, line) in input.trim().lines().enumerate() {
-            last_h_idx = height;
-            last_w_idx = line.len()-1; // len is not zero indexed
-
-            for (width, ch) in line.trim().chars().enumerate() {
-                let c = Coordinate{ height: height as i32, width: width as i32 };
-                if !ch.eq(&field) {antennae.entry(ch).or_insert(Vec::new()).push(c);}
-            }
-        }
-
-        Day08 {
-            last_h_idx,
-            last_w_idx,
-            antennae,
+        Ok(Day08 {
+            antennae: find_antennae(&map),
+            map,
             antinodes: HashMap::new()
-        }
+        })
     }
 
     fn find_antinodes_per_antenna(&mut self) {
         for (ch, coords) in self.antennae.iter() {
             for pair in generate_all_node_pairs(coords.to_owned()) {
-                let nodes = generate_antinodes(pair);
-
-                if self.is_coord_within_map(nodes.0) {
-                    self.antinodes.entry(*ch).or_default().push(nodes.0);
-                }
-
-                if self.is_coord_within_map(nodes.1) {
-                    self.antinodes.entry(*ch).or_default().push(nodes.1);
+                for node in generate_antinodes(pair, &self.map) {
+                    self.antinodes.entry(*ch).or_default().push(node);
                 }
             }
         }
@@ -62,24 +43,23 @@ impl Day08 {
 
         unique_antennae.len()
     }
+}
 
-    fn is_coord_within_map(&self, coordinate: Coordinate) -> bool {
-        if coordinate.width >= 0
-            && coordinate.height >= 0
-            && coordinate.width as usize <= self.last_w_idx
-            && coordinate.height as usize <= self.last_h_idx
-        {
-            return true;
-        }
+/// Every frequency on the map, with the coordinates of the antennae tuned to it.
+pub(crate) fn find_antennae(map: &Grid<char>) -> HashMap<char, Vec<Coordinate>> {
+    let mut antennae: HashMap<char, Vec<Coordinate>> = HashMap::new();
 
-        false
+    for (c, ch) in map.iter() {
+        if *ch != FIELD {
+            antennae.entry(*ch).or_default().push(c);
+        }
     }
 
-
+    antennae
 }
 
-pub(crate) fn solve(input: &str) -> usize {
-    let mut d = Day08::new(input);
+pub(crate) fn solve(input: &str) -> Result<usize, ParseError> {
+    let mut d = Day08::new(input)?;
 
     // println!("map:\n{:?}", d.map);
     // println!("\n\nantennae:\n{:?}", d.antennae);
@@ -90,7 +70,7 @@ pub(crate) fn solve(input: &str) -> usize {
 
     // println!("\nantinodes:\n{:?}", d.antinodes);
 
-    d.count_unique_antennae()
+    Ok(d.count_unique_antennae())
 }
 
 pub(crate) fn generate_all_node_pairs(coords: Vec<Coordinate>) -> Vec<(Coordinate, Coordinate)> {
@@ -109,21 +89,15 @@ pub(crate) fn generate_all_node_pairs(coords: Vec<Coordinate>) -> Vec<(Coordinat
     pairs
 }
 
-fn generate_antinodes(coord_pair: (Coordinate, Coordinate)) -> (Coordinate, Coordinate) {
-    let diff_height = coord_pair.0.height - coord_pair.1.height;
-    let diff_width = coord_pair.0.width-coord_pair.1.width;
-
-    let new_c1 = Coordinate {
-        height: coord_pair.0.height + diff_height,
-        width: coord_pair.0.width + diff_width
-    };
+/// The two antinodes of a pair of antennae, as long as they are on the map.
+fn generate_antinodes(coord_pair: (Coordinate, Coordinate), map: &Grid<char>) -> Vec<Coordinate> {
+    let diff_height = coord_pair.0.vertical as isize - coord_pair.1.vertical as isize;
+    let diff_width = coord_pair.0.horizontal as isize - coord_pair.1.horizontal as isize;
 
-    let new_c2 = Coordinate{
-        height: coord_pair.1.height - diff_height,
-        width: coord_pair.1.width - diff_width
-    };
+    let new_c1 = map.offset(coord_pair.0, diff_width, diff_height);
+    let new_c2 = map.offset(coord_pair.1, -diff_width, -diff_height);
 
-    (new_c1, new_c2)
+    [new_c1, new_c2].into_iter().flatten().collect()
 }
 
 #[cfg(test)]
@@ -132,26 +106,27 @@ mod tests {
 
     #[test]
     fn test_generate_antinodes() {
-        let node1 = Coordinate{ height: 1, width: 4 };
-        let node2 = Coordinate{ height: 3, width: 2 };
+        let map = Grid::new(7, 7, FIELD);
+        let node1 = Coordinate::new(4, 1);
+        let node2 = Coordinate::new(2, 3);
 
-        let want_node_1 = Coordinate{ height: -1, width: 6 };
-        let want_node_2 = Coordinate{ height: 5, width: 0 };
+        // the other one would be at (6, -1), which is off the map
+        let want_node_2 = Coordinate::new(0, 5);
 
-        assert_eq!((want_node_1, want_node_2), generate_antinodes((node1, node2)));
+        assert_eq!(vec![want_node_2], generate_antinodes((node1, node2), &map));
     }
 
     #[test]
     fn test_generate_all_node_pairs() {
         let empty_vec:Vec<(Coordinate, Coordinate)> = vec![];
-        let node1 = Coordinate{ height: 1, width: 4 };
+        let node1 = Coordinate::new(4, 1);
         assert_eq!(empty_vec, generate_all_node_pairs(vec![node1]));
 
-        let node2 = Coordinate{ height: 3, width: 2 };
+        let node2 = Coordinate::new(2, 3);
         let one_pair_vec: Vec<(Coordinate, Coordinate)> = vec![(node1, node2)];
         assert_eq!(one_pair_vec, generate_all_node_pairs(vec![node1, node2]));
 
-        let node3 = Coordinate{ height: 1, width: 2 };
+        let node3 = Coordinate::new(2, 1);
         let three_pair_vec: Vec<(Coordinate, Coordinate)> = vec![(node1, node2), (node1, node3), (node2, node3)];
         assert_eq!(three_pair_vec, generate_all_node_pairs(vec![node1, node2, node3]));
     }
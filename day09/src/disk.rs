@@ -0,0 +1,435 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use common::ParseError;
+use crate::part1::{blocks_checksum, Block, Day09, NumericBlockType};
+use crate::encode::render;
+use crate::part2::compact_whole_files;
+
+/// A disk as the files and the free space on it, in order from the start of the disk. There are no
+/// empty blocks on it, free space is never right next to more free space, and a piece of a file is
+/// never right next to another piece of the same file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskMap {
+    blocks: Vec<Block>,
+}
+
+/// A way of moving the data on a disk around.
+pub trait Strategy {
+    /// Moves the data around and returns how many moves that took. The blocks come in order from
+    /// the start of the disk, and can be left with empty blocks or free space next to free space,
+    /// as long as they're still in order and the disk is the same size. The disk tidies them up.
+    fn compact(&self, blocks: &mut Vec<Block>) -> usize;
+}
+
+/// Moves data one block at a time from the end of the disk into the leftmost free block, like part
+/// 1 does, until there are no gaps left. Every block moved is a move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BlockCompaction;
+
+/// Moves whole files, highest id first, into the leftmost stretch of free space to the left of them
+/// that they fit in, like part 2 does. Each file gets one go, and every file moved is a move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FirstFit;
+
+/// Same as [`FirstFit`], except files go into the smallest stretch of free space they fit in, and
+/// the leftmost one of those if there are more. This one still looks through the whole disk for
+/// every file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BestFit;
+
+/// Puts every file in one piece, packed to the start of the disk in the order they start in, and
+/// all the free space at the end. Every piece of a file that isn't already where it ends up is a
+/// move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Defrag;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Fragmentation {
+    /// Files that are in more than one piece.
+    pub split_files: usize,
+    /// Stretches of free space with data somewhere after them.
+    pub gaps: usize,
+}
+
+/// What the disk looks like after a strategy is done with it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// `None` if it's too big to fit in 64 bits.
+    pub checksum: Option<u64>,
+    pub moves: usize,
+    pub fragmentation: Fragmentation,
+}
+
+impl DiskMap {
+    pub fn parse(input: &str) -> Result<DiskMap, ParseError> {
+        let day = Day09::new(input)?;
+        let blocks = day.data_blocks.into_iter()
+            .zip(day.space_blocks)
+            .flat_map(|(data, space)| [data, space])
+            .collect();
+
+        Ok(DiskMap { blocks: tidy(blocks) })
+    }
+
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Runs the strategy on the disk and reports on what's left.
+    pub fn compact(&mut self, strategy: &dyn Strategy) -> Report {
+        let moves = strategy.compact(&mut self.blocks);
+        self.blocks = tidy(std::mem::take(&mut self.blocks));
+
+        Report {
+            checksum: self.checksum(),
+            moves,
+            fragmentation: self.fragmentation(),
+        }
+    }
+
+    /// `None` if it's too big to fit in 64 bits.
+    pub fn checksum(&self) -> Option<u64> {
+        blocks_checksum(&self.blocks)
+    }
+
+    pub fn fragmentation(&self) -> Fragmentation {
+        let mut pieces: HashMap<u32, usize> = HashMap::new();
+        for block in self.blocks.iter() {
+            if let NumericBlockType::Data(id) = block.block_type {
+                *pieces.entry(id).or_default() += 1;
+            }
+        }
+
+        let last_data = self.blocks.iter()
+            .rposition(|b| b.block_type != NumericBlockType::Space)
+            .unwrap_or(0);
+
+        Fragmentation {
+            split_files: pieces.values().filter(|&&n| n > 1).count(),
+            gaps: self.blocks[..last_data].iter().filter(|b| b.block_type == NumericBlockType::Space).count(),
+        }
+    }
+}
+
+impl Display for DiskMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", render(&self.blocks))
+    }
+}
+
+/// Drops the empty blocks and joins up the ones next to each other that are the same.
+pub(crate) fn tidy(blocks: Vec<Block>) -> Vec<Block> {
+    let mut tidied: Vec<Block> = Vec::with_capacity(blocks.len());
+
+    for block in blocks.into_iter().filter(|b| b.length > 0) {
+        match tidied.last_mut() {
+            Some(last) if last.block_type == block.block_type => last.length += block.length,
+            _ => tidied.push(block),
+        }
+    }
+
+    tidied
+}
+
+/// The disk one block at a time.
+fn expand(blocks: &[Block]) -> Vec<NumericBlockType> {
+    blocks.iter()
+        .flat_map(|b| std::iter::repeat_n(b.block_type, b.length as usize))
+        .collect()
+}
+
+/// The other way round from `expand`, a block for every run of the same thing.
+fn runs(disk: &[NumericBlockType]) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+
+    for (i, &block_type) in disk.iter().enumerate() {
+        match blocks.last_mut() {
+            Some(last) if last.block_type == block_type => last.length += 1,
+            _ => blocks.push(Block { block_type, start: i as u32, length: 1 }),
+        }
+    }
+
+    blocks
+}
+
+impl Strategy for BlockCompaction {
+    fn compact(&self, blocks: &mut Vec<Block>) -> usize {
+        let mut disk = expand(blocks);
+        let mut moves = 0;
+        let (mut space, mut data) = (0, disk.len());
+
+        loop {
+            while space < data && disk[space] != NumericBlockType::Space {
+                space += 1;
+            }
+            while data > space && disk[data - 1] == NumericBlockType::Space {
+                data -= 1;
+            }
+            if space >= data {
+                break;
+            }
+
+            disk.swap(space, data - 1);
+            moves += 1;
+        }
+
+        *blocks = runs(&disk);
+
+        moves
+    }
+}
+
+/// Gives every piece of every file one go at moving into the smallest stretch of free space to the
+/// left of it that it fits in, highest id first, and the piece furthest to the right first out of
+/// the pieces of the same file.
+fn move_files_best_fit(blocks: &mut Vec<Block>) -> usize {
+    let mut pieces = blocks.iter()
+        .filter_map(|b| match b.block_type {
+            NumericBlockType::Data(id) => Some((id, b.start)),
+            NumericBlockType::Space => None,
+        })
+        .collect::<Vec<(u32, u32)>>();
+    pieces.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut moves = 0;
+
+    for (_, start) in pieces {
+        // nothing that isn't free space ever ends up where a file that hasn't moved yet still is
+        let Ok(mut i) = blocks.binary_search_by_key(&start, |b| b.start) else {
+            continue;
+        };
+        let file = blocks[i];
+
+        let found = blocks[..i].iter()
+            .enumerate()
+            .filter(|(_, b)| b.block_type == NumericBlockType::Space && b.length >= file.length)
+            .min_by_key(|(_, b)| b.length);
+        let Some((j, &space)) = found else {
+            continue;
+        };
+
+        blocks[j] = Block { start: space.start, ..file };
+        if space.length > file.length {
+            blocks.insert(j + 1, Block {
+                block_type: NumericBlockType::Space,
+                start: space.start + file.length,
+                length: space.length - file.length,
+            });
+            i += 1;
+        }
+
+        free(blocks, i);
+        moves += 1;
+    }
+
+    moves
+}
+
+/// Turns the block at `i` into free space, joined up with any free space on either side of it, so
+/// files further to the right can use all of it.
+fn free(blocks: &mut Vec<Block>, mut i: usize) {
+    blocks[i].block_type = NumericBlockType::Space;
+
+    if blocks.get(i + 1).is_some_and(|b| b.block_type == NumericBlockType::Space) {
+        blocks[i].length += blocks.remove(i + 1).length;
+    }
+    if i > 0 && blocks[i - 1].block_type == NumericBlockType::Space {
+        blocks[i - 1].length += blocks.remove(i).length;
+        i -= 1;
+    }
+
+    debug_assert_eq!(blocks[i].block_type, NumericBlockType::Space);
+}
+
+impl Strategy for FirstFit {
+    fn compact(&self, blocks: &mut Vec<Block>) -> usize {
+        compact_whole_files(blocks)
+    }
+}
+
+impl Strategy for BestFit {
+    fn compact(&self, blocks: &mut Vec<Block>) -> usize {
+        move_files_best_fit(blocks)
+    }
+}
+
+impl Strategy for Defrag {
+    fn compact(&self, blocks: &mut Vec<Block>) -> usize {
+        let mut order: Vec<u32> = Vec::new();
+        let mut pieces: HashMap<u32, Vec<Block>> = HashMap::new();
+
+        for block in blocks.iter() {
+            if let NumericBlockType::Data(id) = block.block_type {
+                let file = pieces.entry(id).or_default();
+                if file.is_empty() {
+                    order.push(id);
+                }
+                file.push(*block);
+            }
+        }
+
+        let size = blocks.iter().map(|b| b.length).sum::<u32>();
+        let mut defragged = Vec::with_capacity(order.len() + 1);
+        let mut offset = 0;
+        let mut moves = 0;
+
+        for id in order {
+            let file = &pieces[&id];
+            let length = file.iter().map(|b| b.length).sum::<u32>();
+
+            moves += file.len() - (file[0].start == offset) as usize;
+            defragged.push(Block { block_type: NumericBlockType::Data(id), start: offset, length });
+            offset += length;
+        }
+
+        defragged.push(Block { block_type: NumericBlockType::Space, start: offset, length: size - offset });
+        *blocks = defragged;
+
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::part1::disk_checksum;
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    fn compact(input: &str, strategy: &dyn Strategy) -> Report {
+        DiskMap::parse(input).unwrap().compact(strategy)
+    }
+
+    #[test]
+    fn test_block_compaction() {
+        assert_eq!(compact(EXAMPLE, &BlockCompaction), Report {
+            checksum: Some(1928),
+            moves: 12,
+            // 0099811188827773336446555566: 8 in two pieces, 6 in three
+            fragmentation: Fragmentation { split_files: 2, gaps: 0 },
+        });
+
+        // 0..111....22222 becomes 022111222
+        assert_eq!(compact("12345", &BlockCompaction).checksum, Some(60));
+    }
+
+    #[test]
+    fn test_first_fit() {
+        assert_eq!(compact(EXAMPLE, &FirstFit), Report {
+            checksum: Some(2858),
+            // 9, 7, 4 and 2
+            moves: 4,
+            // 00992111777.44.333....5555.6666.....8888..
+            fragmentation: Fragmentation { split_files: 0, gaps: 5 },
+        });
+    }
+
+    #[test]
+    fn test_best_fit() {
+        // 0...1.2223: under first fit 3 goes in the gap of 3, so 2 doesn't fit in it any more and
+        // it ends up as 031...222.
+        assert_eq!(compact("1311301", &FirstFit), Report {
+            checksum: Some(3 + 2 + 2 * (6 + 7 + 8)),
+            moves: 2,
+            fragmentation: Fragmentation { split_files: 0, gaps: 1 },
+        });
+
+        // under best fit 3 goes in the gap of 1 instead, and 2 in the gap of 3: 022213....
+        assert_eq!(compact("1311301", &BestFit), Report {
+            checksum: Some(2 * (1 + 2 + 3) + 4 + 3 * 5),
+            moves: 2,
+            fragmentation: Fragmentation { split_files: 0, gaps: 0 },
+        });
+    }
+
+    #[test]
+    fn test_defrag() {
+        let mut disk = DiskMap::parse(EXAMPLE).unwrap();
+        let report = disk.compact(&Defrag);
+
+        // everything but file 0 moves
+        assert_eq!(report.moves, 9);
+        assert_eq!(report.fragmentation, Fragmentation { split_files: 0, gaps: 0 });
+
+        // block compaction leaves files in pieces, defrag puts them back together
+        let mut disk = DiskMap::parse(EXAMPLE).unwrap();
+        disk.compact(&BlockCompaction);
+        let report = disk.compact(&Defrag);
+        assert_eq!(report.fragmentation, Fragmentation { split_files: 0, gaps: 0 });
+        assert_eq!(disk.blocks().len(), 11);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let strategies: [&dyn Strategy; 4] = [&BlockCompaction, &FirstFit, &BestFit, &Defrag];
+
+        // a single file, files of no length, and nothing but free space after the first file
+        for input in ["1", "3", "0", "10", "09", "103", "1020304"] {
+            let size = input.chars().map(|c| c.to_digit(10).unwrap()).sum::<u32>();
+
+            for strategy in strategies {
+                let mut disk = DiskMap::parse(input).unwrap();
+                disk.compact(strategy);
+
+                assert_eq!(disk.blocks().iter().map(|b| b.length).sum::<u32>(), size, "{}", input);
+            }
+        }
+
+        assert_eq!(compact("103", &FirstFit).checksum, Some(1 + 2 + 3));
+        assert_eq!(compact("1020304", &BlockCompaction).moves, 0);
+        assert!(DiskMap::parse("").is_err());
+    }
+
+    /// Whole files moved to the left, going through the disk one block at a time.
+    fn first_fit_by_block(input: &str) -> Option<u64> {
+        let blocks = DiskMap::parse(input).unwrap().blocks;
+        let mut disk = expand(&blocks);
+
+        let mut files = blocks.iter()
+            .filter_map(|b| match b.block_type {
+                NumericBlockType::Data(id) => Some((id, b.start as usize, b.length as usize)),
+                NumericBlockType::Space => None,
+            })
+            .collect::<Vec<(u32, usize, usize)>>();
+        files.sort_unstable_by(|a, b| b.cmp(a));
+
+        for (id, start, length) in files {
+            let free = (0..start).find(|&i| disk[i..i + length].iter().all(|b| *b == NumericBlockType::Space));
+
+            if let Some(to) = free {
+                disk[start..start + length].fill(NumericBlockType::Space);
+                disk[to..to + length].fill(NumericBlockType::Data(id));
+            }
+        }
+
+        disk_checksum(&disk)
+    }
+
+    #[test]
+    fn test_first_fit_by_block() {
+        let mut state: u64 = 9;
+
+        for _ in 0..500 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let length = 1 + (state >> 33) as usize % 40;
+            let input = (0..length)
+                .map(|_| {
+                    state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                    char::from(b'0' + (state >> 33) as u8 % 10)
+                })
+                .collect::<String>();
+
+            assert_eq!(compact(&input, &FirstFit).checksum, first_fit_by_block(&input), "{}", input);
+        }
+
+        assert_eq!(first_fit_by_block(EXAMPLE), Some(2858));
+    }
+
+    #[test]
+    fn test_puzzle() {
+        let input = include_str!("../input.txt");
+
+        assert_eq!(compact(input, &BlockCompaction).checksum, Some(6301895872542));
+        assert_eq!(compact(input, &FirstFit).checksum, Some(6323761685944));
+    }
+}
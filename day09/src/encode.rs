@@ -0,0 +1,231 @@
+use common::ParseError;
+use crate::disk::tidy;
+use crate::part1::{Block, Day09, NumericBlockType};
+
+/// A disk in the puzzle's dense format, along with which file each of the files in it is.
+///
+/// The dense format numbers files in the order they're in, and can't have more than 9 of anything
+/// in a row, which is no good once files have moved around. Files that are too long are written as
+/// pieces with no free space in between, and free space that's too long has empty files in
+/// between, which have no id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dense {
+    pub map: String,
+    /// The id of each file in the map, in the order they're in.
+    pub ids: Vec<Option<u32>>,
+}
+
+impl Dense {
+    fn expecting_file(&self) -> bool {
+        self.map.len().is_multiple_of(2)
+    }
+
+    fn push(&mut self, length: u32) {
+        self.map.push(char::from_digit(length, 10).expect("lengths are split up to be at most 9"));
+    }
+
+    fn file(&mut self, id: u32, mut length: u32) {
+        if !self.expecting_file() {
+            self.push(0);
+        }
+
+        while length > 9 {
+            self.push(9);
+            self.ids.push(Some(id));
+            self.push(0);
+            length -= 9;
+        }
+
+        self.push(length);
+        self.ids.push(Some(id));
+    }
+
+    fn space(&mut self, mut length: u32) {
+        if self.expecting_file() {
+            self.push(0);
+            self.ids.push(None);
+        }
+
+        while length > 9 {
+            self.push(9);
+            self.push(0);
+            self.ids.push(None);
+            length -= 9;
+        }
+
+        self.push(length);
+    }
+}
+
+/// The blocks in the dense format. They don't need to be in order, and anywhere on the disk there
+/// isn't a block is free space.
+pub fn encode(blocks: &[Block]) -> Dense {
+    let mut dense = Dense { map: String::new(), ids: Vec::new() };
+    let mut free = 0;
+    let mut offset = 0;
+
+    for block in in_order(blocks) {
+        free += block.start - offset;
+        offset = block.start + block.length;
+
+        match block.block_type {
+            NumericBlockType::Space => free += block.length,
+            NumericBlockType::Data(id) => {
+                if free > 0 {
+                    dense.space(free);
+                    free = 0;
+                }
+                dense.file(id, block.length);
+            }
+        }
+    }
+
+    if free > 0 || dense.map.is_empty() {
+        dense.space(free);
+    }
+
+    dense
+}
+
+/// The blocks the dense map is of, the other way round from `encode`, tidied up the same way as
+/// `DiskMap` does.
+pub fn decode(dense: &Dense) -> Result<Vec<Block>, ParseError> {
+    let day = Day09::new(&dense.map)?;
+
+    if day.data_blocks.len() != dense.ids.len() {
+        return Err(ParseError::new(format!("the map has {} files, but there are ids for {}", day.data_blocks.len(), dense.ids.len())));
+    }
+
+    let mut blocks = Vec::with_capacity(2 * dense.ids.len());
+    for ((data, space), id) in day.data_blocks.into_iter().zip(day.space_blocks).zip(dense.ids.iter()) {
+        match (id, data.length) {
+            (Some(id), _) => blocks.push(Block { block_type: NumericBlockType::Data(*id), ..data }),
+            (None, 0) => {}
+            (None, _) => {
+                return Err(ParseError::new(format!("the file at {} has no id, but it isn't empty", data.start)));
+            }
+        }
+
+        blocks.push(space);
+    }
+
+    Ok(tidy(blocks))
+}
+
+/// Draws the disk a block at a time, like the puzzle does: `00...111...2`. Once there are ids of 10
+/// and up, every block of a file is its id in brackets instead: `(10)(10)...(11)`.
+pub fn render(blocks: &[Block]) -> String {
+    let legible = blocks.iter().any(|b| matches!(b.block_type, NumericBlockType::Data(id) if id > 9));
+    let mut s = String::new();
+    let mut offset = 0;
+
+    for block in in_order(blocks) {
+        s += &".".repeat((block.start - offset) as usize);
+        offset = block.start + block.length;
+
+        let one = match (block.block_type, legible) {
+            (NumericBlockType::Data(id), false) => id.to_string(),
+            (block_type, _) => block_type.to_string(),
+        };
+        s += &one.repeat(block.length as usize);
+    }
+
+    s
+}
+
+fn in_order(blocks: &[Block]) -> Vec<Block> {
+    let mut blocks = blocks.to_vec();
+    blocks.sort_by_key(|b| b.start);
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{blocks_checksum, BestFit, BlockCompaction, Defrag, DiskMap, FirstFit, Strategy};
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+
+    fn data(id: u32, start: u32, length: u32) -> Block {
+        Block { block_type: NumericBlockType::Data(id), start, length }
+    }
+
+    #[test]
+    fn test_encode_parsed() {
+        for input in [EXAMPLE, include_str!("../input.txt"), "12345", "1020304"] {
+            let dense = encode(DiskMap::parse(input).unwrap().blocks());
+
+            assert_eq!(dense.map, input.trim_end());
+            assert_eq!(dense.ids, (0..dense.ids.len() as u32).map(Some).collect::<Vec<Option<u32>>>());
+        }
+    }
+
+    #[test]
+    fn test_encode_long_runs() {
+        // twelve 4s, then twenty free blocks with a hole in the middle of them, then a 2
+        let dense = encode(&[
+            data(4, 0, 12),
+            Block { block_type: NumericBlockType::Space, start: 12, length: 10 },
+            data(2, 32, 1),
+        ]);
+
+        assert_eq!(dense, Dense {
+            map: "903909021".to_string(),
+            ids: vec![Some(4), Some(4), None, None, Some(2)],
+        });
+        assert_eq!(decode(&dense).unwrap(), vec![
+            data(4, 0, 12),
+            Block { block_type: NumericBlockType::Space, start: 12, length: 20 },
+            data(2, 32, 1),
+        ]);
+
+        // free space at the start needs an empty file in front of it
+        assert_eq!(encode(&[data(7, 3, 2)]).map, "032");
+        assert_eq!(encode(&[]).map, "00");
+    }
+
+    #[test]
+    fn test_round_trip_after_compaction() {
+        let strategies: [&dyn Strategy; 4] = [&BlockCompaction, &FirstFit, &BestFit, &Defrag];
+
+        for input in [EXAMPLE, "12345", "1311301", "90909", "0919"] {
+            for strategy in strategies {
+                let mut disk = DiskMap::parse(input).unwrap();
+                let report = disk.compact(strategy);
+                let dense = encode(disk.blocks());
+
+                let decoded = decode(&dense).unwrap();
+                assert_eq!(decoded, disk.blocks(), "{} to {}", input, dense.map);
+                assert_eq!(blocks_checksum(&decoded), report.checksum);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let dense = Dense { map: "123".to_string(), ids: vec![Some(0)] };
+        assert_eq!(decode(&dense), Err(ParseError::new("the map has 2 files, but there are ids for 1")));
+
+        let dense = Dense { map: "123".to_string(), ids: vec![Some(0), None] };
+        assert_eq!(decode(&dense), Err(ParseError::new("the file at 3 has no id, but it isn't empty")));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut disk = DiskMap::parse(EXAMPLE).unwrap();
+        assert_eq!(render(disk.blocks()), "00...111...2...333.44.5555.6666.777.888899");
+
+        disk.compact(&FirstFit);
+        assert_eq!(render(disk.blocks()), "00992111777.44.333....5555.6666.....8888..");
+
+        let mut disk = DiskMap::parse(EXAMPLE).unwrap();
+        disk.compact(&BlockCompaction);
+        assert_eq!(render(disk.blocks()), "0099811188827773336446555566..............");
+
+        // ids 0 to 11, each one block long with one free block after it
+        let disk = DiskMap::parse(&"11".repeat(12)).unwrap();
+        assert!(render(disk.blocks()).starts_with("(0).(1).(2)."));
+        assert!(render(disk.blocks()).ends_with("(9).(10).(11)."));
+    }
+}
@@ -0,0 +1,178 @@
+use crate::part1::{Block, NumericBlockType};
+
+/// How much free space there is in a stretch of the disk, and where.
+#[derive(Debug, Default, Copy, Clone)]
+struct Summary {
+    /// Length of the stretch.
+    length: u32,
+    /// Free space right at the start of the stretch.
+    prefix: u32,
+    /// Free space right at the end of the stretch.
+    suffix: u32,
+    /// The longest run of free space anywhere in the stretch.
+    longest: u32,
+}
+
+impl Summary {
+    fn slot(length: u32, free: u32) -> Summary {
+        Summary {
+            length,
+            prefix: if free == length { length } else { 0 },
+            suffix: free,
+            longest: free,
+        }
+    }
+
+    fn is_free(&self) -> bool {
+        self.prefix == self.length
+    }
+
+    fn join(left: Summary, right: Summary) -> Summary {
+        Summary {
+            length: left.length + right.length,
+            prefix: if left.is_free() { left.length + right.prefix } else { left.prefix },
+            suffix: if right.is_free() { right.length + left.suffix } else { right.suffix },
+            longest: left.longest.max(right.longest).max(left.suffix + right.prefix),
+        }
+    }
+}
+
+/// The free space on a disk, as a segment tree over the blocks the disk started out with, so the
+/// leftmost run of free space of at least some length is a walk down the tree away instead of a
+/// walk along the whole disk.
+///
+/// Files only ever go into the start of a run of free space, and a block only ever gets freed all
+/// at once, so the free space in a block is always at the end of it.
+pub(crate) struct FreeSpace {
+    leaves: usize,
+    nodes: Vec<Summary>,
+    /// Where each block starts and ends, and how much free space is at the end of it.
+    starts: Vec<u32>,
+    ends: Vec<u32>,
+    free: Vec<u32>,
+}
+
+impl FreeSpace {
+    pub(crate) fn new(blocks: &[Block]) -> FreeSpace {
+        let leaves = blocks.len().next_power_of_two();
+        let mut nodes = vec![Summary::default(); 2 * leaves];
+
+        let free = blocks.iter()
+            .map(|b| match b.block_type {
+                NumericBlockType::Space => b.length,
+                NumericBlockType::Data(_) => 0,
+            })
+            .collect::<Vec<u32>>();
+
+        for (i, block) in blocks.iter().enumerate() {
+            nodes[leaves + i] = Summary::slot(block.length, free[i]);
+        }
+        for node in (1..leaves).rev() {
+            nodes[node] = Summary::join(nodes[2 * node], nodes[2 * node + 1]);
+        }
+
+        FreeSpace {
+            leaves,
+            nodes,
+            starts: blocks.iter().map(|b| b.start).collect(),
+            ends: blocks.iter().map(|b| b.start + b.length).collect(),
+            free,
+        }
+    }
+
+    fn update(&mut self, slot: usize) {
+        let length = self.ends[slot] - self.starts[slot];
+        let mut node = self.leaves + slot;
+        self.nodes[node] = Summary::slot(length, self.free[slot]);
+
+        while node > 1 {
+            node /= 2;
+            self.nodes[node] = Summary::join(self.nodes[2 * node], self.nodes[2 * node + 1]);
+        }
+    }
+
+    /// Where the leftmost run of at least `length` free blocks starts.
+    pub(crate) fn first_fit(&self, length: u32) -> Option<u32> {
+        if self.nodes[1].longest < length {
+            return None;
+        }
+
+        let (mut node, mut start) = (1, self.starts.first().copied().unwrap_or(0));
+        while node < self.leaves {
+            let (left, right) = (self.nodes[2 * node], self.nodes[2 * node + 1]);
+
+            if left.longest >= length {
+                node *= 2;
+            } else if left.suffix + right.prefix >= length {
+                return Some(start + left.length - left.suffix);
+            } else {
+                node = 2 * node + 1;
+                start += left.length;
+            }
+        }
+
+        Some(start + self.nodes[node].length - self.nodes[node].suffix)
+    }
+
+    /// Fills `length` blocks from `start` on, which has to be the start of a run of free space at
+    /// least that long.
+    pub(crate) fn take(&mut self, start: u32, mut length: u32) {
+        let mut slot = self.ends.partition_point(|&end| end <= start);
+
+        while length > 0 {
+            let taken = length.min(self.free[slot]);
+            self.free[slot] -= taken;
+            length -= taken;
+
+            self.update(slot);
+            slot += 1;
+        }
+    }
+
+    /// Frees the whole of the block the disk started out with at `slot`.
+    pub(crate) fn release(&mut self, slot: usize) {
+        self.free[slot] = self.ends[slot] - self.starts[slot];
+
+        self.update(slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(block_type: NumericBlockType, start: u32, length: u32) -> Block {
+        Block { block_type, start, length }
+    }
+
+    #[test]
+    fn test_first_fit() {
+        // 00...1.22..
+        let mut free = FreeSpace::new(&[
+            block(NumericBlockType::Data(0), 0, 2),
+            block(NumericBlockType::Space, 2, 3),
+            block(NumericBlockType::Data(1), 5, 1),
+            block(NumericBlockType::Space, 6, 1),
+            block(NumericBlockType::Data(2), 7, 2),
+            block(NumericBlockType::Space, 9, 2),
+        ]);
+
+        assert_eq!(free.first_fit(1), Some(2));
+        assert_eq!(free.first_fit(3), Some(2));
+        assert_eq!(free.first_fit(4), None);
+
+        // 0011.1.22..
+        free.take(2, 2);
+        assert_eq!(free.first_fit(2), Some(9));
+
+        // 0011...22.. once the 1 that was there is gone, and the free space on either side joins up
+        free.release(2);
+        assert_eq!(free.first_fit(3), Some(4));
+        assert_eq!(free.first_fit(4), None);
+
+        // 00111112222
+        free.take(4, 3);
+        free.take(9, 2);
+        assert_eq!(free.first_fit(1), None);
+    }
+}
@@ -1,19 +1,56 @@
+mod disk;
+mod encode;
+mod free_space;
 mod part1;
 mod part2;
 
-pub fn solve_part1_example() -> u64 {
-    part1::solve_swap(include_str!("../example.txt"))
-}
+use common::{Answer, ParseError, Solution, SolutionError};
+
+pub use encode::{decode, encode, render, Dense};
+pub use disk::{BestFit, BlockCompaction, Defrag, DiskMap, FirstFit, Fragmentation, Report, Strategy};
+pub use part1::{blocks_checksum, disk_checksum, Block, NumericBlockType};
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        part1::Day09::new(input).map(|_| ())
+    }
 
-pub fn solve_part1() -> u64 {
-    part1::solve_swap(include_str!("../input.txt"))
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input)?.into())
+    }
+
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input)?.into())
+    }
 }
 
-pub fn solve_part2_example() -> u64 {
-    part2::solve(include_str!("../example.txt"))
+pub fn solve_part1(input: &str) -> Result<u64, SolutionError> {
+    part1::answer(DiskMap::parse(input)?.compact(&BlockCompaction).checksum)
 }
 
-pub fn solve_part2() -> u64 {
-    part2::solve(include_str!("../input.txt"))
+pub fn solve_part2(input: &str) -> Result<u64, SolutionError> {
+    part2::solve(input)
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(solve_part1("2333133121414131402\n"), Ok(1928));
+        assert_eq!(solve_part1("23331x3121414131402").err(), Some(ParseError::at(1, 6, "expected a digit, found 'x'").into()));
+        assert_eq!(solve_part2("\n").err(), Some(ParseError::new("the disk map is empty").into()));
+    }
+
+    #[test]
+    fn test_no_data() {
+        // files of no length, and nothing after them
+        for input in ["0", "0\n", "00", "000", "0909"] {
+            assert_eq!(solve_part1(input), Ok(0), "{}", input);
+            assert_eq!(solve_part2(input), Ok(0), "{}", input);
+        }
+    }
+}
@@ -1,7 +1,9 @@
-use crate::part1::{disk_checksum, Block, NumericBlockType};
+use common::SolutionError;
+use crate::free_space::FreeSpace;
+use crate::part1::{answer, blocks_checksum, Block, NumericBlockType};
 
-pub(crate) fn solve(data: &str) -> u64 {
-    let day = crate::part1::Day09::new(data);
+pub(crate) fn solve(data: &str) -> Result<u64, SolutionError> {
+    let day = crate::part1::Day09::new(data)?;
 
     let mut all_blocks: Vec<Block> = Vec::new();
     for (i, space_block) in day.space_blocks.iter().enumerate() {
@@ -9,176 +11,85 @@ pub(crate) fn solve(data: &str) -> u64 {
         all_blocks.push(*space_block);
     }
 
-    // println!("all blocks: {:?}", all_blocks);
+    compact_whole_files(&mut all_blocks);
 
-    compact_full_files(&mut all_blocks);
-
-    let mut disk: Vec<NumericBlockType> = Vec::new();
-    for data in all_blocks {
-        for _ in 0..data.length {
-            disk.push(data.block_type);
-        }
-    }
-
-    // println!("this is disk: {:?}", disk);
-    disk_checksum(&disk)
+    answer(blocks_checksum(&all_blocks))
 }
 
-fn compact_full_files(blocks: &mut Vec<Block>) {
-    let last_idx = blocks.len()-1;
-
-    // Need this allow, because without declaring the variable, I can't set it in the match
-    // following, and without setting a value, rust will complain that it might not be initialised.
-    #[allow(unused_assignments)]
-    let mut last_id: u32 = 0;
-
-    let last_block_type = blocks.get(last_idx-1).unwrap().block_type;
-    match last_block_type {
-        NumericBlockType::Space => {
-            panic!("len-2 element was a space")
+/// Gives every piece of every file one go at moving into the leftmost run of free space to the left
+/// of it that it fits in, highest id first, and the piece furthest to the right first out of the
+/// pieces of the same file. Free space left behind joins up with the free space around it, so
+/// pieces that go after can use it. Returns how many pieces moved.
+///
+/// The blocks have to be in order, and the ones that come back are as well, without empty ones or
+/// free space next to more free space.
+pub(crate) fn compact_whole_files(blocks: &mut Vec<Block>) -> usize {
+    let mut free = FreeSpace::new(blocks);
+
+    let mut pieces = blocks.iter()
+        .enumerate()
+        .filter_map(|(slot, b)| match b.block_type {
+            NumericBlockType::Data(id) if b.length > 0 => Some((id, slot)),
+            _ => None,
+        })
+        .collect::<Vec<(u32, usize)>>();
+    pieces.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut files = Vec::with_capacity(pieces.len());
+    let mut moves = 0;
+
+    for (_, slot) in pieces {
+        let mut file = blocks[slot];
+
+        if let Some(start) = free.first_fit(file.length).filter(|&start| start < file.start) {
+            free.take(start, file.length);
+            free.release(slot);
+
+            file.start = start;
+            moves += 1;
         }
-        NumericBlockType::Data(i) => {
-            last_id = i;
-        }
-    }
 
-    if last_id == 0 {
-        panic!("last data is 0");
+        files.push(file);
     }
 
+    files.sort_unstable_by_key(|b| b.start);
+    *blocks = with_free_space(blocks, files);
 
-    while last_id > 0 {
-        // println!("this is last id: {}", last_id);
-        find_space_for_data_id(blocks, last_id);
-        // println!("last idx: {}, block: {:?}", last_idx, blocks[last_idx]);
-        last_id -= 1;
-    }
+    moves
 }
 
-fn find_space_for_data_id(blocks: &mut Vec<Block>, _data_id: u32) {
-
-    blocks.push(Block{
-        block_type: NumericBlockType::Space,
-        start: 0,
-        length: 0,
-    });
-
-    // println!("incoming blocks vec is\n{:?}", blocks);
-
-    // filter out all blocks that are length 0, we do not need them.
-    blocks.retain(|&b| b.length != 0);
-    let mut length_needed = 0;
-    let mut data_start = 0;
-
-    // println!("incoming blocks filtered (retained):\n{:?}\n", blocks);
-    // println!("starting the search for the next data block to replace. Incoming data ID is {}, so we need to move that, or a data block that has a lower ID", _data_id);
-
-    // find the last data we have with id _data_id.
-    let mut data_idx = blocks.len() - 1;
-    // println!("starting the search at index {} in the new block", data_idx);
-    while data_idx > 0 {
-        // println!("-- while loop start at data idx {}", data_idx);
-        match blocks[data_idx].block_type {
-            NumericBlockType::Space => {
-                // println!("-- X block was a space, decrementing idx and continuing");
-                data_idx -= 1;
-            }
-            NumericBlockType::Data(id) => {
-                if id != _data_id {
-                    // println!("-- P block was data, but its id {} is too big, we're looking for {}. Decrementing and continuing.", id, _data_id);
-                    data_idx -= 1;
-                    continue;
-                }
+/// The files, with free space in between them wherever there's room, to fill the same bit of the
+/// disk the blocks were on.
+fn with_free_space(blocks: &[Block], files: Vec<Block>) -> Vec<Block> {
+    let (Some(first), Some(last)) = (blocks.first(), blocks.last()) else {
+        return Vec::new();
+    };
 
-                // store the length needed for the space, and where this data starts, so we can
-                // figure out whether a space is big enough, and whether it's to the left of it.
-                length_needed = blocks[data_idx].length;
-                data_start = blocks[data_idx].start;
+    let mut filled = Vec::with_capacity(2 * files.len() + 1);
+    let mut offset = first.start;
 
-                // println!("found the data block: {:?} at index {}, set length needed to {} and data start to {}",
-                //          blocks[data_idx], data_idx, length_needed, data_start );
-                break;
-            }
+    for file in files.into_iter().chain([Block { block_type: NumericBlockType::Space, start: last.start + last.length, length: 0 }]) {
+        if file.start > offset {
+            filled.push(Block { block_type: NumericBlockType::Space, start: offset, length: file.start - offset });
         }
-    }
-
-    // println!("\nNext up let's find the space that's big enough to take our data");
-    for (i, &block) in blocks.iter().enumerate() {
-        // println!("-- i {}", i);
-        match block.block_type {
-            NumericBlockType::Space => {
-                // println!("-- Ok: this is a space");
-                if block.length < length_needed {
-                    // println!("-- X: its length {} is too short for what we need {}", block.length, length_needed);
-                    continue;
-                }
 
-                if block.start > data_start {
-                    // println!("-- X: its start {} is too late for what we need {}, returning", block.start, data_start);
-                    return;
-                }
-
-                // println!("-- Ok: found the block! {:?}", block);
-
-                let replacement_space = vec![Block {
-                    block_type: NumericBlockType::Space,
-                    length: length_needed,
-                    start: data_start,
-                }];
-
-                // println!("created a replacement space: {:?}", replacement_space);
-                // println!("about to replace the data block we found with the space. The incoming blocks vec is\n{:?}", blocks);
-                // replace the data from the end with an equal sized space
-                let _replaced_block = blocks
-                    .splice(data_idx..data_idx + 1, replacement_space)
-                    .collect::<Vec<Block>>();
-
-                // println!("replaced the data at idx with the replacement space\n{:?}", blocks);
-
-                let moved_data = Block {
-                    block_type: NumericBlockType::Data(_data_id),
-                    start: block.start,
-                    length: length_needed,
-                };
-
-                // println!("-- created a new data block for the same data: {:?}", moved_data);
-
-                let diff = block.length - length_needed;
-                let extra_space = Block {
-                    block_type: NumericBlockType::Space,
-                    length: diff,
-                    start: block.start + length_needed
-                };
-
-                // println!("-- also created an extra space block so we can replace the space fully: {:?}", extra_space);
-                // println!("-- for context, need to replace space length {} with data len {} + extra space len {}",
-                // block.length, length_needed, diff);
-
-                // println!("this is the vec we're doing a splice on\n{:?}", blocks);
-                // println!("it has {} len and we're doing the range {}..{}", blocks.len(), i, i+1);
-
-                let replacement = vec![moved_data, extra_space];
-
-                let _replaced_space = blocks
-                    .splice(i..i+1, replacement).collect::<Vec<Block>>();
-
-                // println!("replacement supposedly done, whit is new_blocks?\n{:?}", blocks);
-                return;
-            }
-            NumericBlockType::Data(_) => {
-                // println!("-- X block was a data block, we're not messing with it");
-                continue;
-            }
+        offset = file.start + file.length;
+        if file.length > 0 {
+            filled.push(file);
         }
     }
+
+    filled
 }
 
 #[cfg(test)]
 mod tests {
+    use std::cmp::Reverse;
+    use std::collections::BinaryHeap;
     use super::*;
 
     #[test]
-    fn test_find_space_for_data_id() {
+    fn test_compact_whole_files() {
         let mut blocks = vec![
             Block{
                 block_type: NumericBlockType::Data(0),
@@ -211,17 +122,94 @@ mod tests {
             Block{
                 block_type: NumericBlockType::Space,
                 start: 5,
-                length: 2,
-            },
-            Block{
-                block_type: NumericBlockType::Space,
-                start: 7,
-                length: 3,
+                length: 5,
             },
         ];
 
-        find_space_for_data_id(&mut blocks, 1);
-
+        assert_eq!(compact_whole_files(&mut blocks), 1);
         assert_eq!(want, blocks);
     }
-}
\ No newline at end of file
+
+    /// Part 2 the way it's usually done, for a disk straight from a dense map: with the free space
+    /// kept in a heap for each length it can be, since files only ever move left, and the space
+    /// they leave behind is always to the right of every file still to go. The checksum doesn't
+    /// have to fit in 64 bits.
+    fn first_fit_by_heaps(input: &str) -> u128 {
+        let mut heaps: Vec<BinaryHeap<Reverse<u32>>> = vec![BinaryHeap::new(); 10];
+        let mut files = Vec::new();
+        let mut offset = 0;
+
+        for (i, length) in input.trim_end().chars().map(|c| c.to_digit(10).unwrap()).enumerate() {
+            match i.is_multiple_of(2) {
+                true => files.push((offset, length)),
+                false => heaps[length as usize].push(Reverse(offset)),
+            }
+            offset += length;
+        }
+
+        let mut checksum = 0;
+        for (id, &(start, length)) in files.iter().enumerate().rev() {
+            let leftmost = (length as usize..10)
+                .filter_map(|l| heaps[l].peek().map(|&Reverse(s)| (s, l)))
+                .filter(|&(s, _)| s < start)
+                .min();
+
+            let start = match leftmost {
+                None => start,
+                Some((s, l)) => {
+                    heaps[l].pop();
+                    heaps[l - length as usize].push(Reverse(s + length));
+                    s
+                }
+            };
+
+            checksum += (start..start + length).map(|p| p as u128 * id as u128).sum::<u128>();
+        }
+
+        checksum
+    }
+
+    #[test]
+    fn test_first_fit_by_heaps() {
+        assert_eq!(first_fit_by_heaps(include_str!("../example.txt")), 2858);
+        assert_eq!(first_fit_by_heaps(include_str!("../input.txt")), 6323761685944);
+    }
+
+    /// The input over and over, with a gap of 9 in between each copy.
+    fn repeated(times: usize) -> String {
+        vec![include_str!("../input.txt").trim_end(); times].join("9")
+    }
+
+    #[test]
+    fn test_large_disk() {
+        let large = repeated(20);
+
+        let mut blocks = crate::DiskMap::parse(&large).unwrap().blocks().to_vec();
+        let size = blocks.iter().map(|b| b.length).sum::<u32>();
+        compact_whole_files(&mut blocks);
+
+        assert_eq!(blocks.iter().map(|b| b.length).sum::<u32>(), size);
+        assert!(blocks.windows(2).all(|w| w[0].start + w[0].length == w[1].start));
+        assert_eq!(blocks_checksum(&blocks).map(u128::from), Some(first_fit_by_heaps(&large)));
+    }
+
+    /// Slow without optimisations, run it with `cargo test --release -p day09 -- --ignored`.
+    #[test]
+    #[ignore]
+    fn test_checksum_too_big() {
+        // the checksum grows with the cube of the size of the disk, so by now it needs more than
+        // 64 bits
+        let large = repeated(200);
+        let want = first_fit_by_heaps(&large);
+        assert!(want > u64::MAX as u128);
+
+        let mut disk = crate::DiskMap::parse(&large).unwrap();
+        assert_eq!(disk.compact(&crate::FirstFit).checksum, None);
+        // the disk map is fine, it's the answer that doesn't fit
+        assert!(matches!(solve(&large), Err(SolutionError::Solve(_))));
+
+        // at half the size it still fits, and is right
+        let large = repeated(100);
+        assert_eq!(solve(&large).map(u128::from), Ok(first_fit_by_heaps(&large)));
+    }
+}
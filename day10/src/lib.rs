@@ -1,24 +1,28 @@
 mod part1;
 mod part2;
 
-pub fn solve_part1_example() -> usize {
-    let input = include_str!("../example.txt");
+use common::{Answer, ParseError, Solution, SolutionError};
 
-    part1::solve(input)
-}
+pub struct Day10;
 
-pub fn solve_part1() -> usize {
-    let input = include_str!("../input.txt");
+impl Solution for Day10 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        part1::Day10::new(input).map(|_| ())
+    }
 
-    part1::solve(input)
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input)?.into())
+    }
+
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input)?.into())
+    }
 }
 
-pub fn solve_part2_example() -> usize {
-    let input = include_str!("../example.txt");
-    part2::solve(input)
+pub fn solve_part1(input: &str) -> Result<usize, ParseError> {
+    part1::solve(input)
 }
 
-pub fn solve_part2() -> usize {
-    let input = include_str!("../input.txt");
+pub fn solve_part2(input: &str) -> Result<usize, ParseError> {
     part2::solve(input)
-}
\ No newline at end of file
+}
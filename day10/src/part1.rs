@@ -1,69 +1,22 @@
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
-
-#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
-pub (crate) struct Coordinate {
-    height: usize,
-    width: usize,
-}
-
-impl Coordinate {
-    pub fn new(height: usize, width: usize) -> Self {
-        Self { height, width }
-    }
-
-    pub fn neighbours(&self) -> Vec<Self> {
-        let mut n = Vec::new();
-        match self.height.cmp(&0) {
-            Ordering::Less => { panic!("coordinate should not have negative height as unsigned number!") }
-            Ordering::Equal => {
-                n.push(Self::new(self.height+1, self.width));
-            }
-            Ordering::Greater => {
-                n.push(Self::new(self.height+1, self.width));
-                n.push(Self::new(self.height-1, self.width));
-            }
-        }
-
-        match self.width.cmp(&0) {
-            Ordering::Less => { panic!("coordinate should not have negative width as unsigned number!") }
-            Ordering::Equal => {
-                n.push(Self::new(self.height, self.width+1));
-            }
-            Ordering::Greater => {
-                n.push(Self::new(self.height, self.width-1));
-                n.push(Self::new(self.height, self.width+1));
-            }
-        }
-
-        n
-    }
-}
-
+use std::collections::HashSet;
+use common::ParseError;
+use grid::{Coordinate, Grid};
 
 pub (crate) struct Day10 {
-    map: HashMap<Coordinate, u8>,
+    map: Grid<u8>,
     trailheads: Vec<Coordinate>
 }
 
 impl Day10 {
-    pub fn new(data: &str) -> Self {
-        let mut map: HashMap<Coordinate, u8> = HashMap::new();
-        let mut trailheads = Vec::new();
-
-        for (height, line) in data.trim().lines().enumerate() {
-            for (width, character) in line.trim().chars().enumerate() {
-                let h = u8::try_from(character.to_digit(10).unwrap()).unwrap();
-                let c = Coordinate{height, width};
-                map.insert(c, h);
-
-                if h == 0 {
-                    trailheads.push(c);
-                }
-            }
-        }
-
-        Day10 { map, trailheads }
+    pub fn new(data: &str) -> Result<Self, ParseError> {
+        let map = Grid::parse(data, |character| character.to_digit(10).map(|h| h as u8))?;
+        let trailheads = map.iter()
+            .filter(|(_, h)| **h == 0)
+            .map(|(c, _)| c)
+            .collect();
+
+        Ok(Day10 { map, trailheads })
     }
 
     pub(crate) fn find_trails(&self) -> Vec<Vec<Coordinate>> {
@@ -91,7 +44,7 @@ impl Day10 {
     }
 
     fn trail_next(&self, current: Coordinate, mut trail_so_far: Vec<Coordinate>) -> Vec<Vec<Coordinate>> {
-        let v = self.map.get(&current).unwrap_or(&10);
+        let v = self.map.get(current).unwrap_or(&10);
         trail_so_far.push(current);
 
         // println!("== Starting trail_next block with current {} @ {:?}  ==", v, current);
@@ -110,8 +63,8 @@ impl Day10 {
             Ordering::Less => {
                 let mut new_trails: Vec<Vec<Coordinate>> = Vec::new();
                 // println!("  -- for each neighbours:");
-                for c in current.neighbours() {
-                    let n = self.map.get(&c).unwrap_or(&10);
+                for c in self.map.neighbours(current) {
+                    let n = &self.map[c];
                     // println!("    -- value {} @ {:?}", n, c);
 
                     match n.cmp(&(v + 1)) {
@@ -134,10 +87,10 @@ impl Day10 {
     }
 }
 
-pub(crate) fn solve(data: &str) -> usize {
-    let d10 = Day10::new(data);
+pub(crate) fn solve(data: &str) -> Result<usize, ParseError> {
+    let d10 = Day10::new(data)?;
 
-    d10.find_score()
+    Ok(d10.find_score())
 }
 
 fn find_nines(trails: Vec<Vec<Coordinate>>) -> usize {
@@ -150,41 +103,23 @@ fn find_nines(trails: Vec<Vec<Coordinate>>) -> usize {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_coordinate() {
-        let c = Coordinate::new(5, 3);
-        assert_eq!(c.neighbours().len(), 4);
-        assert_eq!(c.neighbours(), vec![
-            Coordinate::new(6, 3),
-            Coordinate::new(4, 3),
-            Coordinate::new(5, 2),
-            Coordinate::new(5, 4),
-        ]);
-
-        let c0 = Coordinate::new(0, 0);
-        assert_eq!(c0.neighbours().len(), 2);
-        assert_eq!(c0.neighbours(), vec![
-            Coordinate::new(1, 0),
-            Coordinate::new(0, 1),
-        ]);
-    }
-
     #[test]
     fn test_find_trails() {
         let input = "0123456789";
-        let d = Day10::new(input);
+        let d = Day10::new(input).unwrap();
 
+        // neighbours are tried clockwise starting with up, so that's the order trails come in
         assert_eq!(d.find_trails(), vec![vec![
             Coordinate::new(0, 0),
-            Coordinate::new(0, 1),
-            Coordinate::new(0, 2),
-            Coordinate::new(0, 3),
-            Coordinate::new(0, 4),
-            Coordinate::new(0, 5),
-            Coordinate::new(0, 6),
-            Coordinate::new(0, 7),
-            Coordinate::new(0, 8),
-            Coordinate::new(0, 9),
+            Coordinate::new(1, 0),
+            Coordinate::new(2, 0),
+            Coordinate::new(3, 0),
+            Coordinate::new(4, 0),
+            Coordinate::new(5, 0),
+            Coordinate::new(6, 0),
+            Coordinate::new(7, 0),
+            Coordinate::new(8, 0),
+            Coordinate::new(9, 0),
         ]]);
     }
 
@@ -195,34 +130,34 @@ mod tests {
         8343522222\n\
         1111678922";
 
-        let d = Day10::new(input);
+        let d = Day10::new(input).unwrap();
 
+        // neighbours are tried clockwise starting with up, so that's the order trails come in
         assert_eq!(d.find_trails(), vec![
             vec![
                 Coordinate::new(0, 0), // 0
-                Coordinate::new(0, 1), // 1
-                Coordinate::new(0, 2), // 2
-                Coordinate::new(0, 3), // 3
-                Coordinate::new(0, 4), // 4
-                Coordinate::new(1, 4), // 5
-                Coordinate::new(2, 4),
-                Coordinate::new(2, 5),
-                Coordinate::new(2, 6),
-                Coordinate::new(2, 7),
+                Coordinate::new(1, 0), // 1
+                Coordinate::new(2, 0), // 2
+                Coordinate::new(3, 0), // 3
+                Coordinate::new(4, 0), // 4
+                Coordinate::new(5, 0), // 5
+                Coordinate::new(6, 0),
+                Coordinate::new(7, 0),
+                Coordinate::new(8, 0),
+                Coordinate::new(9, 0),
             ],
             vec![
                 Coordinate::new(0, 0), // 0
-                Coordinate::new(0, 1), // 1
-                Coordinate::new(0, 2), // 2
-                Coordinate::new(0, 3), // 3
-                Coordinate::new(0, 4), // 4
-                Coordinate::new(0, 5), // 5
-                Coordinate::new(0, 6),
-                Coordinate::new(0, 7),
-                Coordinate::new(0, 8),
-                Coordinate::new(0, 9),
+                Coordinate::new(1, 0), // 1
+                Coordinate::new(2, 0), // 2
+                Coordinate::new(3, 0), // 3
+                Coordinate::new(4, 0), // 4
+                Coordinate::new(4, 1), // 5
+                Coordinate::new(4, 2),
+                Coordinate::new(5, 2),
+                Coordinate::new(6, 2),
+                Coordinate::new(7, 2),
             ],
-
         ]);
     }
 
@@ -233,32 +168,33 @@ mod tests {
         0129678922\n\
         9934522222";
 
-        let d = Day10::new(input);
+        let d = Day10::new(input).unwrap();
 
+        // neighbours are tried clockwise starting with up, so that's the order trails come in
         assert_eq!(d.find_trails(), vec![
             vec![
-                Coordinate::new(1, 0), // 0
+                Coordinate::new(0, 1), // 0
                 Coordinate::new(1, 1), // 1
-                Coordinate::new(1, 2), // 2
-                Coordinate::new(2, 2), // 3
-                Coordinate::new(2, 3), // 4
-                Coordinate::new(2, 4), // 5
-                Coordinate::new(1, 4), // 6
-                Coordinate::new(1, 5),
-                Coordinate::new(1, 6),
-                Coordinate::new(1, 7),
+                Coordinate::new(2, 1), // 2
+                Coordinate::new(2, 0), // 3
+                Coordinate::new(3, 0), // 4
+                Coordinate::new(4, 0), // 5
+                Coordinate::new(4, 1), // 6
+                Coordinate::new(5, 1),
+                Coordinate::new(6, 1),
+                Coordinate::new(7, 1),
             ],
             vec![
-                Coordinate::new(1, 0), // 0
+                Coordinate::new(0, 1), // 0
                 Coordinate::new(1, 1), // 1
-                Coordinate::new(1, 2), // 2
-                Coordinate::new(0, 2), // 3
-                Coordinate::new(0, 3), // 4
-                Coordinate::new(0, 4), // 5
-                Coordinate::new(1, 4), // 6
-                Coordinate::new(1, 5),
-                Coordinate::new(1, 6),
-                Coordinate::new(1, 7),
+                Coordinate::new(2, 1), // 2
+                Coordinate::new(2, 2), // 3
+                Coordinate::new(3, 2), // 4
+                Coordinate::new(4, 2), // 5
+                Coordinate::new(4, 1), // 6
+                Coordinate::new(5, 1),
+                Coordinate::new(6, 1),
+                Coordinate::new(7, 1),
             ],
         ]);
     }
@@ -275,9 +211,19 @@ mod tests {
 1119662\n\
 3333301";
 
-        let d = Day10::new(input);
+        let d = Day10::new(input).unwrap();
         let trails = d.find_trails();
 
         assert_eq!(trails.len(), 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_new_bad_height() {
+        let input = "0123\n1.34\n";
+
+        assert_eq!(
+            Day10::new(input).err(),
+            Some(ParseError::at(2, 2, "unexpected character '.' on the map"))
+        );
+    }
+}
@@ -1,9 +1,10 @@
+use common::ParseError;
 use crate::part1::Day10;
 
-pub(crate) fn solve(input: &str) -> usize {
-    let d10 = Day10::new(input);
+pub(crate) fn solve(input: &str) -> Result<usize, ParseError> {
+    let d10 = Day10::new(input)?;
 
     let trails = d10.find_trails();
 
-    trails.len()
+    Ok(trails.len())
 }
\ No newline at end of file
@@ -0,0 +1,84 @@
+//! Counts the stones after any number of blinks, by the puzzle's rules or any others.
+//!
+//!     cargo run --release -p day11 --example blink -- day11/input.txt 25,75,500
+//!     cargo run --release -p day11 --example blink -- day11/input.txt 40 --preset binary
+//!     cargo run --release -p day11 --example blink -- day11/example.txt 25 --rules "0 -> 1; split 3; * 7"
+
+use std::process::ExitCode;
+use day11::{counts_after, parse_stones, Rules, PRESETS};
+
+const USAGE: &str = "\
+Usage: blink <INPUT> <BLINKS>[,<BLINKS>...] [--preset NAME | --rules RULES]
+
+Counts the stones after each number of blinks, by the puzzle's rules, or the preset or the rules
+given. Rules go one after the other, separated by ';', and every stone goes by the first one that
+applies to it:
+
+    0 -> 1          a stone with a 0 on it turns into a stone with a 1 on it
+    7 -> 1 2 3      or into more than one stone
+    split 2         a stone with an even number of digits splits in half
+    split 3 base 2  a stone with a multiple of 3 binary digits splits in three
+    * 2024          the number on the stone is multiplied by 2024";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let (input, blinks, rules) = match &args[..] {
+        [input, blinks] => (input, blinks, Ok(Rules::puzzle())),
+        [input, blinks, flag, name] if flag == "--preset" => {
+            let Some(rules) = Rules::preset(name) else {
+                let names = PRESETS.iter().map(|(n, _)| *n).collect::<Vec<&str>>();
+                eprintln!("There is no preset called {}, there's {}", name, names.join(", "));
+                return ExitCode::from(2);
+            };
+
+            (input, blinks, Ok(rules))
+        }
+        [input, blinks, flag, spec] if flag == "--rules" => (input, blinks, Rules::parse(spec).map_err(|e| e.diagnostic(spec))),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let Ok(blinks) = blinks.split(',').map(str::parse::<usize>).collect::<Result<Vec<usize>, _>>() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let rules = match rules {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Could not read the rules: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    let text = match std::fs::read_to_string(input) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Could not read {}: {}", input, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let stones = match parse_stones(&text) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Could not parse {}: {}", input, e.diagnostic(&text));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut code = ExitCode::SUCCESS;
+    for (count, b) in counts_after(&stones, &rules, &blinks).into_iter().zip(blinks) {
+        match count {
+            Ok(count) => println!("{}: {}", b, count),
+            Err(e) => {
+                println!("{}: {}", b, e);
+                code = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    code
+}
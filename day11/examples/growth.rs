@@ -0,0 +1,68 @@
+//! Writes how the stones grow, blink by blink, as CSV, for plotting.
+//!
+//!     cargo run --release -p day11 --example growth -- day11/input.txt 100 > growth.csv
+//!     cargo run --release -p day11 --example growth -- day11/example.txt 60 --rules "0 -> 1; split 3; * 7"
+
+use std::process::ExitCode;
+use day11::{growth, parse_stones, Rules};
+
+const USAGE: &str = "\
+Usage: growth <INPUT> <BLINKS> [--preset NAME | --rules RULES]
+
+Blinks up to BLINKS times by the puzzle's rules, or the preset or the rules given the same way the
+blink example takes them. Writes how many stones there are, how many different numbers are on them,
+the biggest one, and how many different numbers there have been so far, after each blink, as CSV.
+The blink after which there stop being new numbers, and anything that got too big to carry on, go
+to stderr.";
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    let (input, blinks, rules) = match &args[..] {
+        [input, blinks] => (input, blinks, Some(Rules::puzzle())),
+        [input, blinks, flag, name] if flag == "--preset" => (input, blinks, Rules::preset(name)),
+        [input, blinks, flag, spec] if flag == "--rules" => match Rules::parse(spec) {
+            Ok(rules) => (input, blinks, Some(rules)),
+            Err(e) => {
+                eprintln!("Could not read the rules: {}", e.diagnostic(spec));
+                return ExitCode::from(2);
+            }
+        },
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let (Ok(blinks), Some(rules)) = (blinks.parse::<usize>(), rules) else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let text = match std::fs::read_to_string(input) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Could not read {}: {}", input, e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let stones = match parse_stones(&text) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Could not parse {}: {}", input, e.diagnostic(&text));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let growth = growth(&stones, &rules, blinks);
+    print!("{}", growth.to_csv());
+
+    if let Some(closure) = growth.closure {
+        eprintln!("No new numbers from blink {} on, there are {} in all", closure, growth.stats[closure].seen);
+    }
+    if let Some(overflow) = growth.overflow {
+        eprintln!("Stopped after blink {}: {}", growth.stats.len() - 1, overflow);
+    }
+
+    ExitCode::SUCCESS
+}
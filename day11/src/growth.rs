@@ -0,0 +1,133 @@
+use std::collections::HashSet;
+use crate::population::Population;
+use crate::rules::{Overflow, Rules};
+
+/// What the stones look like after some number of blinks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BlinkStats {
+    pub blinks: usize,
+    /// How many stones there are.
+    pub total: u64,
+    /// How many different numbers there are on them.
+    pub distinct: usize,
+    /// The biggest number on any of them.
+    pub largest: u64,
+    /// How many different numbers there have been on any stone so far, these ones included.
+    pub seen: usize,
+}
+
+/// How the stones grow, blink by blink.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Growth {
+    /// One for every blink, starting with the stones before any blinking.
+    pub stats: Vec<BlinkStats>,
+    /// The first blink after which there isn't a number on any stone that hadn't been on one
+    /// before. Every number there's been turns into numbers there have been by then, so no blink
+    /// after that has new numbers either: every number the stones can ever have is in `seen`.
+    pub closure: Option<usize>,
+    /// What got too big to keep blinking, if the stones stopped before all the blinks.
+    pub overflow: Option<Overflow>,
+}
+
+/// Blinks up to `blinks` times, and keeps track of what the stones look like after each one.
+pub fn growth(stones: &[u64], rules: &Rules, blinks: usize) -> Growth {
+    let mut population = Population::new(stones);
+    let mut seen: HashSet<u64> = HashSet::new();
+    let mut growth = Growth { stats: Vec::with_capacity(blinks + 1), closure: None, overflow: None };
+
+    loop {
+        let before = seen.len();
+        seen.extend(population.counts().keys());
+        if growth.closure.is_none() && population.blinks() > 0 && seen.len() == before {
+            growth.closure = Some(population.blinks());
+        }
+
+        let total = match population.total() {
+            Ok(total) => total,
+            Err(overflow) => {
+                growth.overflow = Some(overflow);
+                break;
+            }
+        };
+
+        growth.stats.push(BlinkStats {
+            blinks: population.blinks(),
+            total,
+            distinct: population.counts().len(),
+            largest: population.counts().keys().max().copied().unwrap_or(0),
+            seen: seen.len(),
+        });
+
+        if population.blinks() == blinks {
+            break;
+        }
+        if let Err(overflow) = population.blink(rules) {
+            growth.overflow = Some(overflow);
+            break;
+        }
+    }
+
+    growth
+}
+
+impl Growth {
+    /// A header line, then one line per blink.
+    pub fn to_csv(&self) -> String {
+        let mut s = "blinks,total,distinct,largest,seen\n".to_string();
+
+        for b in self.stats.iter() {
+            s += &format!("{},{},{},{},{}\n", b.blinks, b.total, b.distinct, b.largest, b.seen);
+        }
+
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solve_part2;
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let growth = growth(&[125, 17], &Rules::puzzle(), 6);
+
+        assert_eq!(growth.stats.iter().map(|b| b.total).collect::<Vec<u64>>(), vec![2, 3, 4, 5, 9, 13, 22]);
+        // 512 72 2024 2 0 2 4 2867 6032
+        assert_eq!(growth.stats[4], BlinkStats { blinks: 4, total: 9, distinct: 8, largest: 6032, seen: 19 });
+        assert_eq!(growth.stats[6].largest, 2097446912);
+        assert_eq!((growth.closure, growth.overflow), (None, None));
+    }
+
+    #[test]
+    fn test_closure() {
+        let input = include_str!("../input.txt");
+        let stones = crate::parse_stones(input).unwrap();
+        let growth = growth(&stones, &Rules::puzzle(), 500);
+
+        assert_eq!(growth.stats[75].total, solve_part2(input).unwrap());
+        assert_eq!(growth.overflow, Some(Overflow::Count));
+
+        // once there's nothing new, there never is again, and the stones have never had a number
+        // that isn't one of those on them
+        let closure = growth.closure.unwrap();
+        let all = growth.stats[closure].seen;
+        assert!(growth.stats[closure - 2].seen < all);
+        assert!(growth.stats[closure - 1..].iter().all(|b| b.seen == all));
+        assert!(growth.stats.iter().all(|b| b.distinct <= all));
+
+        // without any rules nothing changes, so there's nothing new as soon as they blink
+        assert_eq!(super::growth(&[5], &Rules::new(), 10).closure, Some(1));
+    }
+
+    #[test]
+    fn test_to_csv() {
+        assert_eq!(growth(&[0], &Rules::puzzle(), 3).to_csv(), "\
+blinks,total,distinct,largest,seen
+0,1,1,0,1
+1,1,1,1,2
+2,1,1,2024,3
+3,2,2,24,5
+");
+    }
+}
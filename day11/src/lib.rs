@@ -1,20 +1,72 @@
-mod part1;
-mod part2;
+mod growth;
+mod population;
+mod rules;
 
-pub fn solve_part1_example() -> usize {
-    let input = include_str!("../example.txt");
+use common::{parse_number, Answer, ParseError, Solution, SolutionError};
 
-    part1::solve(input)
+pub use growth::{growth, BlinkStats, Growth};
+pub use population::{counts_after, Population};
+pub use rules::{Memo, Multiply, Overflow, Replace, Rule, Rules, Split, PRESETS};
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        parse_stones(input).map(|_| ())
+    }
+
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input)?.into())
+    }
+
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input)?.into())
+    }
+}
+
+pub fn solve_part1(input: &str) -> Result<u64, SolutionError> {
+    blink_puzzle(input, 25)
+}
+
+pub fn solve_part2(input: &str) -> Result<u64, SolutionError> {
+    blink_puzzle(input, 75)
+}
+
+/// How many stones there are after `blinks` blinks by the puzzle's rules.
+fn blink_puzzle(input: &str, blinks: usize) -> Result<u64, SolutionError> {
+    Ok(Rules::puzzle().stones_after(&parse_stones(input)?, blinks)?)
 }
 
-pub fn solve_part1() -> usize {
-    let input = include_str!("../input.txt");
+/// The numbers engraved on the stones, separated by whitespace.
+pub fn parse_stones(input: &str) -> Result<Vec<u64>, ParseError> {
+    let mut stones = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        for chunk in line.split_whitespace() {
+            stones.push(parse_number(i + 1, line, chunk)?);
+        }
+    }
 
-    part1::solve(input)
+    Ok(stones)
 }
 
-pub fn solve_part2() -> u64 {
-    let input = include_str!("../input.txt");
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    part2::solve(input)
-}
\ No newline at end of file
+    #[test]
+    fn test_parse_stones() {
+        assert_eq!(parse_stones("125 17\n"), Ok(vec![125, 17]));
+        assert_eq!(parse_stones("125 -17"), Err(ParseError::at(1, 5, "expected a number, found '-17'")));
+    }
+
+    #[test]
+    fn test_overflow() {
+        // an odd number of digits, so it gets multiplied by 2024
+        assert_eq!(solve_part1("9999999999999999999"), Err(Overflow::Stone(9999999999999999999).into()));
+
+        // a single 0 is 22938365706844 stones after 75 blinks, so this many of them is just too many
+        assert_eq!(solve_part2(&"0 ".repeat(804_187)), Ok(804_187 * 22938365706844));
+        assert!(matches!(solve_part2(&"0 ".repeat(804_188)), Err(SolutionError::Solve(_))));
+    }
+}
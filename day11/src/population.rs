@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use crate::rules::{Overflow, Rules};
+
+/// The stones as how many there are with each number on them. Stones with the same number on them
+/// all turn into the same thing, so they can all blink at once, and however many blinks it takes
+/// there are only ever as many entries as there are different numbers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Population {
+    counts: HashMap<u64, u64>,
+    blinks: usize,
+}
+
+impl Population {
+    pub fn new(stones: &[u64]) -> Population {
+        let mut counts: HashMap<u64, u64> = HashMap::new();
+        for &stone in stones {
+            *counts.entry(stone).or_default() += 1;
+        }
+
+        Population { counts, blinks: 0 }
+    }
+
+    /// How many times the stones have blinked so far.
+    pub fn blinks(&self) -> usize {
+        self.blinks
+    }
+
+    /// How many stones there are with each number on them.
+    pub fn counts(&self) -> &HashMap<u64, u64> {
+        &self.counts
+    }
+
+    /// How many stones there are.
+    pub fn total(&self) -> Result<u64, Overflow> {
+        self.counts.values().try_fold(0u64, |sum, &count| sum.checked_add(count).ok_or(Overflow::Count))
+    }
+
+    /// Blinks once. If that goes wrong the stones stay the way they were.
+    pub fn blink(&mut self, rules: &Rules) -> Result<(), Overflow> {
+        let mut counts: HashMap<u64, u64> = HashMap::with_capacity(self.counts.len());
+
+        for (&stone, &count) in self.counts.iter() {
+            for next in rules.blink(stone)? {
+                let entry = counts.entry(next).or_default();
+                *entry = entry.checked_add(count).ok_or(Overflow::Count)?;
+            }
+        }
+
+        self.counts = counts;
+        self.blinks += 1;
+
+        Ok(())
+    }
+}
+
+/// How many stones there are after each of the numbers of blinks, all from the one run. Once the
+/// stones can't blink any more without something getting too big, every count after that is the
+/// same error.
+pub fn counts_after(stones: &[u64], rules: &Rules, blinks: &[usize]) -> Vec<Result<u64, Overflow>> {
+    let mut population = Population::new(stones);
+    let mut stuck: Option<Overflow> = None;
+    let mut totals: HashMap<usize, Result<u64, Overflow>> = HashMap::new();
+
+    let mut wanted = blinks.to_vec();
+    wanted.sort_unstable();
+    wanted.dedup();
+
+    for want in wanted {
+        while stuck.is_none() && population.blinks() < want {
+            stuck = population.blink(rules).err();
+        }
+
+        let total = match stuck {
+            Some(overflow) => Err(overflow),
+            None => population.total(),
+        };
+        totals.insert(want, total);
+    }
+
+    blinks.iter().map(|b| totals[b]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{solve_part1, solve_part2};
+    use super::*;
+
+    #[test]
+    fn test_counts_after() {
+        let rules = Rules::puzzle();
+        assert_eq!(counts_after(&[125, 17], &rules, &[6, 0, 25, 6]), vec![Ok(22), Ok(2), Ok(55312), Ok(22)]);
+
+        let input = include_str!("../input.txt");
+        let stones = crate::parse_stones(input).unwrap();
+        assert_eq!(counts_after(&stones, &rules, &[25, 75, 500]), vec![
+            Ok(solve_part1(input).unwrap()),
+            Ok(solve_part2(input).unwrap()),
+            Err(Overflow::Count),
+        ]);
+    }
+
+    #[test]
+    fn test_same_as_memo() {
+        let rules = Rules::parse("0 -> 1; split 3; * 7").unwrap();
+        let mut population = Population::new(&[125, 17, 0]);
+
+        for blinks in 1..=30 {
+            population.blink(&rules).unwrap();
+            assert_eq!(population.total(), rules.stones_after(&[125, 17, 0], blinks));
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        let rules = Rules::parse("* 1000").unwrap();
+        let mut population = Population::new(&[7, 7]);
+
+        for _ in 0..6 {
+            population.blink(&rules).unwrap();
+        }
+        assert_eq!(population.counts(), &HashMap::from([(7_000_000_000_000_000_000, 2)]));
+
+        assert_eq!(population.blink(&rules), Err(Overflow::Stone(7_000_000_000_000_000_000)));
+        assert_eq!(population.blinks(), 6);
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use common::{column_of, parse_number, ParseError, SolutionError};
+
+/// Something that can happen to a stone when you blink.
+pub trait Rule {
+    /// The stones `stone` turns into, or `None` if this rule doesn't apply to it.
+    fn apply(&self, stone: u64) -> Result<Option<Vec<u64>>, Overflow>;
+}
+
+/// A number that got too big for a `u64`, where it would otherwise have wrapped around.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Overflow {
+    /// The number on the stone that was turning into a stone with too big a number on it.
+    Stone(u64),
+    /// There are more stones than there are `u64`s.
+    Count,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Overflow::Stone(n) => write!(f, "the stone with {} on it turns into one with too big a number to fit in 64 bits", n),
+            Overflow::Count => write!(f, "there are too many stones to count in 64 bits"),
+        }
+    }
+}
+
+/// Numbers that don't fit are no fault of the input, they're what happens when it's solved.
+impl From<Overflow> for SolutionError {
+    fn from(overflow: Overflow) -> Self {
+        SolutionError::Solve(overflow.to_string())
+    }
+}
+
+/// A stone with one particular number on it turns into the given stones.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replace {
+    pub from: u64,
+    pub to: Vec<u64>,
+}
+
+/// A stone with a multiple of `parts` digits in `base` splits into `parts` stones, the first with
+/// the first lot of digits on it, and so on. Leading zeroes are dropped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Split {
+    pub parts: u32,
+    pub base: u64,
+}
+
+/// Any stone has its number multiplied by this one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Multiply(pub u64);
+
+impl Rule for Replace {
+    fn apply(&self, stone: u64) -> Result<Option<Vec<u64>>, Overflow> {
+        Ok((stone == self.from).then(|| self.to.clone()))
+    }
+}
+
+/// How many digits `n` has in `base`. Zero has one.
+pub(crate) fn digits(n: u64, base: u64) -> u32 {
+    n.checked_ilog(base).unwrap_or(0) + 1
+}
+
+impl Rule for Split {
+    fn apply(&self, stone: u64) -> Result<Option<Vec<u64>>, Overflow> {
+        let digits = digits(stone, self.base);
+        if !digits.is_multiple_of(self.parts) {
+            return Ok(None);
+        }
+
+        let size = self.base.pow(digits / self.parts);
+        let mut rest = stone;
+        let mut parts = (0..self.parts)
+            .map(|_| {
+                let part = rest % size;
+                rest /= size;
+                part
+            })
+            .collect::<Vec<u64>>();
+        parts.reverse();
+
+        Ok(Some(parts))
+    }
+}
+
+impl Rule for Multiply {
+    fn apply(&self, stone: u64) -> Result<Option<Vec<u64>>, Overflow> {
+        match stone.checked_mul(self.0) {
+            Some(n) => Ok(Some(vec![n])),
+            None => Err(Overflow::Stone(stone)),
+        }
+    }
+}
+
+/// Counts of stones already worked out, keyed by the number on the stone and how many blinks are
+/// left.
+pub type Memo = HashMap<(u64, usize), u64>;
+
+/// The rules there are for blinking, written the way `Rules::parse` reads them.
+pub const PRESETS: [(&str, &str); 2] = [
+    ("puzzle", "0 -> 1; split 2; * 2024"),
+    ("binary", "0 -> 1; split 2 base 2; * 3"),
+];
+
+/// What happens to stones when you blink. Every stone goes by the first rule that applies to it,
+/// and stays the same if none of them do.
+#[derive(Default)]
+pub struct Rules {
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl Rules {
+    pub fn new() -> Rules {
+        Rules::default()
+    }
+
+    /// The same rules with one more after them.
+    pub fn with(mut self, rule: impl Rule + 'static) -> Rules {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// The rules from the puzzle, the `puzzle` preset.
+    pub fn puzzle() -> Rules {
+        Rules::preset("puzzle").expect("the puzzle's rules are a preset")
+    }
+
+    /// One of the `PRESETS`, by name.
+    pub fn preset(name: &str) -> Option<Rules> {
+        PRESETS.iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, spec)| Rules::parse(spec).expect("presets are valid rules"))
+    }
+
+    /// Reads rules, one after the other, separated by `;` or on lines of their own:
+    ///
+    /// - `0 -> 1` or `7 -> 1 2 3`: a stone with that number turns into those stones.
+    /// - `split 2` or `split 3 base 2`: a stone with a multiple of that many digits splits into that
+    ///   many stones. Digits are in base 10 unless it says otherwise.
+    /// - `* 2024`: the number on the stone is multiplied by that.
+    pub fn parse(spec: &str) -> Result<Rules, ParseError> {
+        let mut rules = Rules::new();
+
+        for (i, line) in spec.lines().enumerate() {
+            for part in line.split(';') {
+                let number = |word: &str| parse_number::<u64>(i + 1, line, word);
+                let words = part.split_whitespace().collect::<Vec<&str>>();
+
+                rules = match words[..] {
+                    [] => rules,
+                    ["*", n] => rules.with(Multiply(number(n)?)),
+                    ["split", parts] => rules.with(split(number(parts)?, 10, i + 1, line, part)?),
+                    ["split", parts, "base", base] => rules.with(split(number(parts)?, number(base)?, i + 1, line, part)?),
+                    [from, "->", ref to @ ..] if !to.is_empty() => rules.with(Replace {
+                        from: number(from)?,
+                        to: to.iter().map(|w| number(w)).collect::<Result<Vec<u64>, ParseError>>()?,
+                    }),
+                    _ => {
+                        let rule = part.trim();
+                        return Err(ParseError::at(i + 1, column_of(line, rule), format!("expected a rule, found '{}'", rule)));
+                    }
+                };
+            }
+        }
+
+        Ok(rules)
+    }
+
+    /// The stones `stone` turns into when you blink once.
+    pub fn blink(&self, stone: u64) -> Result<Vec<u64>, Overflow> {
+        for rule in self.rules.iter() {
+            if let Some(stones) = rule.apply(stone)? {
+                return Ok(stones);
+            }
+        }
+
+        Ok(vec![stone])
+    }
+
+    /// How many stones there are after `blinks` blinks, starting with just the one. Every count
+    /// worked out along the way goes in the memo, so the next stone can make use of it.
+    pub fn count(&self, stone: u64, blinks: usize, memo: &mut Memo) -> Result<u64, Overflow> {
+        if blinks == 0 {
+            return Ok(1);
+        }
+
+        if let Some(&count) = memo.get(&(stone, blinks)) {
+            return Ok(count);
+        }
+
+        let mut count: u64 = 0;
+        for s in self.blink(stone)? {
+            count = count.checked_add(self.count(s, blinks - 1, memo)?).ok_or(Overflow::Count)?;
+        }
+        memo.insert((stone, blinks), count);
+
+        Ok(count)
+    }
+
+    /// How many stones there are after `blinks` blinks, starting with `stones`.
+    pub fn stones_after(&self, stones: &[u64], blinks: usize) -> Result<u64, Overflow> {
+        let mut memo = Memo::new();
+
+        stones.iter().try_fold(0u64, |sum, &stone| {
+            sum.checked_add(self.count(stone, blinks, &mut memo)?).ok_or(Overflow::Count)
+        })
+    }
+}
+
+fn split(parts: u64, base: u64, line_number: usize, line: &str, part: &str) -> Result<Split, ParseError> {
+    if parts < 2 || base < 2 || parts > u32::MAX as u64 {
+        let rule = part.trim();
+        return Err(ParseError::at(line_number, column_of(line, rule), format!("can't split into {} parts in base {}", parts, base)));
+    }
+
+    Ok(Split { parts: parts as u32, base })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{solve_part1, solve_part2};
+    use super::*;
+
+    const INPUT: &str = include_str!("../input.txt");
+
+    #[test]
+    fn test_split() {
+        let halves = Split { parts: 2, base: 10 };
+        assert_eq!(halves.apply(2000), Ok(Some(vec![20, 0])));
+        assert_eq!(halves.apply(0), Ok(None));
+        assert_eq!(halves.apply(999), Ok(None));
+
+        assert_eq!(halves.apply(u64::MAX), Ok(Some(vec![1844674407, 3709551615])));
+
+        assert_eq!(Split { parts: 3, base: 10 }.apply(123405), Ok(Some(vec![12, 34, 5])));
+        // 0b1011 is 2 and 3
+        assert_eq!(Split { parts: 2, base: 2 }.apply(11), Ok(Some(vec![2, 3])));
+    }
+
+    #[test]
+    fn test_puzzle_preset() {
+        let rules = Rules::puzzle();
+        assert_eq!(rules.blink(0), Ok(vec![1]));
+        assert_eq!(rules.blink(1), Ok(vec![2024]));
+        assert_eq!(rules.blink(11), Ok(vec![1, 1]));
+        assert_eq!(rules.blink(2000), Ok(vec![20, 0]));
+
+        assert_eq!(rules.stones_after(&[125, 17], 6), Ok(22));
+        assert_eq!(rules.stones_after(&[125, 17], 25), Ok(55312));
+
+        // both parts are the preset
+        assert_eq!(solve_part1(INPUT), Ok(197157));
+        assert_eq!(solve_part2(INPUT), Ok(234430066982597));
+    }
+
+    #[test]
+    fn test_other_rules() {
+        // 0 -> 1 -> 3 -> 1 1 -> 3 3 -> 1 1 1 1
+        let binary = Rules::preset("binary").unwrap();
+        assert_eq!(binary.stones_after(&[0], 5), Ok(4));
+
+        // without a rule for it, a stone stays the same
+        let rules = Rules::new().with(Replace { from: 1, to: vec![2, 2] });
+        assert_eq!(rules.blink(1), Ok(vec![2, 2]));
+        assert_eq!(rules.stones_after(&[1, 1, 5], 10), Ok(5));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let rules = Rules::new().with(Multiply(2024));
+        assert_eq!(rules.stones_after(&[u64::MAX / 1000], 1), Err(Overflow::Stone(u64::MAX / 1000)));
+
+        // doubling up 64 times is one time too many
+        let rules = Rules::new().with(Replace { from: 1, to: vec![1, 1] });
+        assert_eq!(rules.stones_after(&[1], 63), Ok(1 << 63));
+        assert_eq!(rules.stones_after(&[1], 64), Err(Overflow::Count));
+    }
+
+    #[test]
+    fn test_parse() {
+        let rules = Rules::parse("7 -> 1 2 3; split 3 base 2\n* 5").unwrap();
+        assert_eq!(rules.blink(7), Ok(vec![1, 2, 3]));
+        // 0b111000
+        assert_eq!(rules.blink(56), Ok(vec![3, 2, 0]));
+        assert_eq!(rules.blink(2), Ok(vec![10]));
+
+        assert_eq!(Rules::parse("0 -> 1; half 2").err(), Some(ParseError::at(1, 9, "expected a rule, found 'half 2'")));
+        assert_eq!(Rules::parse("* x").err(), Some(ParseError::at(1, 3, "expected a number, found 'x'")));
+        assert_eq!(Rules::parse("\nsplit 1").err(), Some(ParseError::at(2, 1, "can't split into 1 parts in base 10")));
+    }
+}
@@ -1,18 +1,29 @@
 mod part1;
 mod part2;
 
-pub fn solve_part1_example() -> u32 {
-    part1::solve(include_str!("../example.txt"))
-}
+use common::{Answer, ParseError, Solution, SolutionError};
+use grid::Grid;
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        Grid::parse(input, Some).map(|_: Grid<char>| ())
+    }
 
-pub fn solve_part1() -> u32 {
-    part1::solve(include_str!("../input.txt"))
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input)?.into())
+    }
+
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input)?.into())
+    }
 }
 
-pub fn solve_part2_example() -> u32 {
-    part2::solve(include_str!("../example.txt"))
+pub fn solve_part1(input: &str) -> Result<u32, ParseError> {
+    part1::solve(input)
 }
 
-pub fn solve_part2() -> u32 {
-    part2::solve(include_str!("../input.txt"))
-}
\ No newline at end of file
+pub fn solve_part2(input: &str) -> Result<u32, ParseError> {
+    part2::solve(input)
+}
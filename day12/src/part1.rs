@@ -1,26 +1,6 @@
-use std::collections::{HashMap, HashSet};
-
-#[derive(Debug, Hash, PartialEq, Eq, Copy, Clone)]
-pub(crate) struct Coordinate {
-    pub(crate) horizontal: i32,
-    pub(crate) vertical: i32,
-}
-
-impl Coordinate {
-    pub(crate) fn new(horizontal: i32, vertical: i32) -> Self {
-        Self { horizontal, vertical }
-    }
-
-    pub(crate) fn neighbours(&self) -> Vec<Self> {
-        vec![
-            Self::new(self.horizontal-1, self.vertical),
-            Self::new(self.horizontal+1, self.vertical),
-            Self::new(self.horizontal, self.vertical-1),
-            Self::new(self.horizontal, self.vertical+1),
-        ]
-    }
-}
-
+use std::collections::HashSet;
+use common::ParseError;
+use grid::{Coordinate, Direction, Grid};
 
 #[derive(Debug)]
 pub(crate) struct Region {
@@ -48,9 +28,11 @@ impl Region {
 
             visited.insert(*c);
 
-            for n in c.neighbours() {
-                if !self.plots.contains(&n) {
-                    perimeter += 1;
+            for d in Direction::ALL {
+                // the edge of the map is a fence too
+                match c.step(d) {
+                    Some(n) if self.plots.contains(&n) => {}
+                    _ => perimeter += 1,
                 }
             }
             // count out of set neighbours
@@ -61,37 +43,37 @@ impl Region {
 }
 
 
-pub(crate) fn solve(input: &str) -> u32 {
-    let lines = input.trim().lines();
-    let mut map: HashMap<Coordinate, String> = HashMap::new();
-
-    for (height, line) in lines.enumerate() {
-        for (width, c) in line.chars().enumerate() {
-            map.insert(Coordinate::new(width as i32, height as i32), c.to_string());
-        }
-    }
+/// Every region on the map. Any character is a plant type, the lines only have to be the same
+/// length.
+pub(crate) fn find_regions(input: &str) -> Result<Vec<Region>, ParseError> {
+    let map = Grid::parse(input, Some)?;
 
     let mut visited: HashSet<Coordinate> = HashSet::new();
     let mut regions : Vec<Region> = Vec::new();
 
-    for coord in map.keys() {
-        let region = flood_fill(&map, *coord, map.get(coord).unwrap(), &mut visited);
+    for coord in map.coordinates() {
+        let region = flood_fill(&map, coord, map[coord], &mut visited);
 
-        regions.push(Region::new(region));
+        if !region.is_empty() {
+            regions.push(Region::new(region));
+        }
     }
 
-    let regions: Vec<&Region> = regions.iter().filter(|&r| !r.plots.is_empty()).collect();
+    Ok(regions)
+}
+
+pub(crate) fn solve(input: &str) -> Result<u32, ParseError> {
+    let regions = find_regions(input)?;
 
     let mut price: i32 = 0;
     for region in regions.iter() {
         price += region.perimeter() * region.area();
     }
 
-    price as u32
+    Ok(price as u32)
 }
 
-pub(crate) fn flood_fill(map: &HashMap<Coordinate, String>, origin: Coordinate, plot_type: &String, visited: &mut HashSet<Coordinate>) -> HashSet<Coordinate> {
-    let unknown: &String = &"1".to_string();
+pub(crate) fn flood_fill(map: &Grid<char>, origin: Coordinate, plot_type: char, visited: &mut HashSet<Coordinate>) -> HashSet<Coordinate> {
     // println!("flood fill on coordinate {:?}", origin);
     let mut local_set = HashSet::new();
 
@@ -100,7 +82,7 @@ pub(crate) fn flood_fill(map: &HashMap<Coordinate, String>, origin: Coordinate,
         return local_set;
     }
 
-    let plot_at_coord = map.get(&origin).unwrap_or(unknown);
+    let plot_at_coord = map[origin];
 
     if plot_at_coord != plot_type {
         // println!("-- we were looking for plot type {}, but this {} was different",plot_type, plot_at_coord);
@@ -114,7 +96,7 @@ pub(crate) fn flood_fill(map: &HashMap<Coordinate, String>, origin: Coordinate,
     visited.insert(origin);
 
     // println!("-- checking neighbours");
-    for neighbour in origin.neighbours() {
+    for neighbour in map.neighbours(origin) {
         for p in flood_fill(map, neighbour, plot_type, visited) {
             // println!("-- -- inserting coordinate {:?} into local set from neighbour {:?}", p, neighbour);
             local_set.insert(p);
@@ -1,48 +1,35 @@
 use std::collections::{HashMap, HashSet};
-use crate::part1::{Coordinate, Region};
-
-#[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
-enum Side {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use common::ParseError;
+use grid::{Coordinate, Direction};
+use crate::part1::{find_regions, Region};
 
-impl Coordinate {
-    fn neighbour_on_side(&self, side: Side) -> Self {
-        match side {
-            Side::Up => {Coordinate::new(self.horizontal, self.vertical-1)}
-            Side::Down => {Coordinate::new(self.horizontal, self.vertical+1)}
-            Side::Left => {Coordinate::new(self.horizontal-1, self.vertical)}
-            Side::Right => {Coordinate::new(self.horizontal+1, self.vertical)}
-        }
-    }
+/// The plot next to this one on the given side, if the same wall continues there.
+fn same_side(side_set: &HashSet<(Coordinate, Direction)>, plot: Coordinate, towards: Direction, side: Direction) -> Option<Coordinate> {
+    plot.step(towards).filter(|next| side_set.contains(&(*next, side)))
 }
 
 impl Region {
     fn sides(&self) -> u32 {
-        let mut side_set: HashSet<(Coordinate, Side)> = HashSet::new();
+        let mut side_set: HashSet<(Coordinate, Direction)> = HashSet::new();
 
         for plot in &self.plots {
-            for s in [Side::Up, Side::Down, Side::Left, Side::Right] {
-                if !self.plots.contains(&plot.neighbour_on_side(s)) {
-                    side_set.insert((*plot, s));
+            for s in Direction::ALL {
+                match plot.step(s) {
+                    Some(n) if self.plots.contains(&n) => {}
+                    _ => { side_set.insert((*plot, s)); }
                 }
             }
         }
 
-        let mut grouped_sides: HashMap<(Coordinate,Side), i32> = HashMap::new();
+        let mut grouped_sides: HashMap<(Coordinate, Direction), i32> = HashMap::new();
         for side_entry in side_set.iter() {
             let mut current = side_entry.0;
 
             match side_entry.1 {
-                Side::Up|Side::Down => {
-                    let mut to_the_left = current.neighbour_on_side(Side::Left);
+                Direction::Up|Direction::Down => {
                     // find leftmost
-                    while side_set.contains(&(to_the_left, side_entry.1)) {
+                    while let Some(to_the_left) = same_side(&side_set, current, Direction::Left, side_entry.1) {
                         current = to_the_left;
-                        to_the_left = current.neighbour_on_side(Side::Left);
                     }
 
                     // current is the leftmost coordinate for the side. side_entry.1 is either
@@ -56,24 +43,20 @@ impl Region {
                     }
 
                     let mut side_length = 1;
-                    let mut to_the_right = current.neighbour_on_side(Side::Right);
 
                     // once we moved all the way to the left, check that if the space to the right
                     // also has the same border.
-                    while side_set.contains(&(to_the_right, side_entry.1)) {
+                    while let Some(to_the_right) = same_side(&side_set, current, Direction::Right, side_entry.1) {
                         current = to_the_right;
-                        to_the_right = current.neighbour_on_side(Side::Right);
                         side_length += 1;
                     }
 
                     grouped_sides.insert(key, side_length);
                 }
-                Side::Left|Side::Right => {
+                Direction::Left|Direction::Right => {
                     // find the bottom most
-                    let mut below = current.neighbour_on_side(Side::Down);
-                    while side_set.contains(&(below, side_entry.1)) {
+                    while let Some(below) = same_side(&side_set, current, Direction::Down, side_entry.1) {
                         current = below;
-                        below = current.neighbour_on_side(Side::Down);
                     }
 
                     // current is the leftmost coordinate for the side. side_entry.1 is either
@@ -87,10 +70,8 @@ impl Region {
                     }
 
                     let mut side_length = 1;
-                    let mut above = current.neighbour_on_side(Side::Up);
-                    while side_set.contains(&(above, side_entry.1)) {
+                    while let Some(above) = same_side(&side_set, current, Direction::Up, side_entry.1) {
                         current = above;
-                        above = current.neighbour_on_side(Side::Up);
                         side_length += 1;
                     }
 
@@ -103,26 +84,8 @@ impl Region {
     }
 }
 
-pub(crate) fn solve(input: &str) -> u32 {
-    let lines = input.trim().lines();
-    let mut map: HashMap<Coordinate, String> = HashMap::new();
-
-    for (height, line) in lines.enumerate() {
-        for (width, c) in line.chars().enumerate() {
-            map.insert(Coordinate::new(width as i32, height as i32), c.to_string());
-        }
-    }
-
-    let mut visited: HashSet<Coordinate> = HashSet::new();
-    let mut regions : Vec<Region> = Vec::new();
-
-    for coord in map.keys() {
-        let region = crate::part1::flood_fill(&map, *coord, map.get(coord).unwrap(), &mut visited);
-
-        regions.push(Region::new(region));
-    }
-
-    let regions: Vec<&Region> = regions.iter().filter(|&r| !r.plots.is_empty()).collect();
+pub(crate) fn solve(input: &str) -> Result<u32, ParseError> {
+    let regions = find_regions(input)?;
 
     let mut sum = 0;
 
@@ -140,7 +103,7 @@ pub(crate) fn solve(input: &str) -> u32 {
     //     println!("these are the sides: {:?}", sides);
     // }
     // //c
-    sum
+    Ok(sum)
 }
 
 
@@ -150,7 +113,14 @@ mod tests {
 
     #[test]
     fn test_group_sides() {
-        let input = "XX\nXY\n";
-
+        // XX
+        // XY
+        let region = Region::new(HashSet::from([
+            Coordinate::new(0, 0),
+            Coordinate::new(1, 0),
+            Coordinate::new(0, 1),
+        ]));
+
+        assert_eq!(region.sides(), 6);
     }
 }
\ No newline at end of file
@@ -1,18 +1,28 @@
 mod part1;
 mod part2;
 
-pub fn solve_part1_example() -> i64 {
-    part1::solve(include_str!("../example.txt"))
-}
+use common::{Answer, ParseError, Solution, SolutionError};
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        part1::parse_machines(input, part1::ClawMachine::try_from).map(|_| ())
+    }
 
-pub fn solve_part1() -> i64 {
-    part1::solve(include_str!("../input.txt"))
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input)?.into())
+    }
+
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input)?.into())
+    }
 }
 
-pub fn solve_part2_example() -> i64 {
-    part2::solve(include_str!("../example.txt"))
+pub fn solve_part1(input: &str) -> Result<i64, ParseError> {
+    part1::solve(input)
 }
 
-pub fn solve_part2() -> i64 {
-    part2::solve(include_str!("../input.txt"))
-}
\ No newline at end of file
+pub fn solve_part2(input: &str) -> Result<i64, ParseError> {
+    part2::solve(input)
+}
@@ -1,3 +1,5 @@
+use common::{column_of, parse_number, ParseError};
+
 #[derive(Debug)]
 pub(crate) struct Vector {
     horizontal: i64,
@@ -5,10 +7,14 @@ pub(crate) struct Vector {
 }
 
 impl Vector {
-    pub(crate) fn try_from(input: &str) -> Option<Vector> {
-        let nums = str_to_nums(&input[10..])?;
+    pub(crate) fn try_from(line_number: usize, input: &str) -> Result<Vector, ParseError> {
+        if !input.starts_with("Button ") {
+            return Err(ParseError::at(line_number, 1, format!("expected a button, found '{}'", input)));
+        }
 
-        Some(Self::new(nums.0, nums.1))
+        let nums = str_to_nums(line_number, input)?;
+
+        Ok(Self::new(nums.0, nums.1))
     }
 
     fn new(horizontal: i64, vertical: i64) -> Vector {
@@ -18,14 +24,19 @@ impl Vector {
 
 #[derive(Debug)]
 pub(crate) struct Coordinate {
-    horizontal: i64,
-    vertical: i64,
+    pub(crate) horizontal: i64,
+    pub(crate) vertical: i64,
 }
 
 impl Coordinate {
-    fn try_from(input: &str) -> Option<Coordinate> {
-        let nums = str_to_nums(&input[7..])?;
-        Some(Self::new(nums.0, nums.1))
+    pub(crate) fn try_from(line_number: usize, input: &str) -> Result<Coordinate, ParseError> {
+        if !input.starts_with("Prize: ") {
+            return Err(ParseError::at(line_number, 1, format!("expected a prize, found '{}'", input)));
+        }
+
+        let nums = str_to_nums(line_number, input)?;
+
+        Ok(Self::new(nums.0, nums.1))
     }
 
     pub(crate) fn new(horizontal: i64, vertical: i64) -> Coordinate {
@@ -43,13 +54,15 @@ pub(crate) struct ClawMachine {
 }
 
 impl ClawMachine {
-    fn try_from(input: &str) -> Option<ClawMachine> {
-        let parts = input.trim().lines().collect::<Vec<&str>>();
-        let ba: Vector = Vector::try_from(parts[0])?;
-        let bb: Vector = Vector::try_from(parts[1])?;
-        let target = Coordinate::try_from(parts[2])?;
+    /// Takes the lines of a single machine, each with its line number.
+    pub(crate) fn try_from(parts: &[(usize, &str)]) -> Result<ClawMachine, ParseError> {
+        check_machine_lines(parts)?;
+
+        let ba: Vector = Vector::try_from(parts[0].0, parts[0].1)?;
+        let bb: Vector = Vector::try_from(parts[1].0, parts[1].1)?;
+        let target = Coordinate::try_from(parts[2].0, parts[2].1)?;
 
-        Some(Self::new(ba, bb, target))
+        Ok(Self::new(ba, bb, target))
     }
 
     pub(crate) fn new(ba: Vector, bb: Vector, target: Coordinate) -> ClawMachine {
@@ -104,27 +117,70 @@ impl ClawMachine {
     }
 }
 
-pub(crate) fn str_to_nums(input: &str) -> Option<(i64, i64)> {
-    let nums = input
+/// Reads the two numbers off a line like `Button A: X+94, Y+34` or `Prize: X=8400, Y=5400`.
+fn str_to_nums(line_number: usize, input: &str) -> Result<(i64, i64), ParseError> {
+    let (_, values) = input.split_once(": ")
+        .ok_or(ParseError::at(line_number, 1, format!("expected a label followed by ': ', found '{}'", input)))?;
+
+    let nums = values
         .trim()
         .split(", ")
-        .map(|x| x[2..].parse::<i64>().unwrap())
-        .collect::<Vec<i64>>();
+        .map(|x| parse_number(line_number, input, x.get(2..).unwrap_or(x)))
+        .collect::<Result<Vec<i64>, ParseError>>()?;
 
     if nums.len() != 2 {
-        return None
+        return Err(ParseError::at(
+            line_number,
+            column_of(input, values),
+            format!("expected an X and a Y value, found {} values", nums.len()),
+        ));
     }
 
-    Some((nums[0], nums[1]))
+    Ok((nums[0], nums[1]))
 }
 
+pub(crate) fn check_machine_lines(parts: &[(usize, &str)]) -> Result<(), ParseError> {
+    if parts.len() != 3 {
+        return Err(ParseError::at(
+            parts[0].0,
+            1,
+            format!("expected two buttons and a prize, found {} lines", parts.len()),
+        ));
+    }
 
+    Ok(())
+}
 
-pub(crate) fn solve(input: &str) -> i64 {
-    let machines:Vec<ClawMachine> = input
-        .trim()
-        .split("\n\n")
-        .map(|block| { ClawMachine::try_from(block).unwrap() }).collect();
+/// Builds a single machine from its lines, each with its line number.
+pub(crate) type MachineParser = fn(&[(usize, &str)]) -> Result<ClawMachine, ParseError>;
+
+/// Machines are separated by empty lines.
+pub(crate) fn parse_machines(input: &str, parse: MachineParser) -> Result<Vec<ClawMachine>, ParseError> {
+    let mut machines = Vec::new();
+    let mut block: Vec<(usize, &str)> = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                machines.push(parse(&block)?);
+                block.clear();
+            }
+
+            continue;
+        }
+
+        block.push((i + 1, line.trim_end()));
+    }
+
+    if !block.is_empty() {
+        machines.push(parse(&block)?);
+    }
+
+    Ok(machines)
+}
+
+pub(crate) fn solve(input: &str) -> Result<i64, ParseError> {
+    let machines = parse_machines(input, ClawMachine::try_from)?;
 
     let mut sum = 0;
 
@@ -137,7 +193,7 @@ pub(crate) fn solve(input: &str) -> i64 {
         }
     }
 
-    sum
+    Ok(sum)
 }
 
 #[cfg(test)]
@@ -155,4 +211,20 @@ mod tests {
         assert!(machine.least_tokens().is_some());
         assert_eq!(machine.least_tokens(), Some(280));
     }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            solve("Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400, Y=5400\n\nButton A: X+26, Y+6\n").err(),
+            Some(ParseError::at(5, 1, "expected two buttons and a prize, found 1 lines"))
+        );
+        assert_eq!(
+            solve("Button A: X+94, Y+34\nButton B: X+22, Y+z67\nPrize: X=8400, Y=5400\n").err(),
+            Some(ParseError::at(2, 19, "expected a number, found 'z67'"))
+        );
+        assert_eq!(
+            solve("Button A: X+94, Y+34\nPrize: X=8400, Y=5400\nButton B: X+22, Y+67\n").err(),
+            Some(ParseError::at(2, 1, "expected a button, found 'Prize: X=8400, Y=5400'"))
+        );
+    }
 }
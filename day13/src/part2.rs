@@ -1,32 +1,31 @@
-use crate::part1::{ClawMachine, Coordinate, Vector};
+use common::ParseError;
+use crate::part1::{check_machine_lines, parse_machines, ClawMachine, Coordinate, Vector};
 
 const COST:i64 = 10_000_000_000_000; // thou, mil, bil, tril, quadril, 10 quadrillion
 
 
 impl Coordinate {
-    fn try_from_part2(input: &str) -> Option<Coordinate> {
-        let nums = crate::part1::str_to_nums(&input[7..])?;
-        Some(Self::new(nums.0+COST, nums.1+COST))
+    fn try_from_part2(line_number: usize, input: &str) -> Result<Coordinate, ParseError> {
+        let nums = Coordinate::try_from(line_number, input)?;
+        Ok(Self::new(nums.horizontal+COST, nums.vertical+COST))
     }
 }
 
 
 impl ClawMachine {
-    fn try_from_part2(input: &str) -> Option<ClawMachine> {
-        let parts = input.trim().lines().collect::<Vec<&str>>();
-        let ba: Vector = Vector::try_from(parts[0])?;
-        let bb: Vector = Vector::try_from(parts[1])?;
-        let target = Coordinate::try_from_part2(parts[2])?;
+    fn try_from_part2(parts: &[(usize, &str)]) -> Result<ClawMachine, ParseError> {
+        check_machine_lines(parts)?;
 
-        Some(Self::new(ba, bb, target))
+        let ba: Vector = Vector::try_from(parts[0].0, parts[0].1)?;
+        let bb: Vector = Vector::try_from(parts[1].0, parts[1].1)?;
+        let target = Coordinate::try_from_part2(parts[2].0, parts[2].1)?;
+
+        Ok(Self::new(ba, bb, target))
     }
 }
 
-pub(crate) fn solve(input: &str) -> i64 {
-    let machines:Vec<ClawMachine> = input
-        .trim()
-        .split("\n\n")
-        .map(|block| { ClawMachine::try_from_part2(block).unwrap() }).collect();
+pub(crate) fn solve(input: &str) -> Result<i64, ParseError> {
+    let machines = parse_machines(input, ClawMachine::try_from_part2)?;
 
     let mut sum = 0;
 
@@ -39,5 +38,5 @@ pub(crate) fn solve(input: &str) -> i64 {
         }
     }
 
-    sum
-}
\ No newline at end of file
+    Ok(sum)
+}
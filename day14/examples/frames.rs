@@ -0,0 +1,99 @@
+//! Writes what the robots look like at the given seconds as images.
+//!
+//!     cargo run --release -p day14 --example frames -- day14/input.txt 7600..7700 frames
+//!     cargo run --release -p day14 --example frames -- day14/input.txt 7672 frames --pbm
+//!     cargo run --release -p day14 --example frames -- day14/input.txt 0..400 frames --sheet 20
+//!     cargo run --release -p day14 --example frames -- day14/example.txt 0..10 frames --room 11x7
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use day14::{frames, write_contact_sheet, write_frames, ImageFormat, Room};
+
+const USAGE: &str = "\
+Usage: frames <INPUT> <SECONDS | FROM..TO> <DIR> [--pgm | --pbm | --sheet <COLUMNS>] [--room <WxH>]
+
+Writes a PGM image per second by default. --pbm writes black and white images instead, --sheet
+puts every frame on a single contact sheet that many frames wide. The end of a range is exclusive.
+The room is the puzzle's 101x103 unless --room says otherwise, the example's is 11x7.";
+
+enum Output {
+    Frames(ImageFormat),
+    Sheet(usize),
+}
+
+fn parse_seconds(value: &str) -> Option<Vec<i32>> {
+    match value.split_once("..") {
+        Some((from, to)) => Some((from.parse().ok()?..to.parse().ok()?).collect()),
+        None => Some(vec![value.parse().ok()?]),
+    }
+}
+
+fn parse_output(args: &[String]) -> Option<Output> {
+    match args {
+        [] => Some(Output::Frames(ImageFormat::Pgm)),
+        [flag] if flag == "--pgm" => Some(Output::Frames(ImageFormat::Pgm)),
+        [flag] if flag == "--pbm" => Some(Output::Frames(ImageFormat::Pbm)),
+        [flag, columns] if flag == "--sheet" => columns.parse().ok().map(Output::Sheet),
+        _ => None,
+    }
+}
+
+/// Takes `--room <WxH>` out of the arguments, wherever it is.
+fn take_room(args: &mut Vec<String>) -> Option<Room> {
+    let Some(i) = args.iter().position(|a| a == "--room") else {
+        return Some(Room::PUZZLE);
+    };
+
+    let room = args.get(i + 1).and_then(|r| Room::parse(r));
+    args.drain(i..(i + 2).min(args.len()));
+
+    room
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1).collect::<Vec<String>>();
+    let room = take_room(&mut args);
+
+    let parsed = match args.as_slice() {
+        [input, seconds, dir, rest @ ..] => parse_seconds(seconds)
+            .zip(parse_output(rest))
+            .zip(room)
+            .map(|((seconds, output), room)| (input, seconds, PathBuf::from(dir), output, room)),
+        _ => None,
+    };
+    let Some((input, seconds, dir, output, room)) = parsed else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let text = match std::fs::read_to_string(input) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Could not read {}: {}", input, e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let frames = match frames(&text, room, seconds) {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Could not parse {}: {}", input, e.diagnostic(&text));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let written = match output {
+        Output::Frames(format) => write_frames(&frames, format, &dir),
+        Output::Sheet(columns) => write_contact_sheet(&frames, columns, &dir).map(|p| vec![p]),
+    };
+
+    match written {
+        Ok(paths) => {
+            println!("Wrote {} files into {}", paths.len(), dir.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Could not write into {}: {}", dir.display(), e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use common::ParseError;
+use crate::part1::{parse_robots, Day14, Room};
+
+/// Netpbm flavours the frames can be written as. Both are the plain, text based variants, so the
+/// files can also be looked at in an editor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Black and white, a pixel is black if there's at least one robot on the tile.
+    Pbm,
+    /// Greyscale, the more robots are on a tile, the brighter it is.
+    Pgm,
+}
+
+impl ImageFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Pbm => "pbm",
+            ImageFormat::Pgm => "pgm",
+        }
+    }
+}
+
+/// The room at a given second, as the number of robots on each tile, row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub seconds: i32,
+    width: usize,
+    height: usize,
+    robots: Vec<u32>,
+}
+
+impl Frame {
+    fn at(day: &Day14, seconds: i32) -> Frame {
+        let width = day.width as usize;
+        let height = day.height as usize;
+        let mut robots = vec![0; width * height];
+
+        for r in day.robots.iter() {
+            let (h, v) = r.move_robot((day.width, day.height), seconds);
+            robots[v as usize * width + h as usize] += 1;
+        }
+
+        Frame { seconds, width, height, robots }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// How many robots are on the tile.
+    pub fn robots_at(&self, horizontal: usize, vertical: usize) -> u32 {
+        self.robots[vertical * self.width + horizontal]
+    }
+
+    pub fn encode(&self, format: ImageFormat) -> String {
+        match format {
+            ImageFormat::Pbm => {
+                let pixels = self.robots.iter().map(|&r| (r > 0) as u32).collect::<Vec<u32>>();
+                netpbm("P1", self.width, None, &pixels)
+            }
+            ImageFormat::Pgm => {
+                let max = self.robots.iter().copied().max().unwrap_or(0).max(1);
+                netpbm("P2", self.width, Some(max), &self.robots)
+            }
+        }
+    }
+}
+
+/// The same picture the puzzle draws, a # for every tile with a robot on it.
+impl Display for Frame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.robots.chunks(self.width.max(1)) {
+            for &r in row {
+                write!(f, "{}", if r > 0 { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A plain netpbm image, every row of pixels starting on a new line. `max` is the brightest grey,
+/// black and white images don't have one. The format doesn't allow lines longer than 70 characters,
+/// so wide rows carry on over several lines.
+fn netpbm(magic: &str, width: usize, max: Option<u32>, pixels: &[u32]) -> String {
+    let height = pixels.len().checked_div(width).unwrap_or(0);
+    let mut s = format!("{}\n{} {}\n", magic, width, height);
+
+    if let Some(m) = max {
+        s += &format!("{}\n", m);
+    }
+
+    for row in pixels.chunks(width.max(1)) {
+        let mut line = String::new();
+
+        for p in row {
+            let value = p.to_string();
+            if !line.is_empty() && line.len() + 1 + value.len() > 70 {
+                s += &line;
+                s.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line += &value;
+        }
+
+        s += &line;
+        s.push('\n');
+    }
+
+    s
+}
+
+/// Parses the robots, and works out where they are in the room at each of the given seconds.
+pub fn frames<I>(input: &str, room: Room, seconds: I) -> Result<Vec<Frame>, ParseError>
+where
+    I: IntoIterator<Item = i32>,
+{
+    let robots = parse_robots(input)?;
+    let day = Day14::new(room.width, room.height, 0, robots);
+
+    Ok(seconds.into_iter().map(|s| Frame::at(&day, s)).collect())
+}
+
+/// Writes every frame into `dir` as `frame_<seconds>.<pbm|pgm>`, creating the directory if it's not
+/// there yet. Returns the paths of the files in the same order as the frames.
+pub fn write_frames(frames: &[Frame], format: ImageFormat, dir: &Path) -> io::Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir)?;
+
+    frames.iter()
+        .map(|frame| {
+            let path = dir.join(format!("frame_{:05}.{}", frame.seconds, format.extension()));
+            fs::write(&path, frame.encode(format))?;
+
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Lays the frames out in a grid, `columns` wide and in the order they came in, with a one pixel
+/// grey line between them. Robots are white, empty tiles are black.
+pub fn contact_sheet(frames: &[Frame], columns: usize) -> String {
+    let columns = columns.clamp(1, frames.len().max(1));
+    let rows = frames.len().div_ceil(columns);
+    let (frame_width, frame_height) = frames.first().map_or((0, 0), |f| (f.width, f.height));
+
+    // a line between the frames and around the edges
+    let width = columns * (frame_width + 1) + 1;
+    let height = rows * (frame_height + 1) + 1;
+    let mut pixels = vec![1; width * height];
+
+    for (i, frame) in frames.iter().enumerate() {
+        let left = (i % columns) * (frame_width + 1) + 1;
+        let top = (i / columns) * (frame_height + 1) + 1;
+
+        for v in 0..frame_height {
+            for h in 0..frame_width {
+                pixels[(top + v) * width + left + h] = match frame.robots_at(h, v) {
+                    0 => 0,
+                    _ => 2,
+                };
+            }
+        }
+    }
+
+    netpbm("P2", width, Some(2), &pixels)
+}
+
+/// Writes the contact sheet into `dir` as `contact_sheet_<first>-<last>.pgm`, named after the
+/// seconds of the first and last frames.
+pub fn write_contact_sheet(frames: &[Frame], columns: usize, dir: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+
+    let first = frames.first().map_or(0, |f| f.seconds);
+    let last = frames.last().map_or(0, |f| f.seconds);
+    let path = dir.join(format!("contact_sheet_{:05}-{:05}.pgm", first, last));
+
+    fs::write(&path, contact_sheet(frames, columns))?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // three robots in the example's 11 by 7 room, two of which share a tile after a second
+    const ROBOTS: &str = "p=0,0 v=1,0\np=2,0 v=-1,0\np=10,6 v=1,1\n";
+
+    #[test]
+    fn test_frames() {
+        let frames = frames(ROBOTS, Room::EXAMPLE, [0, 1]).unwrap();
+
+        assert_eq!((frames[0].width(), frames[0].height()), (11, 7));
+        assert_eq!(frames[1].robots_at(1, 0), 2);
+        assert_eq!(frames[1].robots_at(0, 0), 1);
+        assert_eq!(frames[1].to_string().lines().next(), Some("##........."));
+        assert_eq!(frames[1].to_string().lines().count(), 7);
+    }
+
+    #[test]
+    fn test_encode() {
+        let frame = Frame { seconds: 0, width: 3, height: 2, robots: vec![0, 2, 0, 1, 0, 0] };
+
+        assert_eq!(frame.encode(ImageFormat::Pbm), "P1\n3 2\n0 1 0\n1 0 0\n");
+        assert_eq!(frame.encode(ImageFormat::Pgm), "P2\n3 2\n2\n0 2 0\n1 0 0\n");
+
+        let wide = Frame { seconds: 0, width: 40, height: 1, robots: vec![1; 40] };
+        let encoded = wide.encode(ImageFormat::Pbm);
+        assert!(encoded.lines().all(|l| l.len() <= 70));
+        assert_eq!(encoded.lines().skip(2).map(|l| l.split(' ').count()).sum::<usize>(), 40);
+    }
+
+    #[test]
+    fn test_contact_sheet() {
+        let a = Frame { seconds: 0, width: 2, height: 1, robots: vec![1, 0] };
+        let b = Frame { seconds: 1, width: 2, height: 1, robots: vec![0, 3] };
+        let c = Frame { seconds: 2, width: 2, height: 1, robots: vec![0, 0] };
+
+        assert_eq!(contact_sheet(&[a, b, c], 2), "\
+P2
+7 5
+2
+1 1 1 1 1 1 1
+1 2 0 1 0 2 1
+1 1 1 1 1 1 1
+1 0 0 1 1 1 1
+1 1 1 1 1 1 1
+");
+    }
+
+    #[test]
+    fn test_write_frames() {
+        let dir = std::env::temp_dir().join(format!("day14_export_{}", std::process::id()));
+        let frames = frames(ROBOTS, Room::EXAMPLE, 3..5).unwrap();
+
+        let paths = write_frames(&frames, ImageFormat::Pbm, &dir).unwrap();
+        let sheet = write_contact_sheet(&frames, 4, &dir).unwrap();
+
+        assert_eq!(paths, vec![dir.join("frame_00003.pbm"), dir.join("frame_00004.pbm")]);
+        assert_eq!(fs::read_to_string(&paths[1]).unwrap(), frames[1].encode(ImageFormat::Pbm));
+        assert_eq!(sheet, dir.join("contact_sheet_00003-00004.pgm"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
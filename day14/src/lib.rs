@@ -1,15 +1,44 @@
+mod export;
 mod part1;
 mod part2;
 
-pub fn solve_part1_example() -> i32 {
-    part1::solve(include_str!("../example.txt"), 11, 7, 100)
+use common::{Answer, ParseError, Solution, SolutionError};
+
+pub use export::{contact_sheet, frames, write_contact_sheet, write_frames, Frame, ImageFormat};
+pub use part1::Room;
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        part1::parse_robots(input).map(|_| ())
+    }
+
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input, Room::PUZZLE)?.into())
+    }
+
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input, Room::PUZZLE)?.into())
+    }
+
+    fn example_part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input, Room::EXAMPLE)?.into())
+    }
+
+    fn example_part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input, Room::EXAMPLE)?.into())
+    }
 }
 
-pub fn solve_part1() -> i32 {
-    part1::solve(include_str!("../input.txt"), 101, 103, 100)
+pub fn solve_part1(input: &str, room: Room) -> Result<i32, ParseError> {
+    let robots = part1::parse_robots(input)?;
+
+    Ok(part1::solve(robots, room.width, room.height, 100))
 }
 
+pub fn solve_part2(input: &str, room: Room) -> Result<i32, ParseError> {
+    let robots = part1::parse_robots(input)?;
 
-pub fn solve_part2() -> i32 {
-    part2::solve(include_str!("../input.txt"), 101, 103)
-}
\ No newline at end of file
+    Ok(part2::solve(robots, room.width, room.height))
+}
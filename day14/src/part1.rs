@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
-use std::fmt::{Debug, Display, Formatter};
+use std::fmt::{Display, Formatter};
+use common::{column_of, parse_number, ParseError};
 
 #[derive(Debug)]
 enum Quadrant {
@@ -8,27 +9,6 @@ enum Quadrant {
     BottomLeft,
     BottomRight,
 }
-pub(crate) struct BadRobotError {
-    msg: String,
-}
-
-impl BadRobotError {
-    pub(crate) fn new(msg: &str) -> BadRobotError {
-        BadRobotError { msg: msg.to_string() }
-    }
-}
-
-impl Debug for BadRobotError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Could not instantiate a robot from input '{}'", self.msg)
-    }
-}
-
-impl Display for BadRobotError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Could not instantiate a robot")
-    }
-}
 
 #[derive(Debug, Copy, Clone)]
 pub(crate) struct Robot {
@@ -51,17 +31,22 @@ impl Display for Robot {
 }
 
 impl Robot {
-    pub(crate) fn try_from(input: &str) -> Result<Robot, BadRobotError> {
+    pub(crate) fn try_from(line_number: usize, input: &str) -> Result<Robot, ParseError> {
         // p=0,0 v=1,3
-        let parts: Vec<Vec<i32>> = input.split_whitespace().map(|line| {
-            line[2..].split(',').map(|part| part.parse().unwrap()).collect::<Vec<i32>>()})
-            .collect();
-
-        if parts.len() != 2 || parts[0].len() != 2 || parts[1].len() != 2 {
-            return Err(BadRobotError::new(input));
+        let parts = input.split_whitespace().collect::<Vec<&str>>();
+
+        if parts.len() != 2 {
+            return Err(ParseError::at(
+                line_number,
+                1,
+                format!("expected a robot like 'p=0,4 v=3,-3', found '{}'", input),
+            ));
         }
 
-        Ok(Robot::new(parts[0][0], parts[0][1],parts[1][0], parts[1][1]))
+        let position = parse_pair(line_number, input, parts[0], "p=")?;
+        let velocity = parse_pair(line_number, input, parts[1], "v=")?;
+
+        Ok(Robot::new(position.0, position.1, velocity.0, velocity.1))
     }
 
     pub(crate) fn new(pos_h: i32, pos_v: i32, vel_h: i32, vel_v: i32) -> Robot {
@@ -101,6 +86,32 @@ impl Robot {
     }
 }
 
+/// Reads the two numbers out of `part`, something like `p=0,4`, which is a slice of `line`.
+fn parse_pair(line_number: usize, line: &str, part: &str, prefix: &str) -> Result<(i32, i32), ParseError> {
+    let values = part.strip_prefix(prefix).ok_or(ParseError::at(
+        line_number,
+        column_of(line, part),
+        format!("expected '{}', found '{}'", prefix, part),
+    ))?;
+
+    let (h, v) = values.split_once(',').ok_or(ParseError::at(
+        line_number,
+        column_of(line, values),
+        format!("expected two numbers separated by a comma, found '{}'", values),
+    ))?;
+
+    Ok((parse_number(line_number, line, h)?, parse_number(line_number, line, v)?))
+}
+
+/// One robot per line, empty lines are skipped.
+pub(crate) fn parse_robots(input: &str) -> Result<Vec<Robot>, ParseError> {
+    input.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| Robot::try_from(i + 1, line))
+        .collect()
+}
+
 pub(crate) struct Day14 {
     pub(crate) width: i32,
     pub(crate) height: i32,
@@ -159,8 +170,28 @@ impl Day14 {
     }
 }
 
-pub(crate) fn solve(input: &str, width: i32, height: i32, steps: i32) -> i32 {
-    let robots: Vec<Robot> = input.trim().lines().map(|line| Robot::try_from(line).unwrap()).collect();
+/// How big the room the robots are in is. The input doesn't say, the puzzle tells you instead, and
+/// the example takes place in a much smaller room than the real input.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Room {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Room {
+    pub const PUZZLE: Room = Room { width: 101, height: 103 };
+    pub const EXAMPLE: Room = Room { width: 11, height: 7 };
+
+    /// Reads a size like `11x7`, width first.
+    pub fn parse(s: &str) -> Option<Room> {
+        let (width, height) = s.split_once('x')?;
+        let room = Room { width: width.parse().ok()?, height: height.parse().ok()? };
+
+        (room.width > 0 && room.height > 0).then_some(room)
+    }
+}
+
+pub(crate) fn solve(robots: Vec<Robot>, width: i32, height: i32, steps: i32) -> i32 {
     let d14 = Day14::new(width, height, steps, robots);
 
     d14.safety_score()
@@ -179,7 +210,7 @@ mod tests {
         // p=1,1 v=1,-1
 
 
-        let r = Robot::try_from("p=1,1 v=1,-1");
+        let r = Robot::try_from(1, "p=1,1 v=1,-1");
         assert!(r.is_ok());
 
         let bounds = (3,3);
@@ -198,4 +229,33 @@ mod tests {
         (horizontal, vertical) = robot.move_robot(bounds, 27);
         assert_eq!((horizontal, vertical), (1, 1));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_room() {
+        assert_eq!(Room::parse("11x7"), Some(Room::EXAMPLE));
+        assert_eq!(Room::parse("101x103"), Some(Room::PUZZLE));
+        assert_eq!(Room::parse("0x7"), None);
+        assert_eq!(Room::parse("11 by 7"), None);
+    }
+
+    #[test]
+    fn test_parse_robots() {
+        assert_eq!(parse_robots("p=0,4 v=3,-3\n\np=6,3 v=-1,-3\n").map(|r| r.len()), Ok(2));
+        assert_eq!(
+            parse_robots("p=0,4 v=3,-3\np=6,3\n").err(),
+            Some(ParseError::at(2, 1, "expected a robot like 'p=0,4 v=3,-3', found 'p=6,3'"))
+        );
+        assert_eq!(
+            parse_robots("p=0,4 v=3,-3\np=6,3 w=-1,-3\n").err(),
+            Some(ParseError::at(2, 7, "expected 'v=', found 'w=-1,-3'"))
+        );
+        assert_eq!(
+            parse_robots("p=0,4 v=3;-3\n").err(),
+            Some(ParseError::at(1, 9, "expected two numbers separated by a comma, found '3;-3'"))
+        );
+        assert_eq!(
+            parse_robots("p=0,4 v=3,-x\n").err(),
+            Some(ParseError::at(1, 11, "expected a number, found '-x'"))
+        );
+    }
+}
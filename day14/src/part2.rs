@@ -1,241 +1,65 @@
-use std::collections::{HashMap, HashSet};
-use std::fs::File;
-use std::io::Write;
-use crate::part1::{Day14};
+use std::collections::HashSet;
+use crate::part1::{Day14, Robot};
 
 impl Day14 {
-    #[allow(dead_code)]
+    /// Every robot is back where it started after width × height seconds, so if there's a picture,
+    /// it shows up within that many. While the robots are scattered about they only ever touch a
+    /// few others, but the tree is drawn with most of them next to each other, so the frame with the
+    /// biggest clump of robots is the one with the tree in it. If more than one frame has a clump
+    /// that big, the earliest wins.
     fn find_easter_egg(&self) -> i32 {
-        // let l = self.robots.len();
-        let _ = self.find_iterations_where_robots_are_in_middle_topmost_row();
-        0
-    }
-
-    #[allow(dead_code)]
-    fn check_for_flood_fill(&self) -> i32 {
-        let mut i = 0;
-        let bounds = (self.width, self.height);
-
-        loop {
-            i +=1;
-            println!("\nlooping iteration {}", i);
-            let mut uniques: HashSet<(i32, i32)> = HashSet::new();
-            self.robots.iter().for_each(|r| {
-                let c = r.move_robot(bounds, i);
-                // println!("moved robot {} {} times and got here: {:?}", r, i, c);
-                uniques.insert(c);
-            });
+        let mut best_seconds = 0;
+        let mut best_size = 0;
 
-            if flood_fill_all(uniques) {
+        for seconds in 0..self.width * self.height {
+            let size = largest_component(&self.positions_at(seconds));
 
-                break;
+            if size > best_size {
+                best_size = size;
+                best_seconds = seconds;
             }
-
-            //
-            // if l == uniques.len() {
-            //     if flood_fill_all(uniques) {
-            //         break;
-            //     }
-            // }
         }
 
-        println!("At iteration {} we found uniques", i);
-
-        i
+        best_seconds
     }
 
-    fn find_iterations_where_robots_are_in_middle_topmost_row(&self) -> Vec<i32> {
-        let bounds = (self.width, self.height);
-        let mid = self.width / 2;
-        println!("mid is {}, width is {}", mid, self.width);
-
-        // at seconds 1979 the top one is in the middle, a distance of 1979 from the previous
-        // at seconds 2746 the top one is in the middle, a distance of 767 from the previous
-        // at seconds 4853 the top one is in the middle, a distance of 2107 from the previous
-        // at seconds 12382 the top one is in the middle, a distance of 7529 from the previous
-        // at seconds 13149 the top one is in the middle, a distance of 767 from the previous
-        //
-
-
-        let mut current = 97;
-        let step = 101;
-        let mut one_at_top:Vec<i32> = Vec::new();
-        let mut seconds = 0;
-        let mut distance = 0;
-
-        for _i in 0..3000 {
-            let mut robot_coords: Vec<(i32, i32)> = Vec::new();
-            let mut robots_per_vert: HashMap<i32, i32> = HashMap::new();
-
-            self.robots.iter().for_each(|r| {
-                let coords = r.move_robot(bounds, current);
-                robot_coords.push(coords);
-                *robots_per_vert.entry(coords.1).or_default() += 1;
-
-                // if coords.1 == 0 && coords.0 == mid {
-                //     robots_in_middle_at_top.insert(seconds, *r);
-                // }
-            });
-
-            /*
-            So let's find out the first ones
-            */
-            let mut robots_per_vert_keys = robots_per_vert.keys().cloned().collect::<Vec<_>>();
-            robots_per_vert_keys.sort();
-
-            let first_key = robots_per_vert_keys[0];
-
-            if robots_per_vert.get(&first_key).unwrap() == &1 {
-                one_at_top.push(seconds);
-            }
-
-            seconds += distance;
-            distance = generator_middle(distance);
-
-            visualise(current, bounds, robot_coords);
-            current += step
-        }
-        // for k in 0..3000 {
-        //     let mut robot_coords: Vec<(i32, i32)> = Vec::new();
-        //
-        //     self.robots.iter().for_each(|r| {
-        //         let coords = r.move_robot(bounds, k);
-        //         robot_coords.push(coords);
-        //         // *robots_per_vert.entry(coords.1).or_default() += 1;
-        //
-        //         // if coords.1 == 0 && coords.0 == mid {
-        //         //     robots_in_middle_at_top.insert(seconds, *r);
-        //         // }
-        //     });
-        //
-        //     visualise(k, bounds, robot_coords)
-        // }
-
-        vec![mid, mid]
-
-        // let mut i = 0;
-        // let mut seconds = 1979; // we start on iteration 12, the first one that has 1 robot in
-        //                              // the topmost row that's also in the middle
-        // let mut distance = 767; // and the next one is 10 from this one
-        // let mut one_at_top:Vec<i32> = Vec::new();
-        // // let mut three_on_second: Vec<i32> = Vec::new();
-        // let mut robots_in_middle_at_top: HashMap<i32, Robot> = HashMap::new();
-        // // return vec![];
-        // loop {
-        //
-        //     i += 1;
-        //     let mut robots_per_vert:HashMap<i32, i32> = HashMap::new();
-        //     let mut robot_coords: Vec<(i32, i32)> = Vec::new();
-        //
-        //     self.robots.iter().for_each(|r| {
-        //         let coords = r.move_robot(bounds, seconds);
-        //         robot_coords.push(coords);
-        //         *robots_per_vert.entry(coords.1).or_default() += 1;
-        //
-        //         if coords.1 == 0 && coords.0 == mid {
-        //             robots_in_middle_at_top.insert(seconds, *r);
-        //         }
-        //     });
-        //
-        //     visualise(seconds, bounds, robot_coords);
-        //
-        //     /*
-        //     So let's find out the first ones
-        //     */
-        //     let mut robots_per_vert_keys = robots_per_vert.keys().cloned().collect::<Vec<_>>();
-        //     robots_per_vert_keys.sort();
-        //
-        //     let first_key = robots_per_vert_keys[0];
-        //
-        //     if robots_per_vert.get(&first_key).unwrap() == &1 {
-        //         one_at_top.push(seconds);
-        //     }
-        //
-        //     seconds += distance;
-        //     distance = generator_middle(distance);
-        //
-        //     if i > 1000 {
-        //         break;
-        //     }
-        //     //
-        //     // for r in self.robots.iter() {
-        //     //     println!("\n------------------------\nrobot we're looking at is {}", r);
-        //     //     println!("we need mod height {} to be 0", self.height);
-        //     //
-        //     //     let mut iterations_where_zero: Vec<i32> = Vec::new();
-        //     //     let mut i = 0;
-        //     //     loop {
-        //     //         if r.move_robot(bounds, i).1 == 0 {
-        //     //             iterations_where_zero.push(i);
-        //     //         }
-        //     //
-        //     //         i += 1;
-        //     //
-        //     //         if i > 320 {
-        //     //             break;
-        //     //         }
-        //     //     }
-        //     //
-        //     //     let mut previous = 0;
-        //     //
-        //     //     for j in iterations_where_zero.iter() {
-        //     //         println!("thingy is zero vertically on iteration {}, {} since last", j, j - previous);
-        //     //         previous = *j;
-        //     //     }
-        //     // }
-        //
-        //
-        //     // we need iterations where the result is divisible by height
-        //     // r.start_vertical + i * r.move_vertical = 100x
-        // }
-        //
-        // let mut robots_in_middle_at_top_keys = robots_in_middle_at_top.keys().cloned().collect::<Vec<_>>();
-        // robots_in_middle_at_top_keys.sort();
-        //
-        // let mut previous = 0;
-        // for l in robots_in_middle_at_top_keys {
-        //     println!("at seconds {} the top one is in the middle, a distance of {} from the previous",
-        //              l, l-previous,);
-        //     previous = l;
-        // }
-        // // for l in one_at_top {
-        // //     // this yields a
-        // //     // println!("iteration {} has one at top, {} since last", l, l-previous);
-        // //     previous = l;
-        // // }
-        //
-        //
-        // vec![]
+    fn positions_at(&self, seconds: i32) -> HashSet<(i32, i32)> {
+        self.robots.iter()
+            .map(|r| r.move_robot((self.width, self.height), seconds))
+            .collect()
     }
 }
 
-fn flood_fill_all(coords: HashSet<(i32, i32)>) -> bool {
-    // println!("-- checking flood fill for coords {:?}", coords);
+/// How many robots are in the biggest group of robots that are next to each other.
+fn largest_component(coords: &HashSet<(i32, i32)>) -> usize {
     let mut visited: HashSet<(i32, i32)> = HashSet::new();
+    let mut largest = 0;
+
+    for coord in coords {
+        if visited.contains(coord) {
+            continue;
+        }
 
-    let first = coords.iter().next().unwrap();
-    // println!("-- FIRST: {:?}", first);
-    visit(&coords, &mut visited, first);
+        let before = visited.len();
+        visit(coords, &mut visited, coord);
+        largest = largest.max(visited.len() - before);
+    }
 
-    coords.len() == visited.len()
+    largest
 }
 
+/// Marks every robot in the same group as `coord` as visited. Goes through them with a stack of its
+/// own rather than by recursing, so a big enough group can't run out of stack.
 fn visit(map: &HashSet<(i32, i32)>, visited: &mut HashSet<(i32, i32)>, coord: &(i32, i32)) {
-    // println!("-- ++ recursive visited: {:?}", coord);
-    if !map.contains(coord) {
-        // println!("-- ++ __ coord is not in the map");
-        return;
-    } // if coordinate is not in the map, return
-    if visited.contains(coord) {
-        // println!("-- ++ __ coord is already in the visited");
-        return;
-    } // if coordinate is in the visited, we've been here, ret
+    let mut stack = vec![*coord];
 
-    // println!("-- ++ __ inserting coord into visited");
-    visited.insert(*coord);
-    for c in gen_neighbours(coord) {
-        // println!("-- ++ __ checking neighbour {:?} of {:?}", c, coord);
-        visit(map, visited, &c);
+    while let Some(c) = stack.pop() {
+        // not a robot, or one we've already been to
+        if !map.contains(&c) || !visited.insert(c) {
+            continue;
+        }
+
+        stack.extend(gen_neighbours(&c));
     }
 }
 
@@ -248,65 +72,41 @@ fn gen_neighbours(coord: &(i32, i32)) -> [(i32, i32); 4] {
     ]
 }
 
-pub(crate) fn solve(input: &str, width: i32, height: i32) -> i32 {
-    let robots: Vec<crate::part1::Robot> = input.trim().lines().map(|line| crate::part1::Robot::try_from(line).unwrap()).collect();
+pub(crate) fn solve(robots: Vec<Robot>, width: i32, height: i32) -> i32 {
     let d14 = Day14::new(width, height, 0, robots);
 
     d14.find_easter_egg()
 }
 
-fn visualise(iteration: i32, bounds: (i32, i32), input: Vec<(i32, i32)>) {
-    let mut s = String::from("Iteration ") + &iteration.to_string() + "\n";
-    for vertical in 0..=bounds.0 {
-        for horizontal in 0..=bounds.1 {
-            match input.contains(&(horizontal, vertical)) {
-                true => {s.push('#');}
-                false => {s.push('.');}
-            }
-        }
-        s.push('\n');
-    }
-
-    let mut file = File::create(iteration.to_string() + "tree.txt").unwrap();
-    file.write_all(s.as_bytes()).unwrap();
-}
-
-#[allow(dead_code)]
-fn generator(previous: i32) -> i32 {
-    match previous {
-        10 => {46}
-        46 => {47}
-        47 => {10}
-        _ => {103}
-    }
-}
-
-// at seconds 2746 the top one is in the middle, a distance of 767 from the previous
-// at seconds 4853 the top one is in the middle, a distance of 2107 from the previous
-// at seconds 12382 the top one is in the middle, a distance of 7529 from the previous
-
-
-fn generator_middle(previous: i32) -> i32 {
-    match previous {
-        767 => {2107}
-        2107 => {7529}
-        7529 => {767}
-        _ => {767}
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_flood_fill_all() {
+    fn test_biggest_group_wins() {
         let input = "p=0,0 v=1,-1\n\
         p=3,1 v=-2,-1\n\
         p=2,2 v=-1,-1\n\
         p=0,3 v=1,3\n";
 
-        assert_eq!(solve(input, 4, 4), 1);
+        let robots = crate::part1::parse_robots(input).unwrap();
+
+        assert_eq!(solve(robots, 4, 4), 1);
+    }
+
+    #[test]
+    fn test_largest_component() {
+        let coords = HashSet::from([(0, 0), (1, 0), (1, 1), (3, 3), (3, 2), (0, 3)]);
+
+        assert_eq!(largest_component(&coords), 3);
+        assert_eq!(largest_component(&HashSet::new()), 0);
+    }
+
+    #[test]
+    fn test_large_component() {
+        // a room the size of the puzzle's with a robot on every tile, all of them in one group
+        let coords = (0..101).flat_map(|h| (0..103).map(move |v| (h, v))).collect::<HashSet<(i32, i32)>>();
 
+        assert_eq!(largest_component(&coords), 101 * 103);
     }
 }
\ No newline at end of file
@@ -0,0 +1,56 @@
+//! Makes up a warehouse and moves for it, and checks that walking it doesn't break anything.
+//!
+//!     cargo run -p day15 --example generate -- 42 > day15/generated.txt
+//!     cargo run -p day15 --example generate -- 42 20 10 0.1 0.3 700
+
+use std::process::ExitCode;
+use day15::{check_invariants, Generator, MapSettings};
+
+const USAGE: &str = "\
+Usage: generate <SEED> [<WIDTH> <HEIGHT> <WALLS> <BOXES> <MOVES>]
+
+Prints a puzzle input made from the seed. WALLS and BOXES are the chance of a tile being one, between
+0 and 1. Defaults to a 10 by 10 map with 0.1 walls, 0.3 boxes and 700 moves, like the example.";
+
+fn parse_args(args: &[String]) -> Option<(u64, MapSettings, usize)> {
+    match args {
+        [seed] => Some((seed.parse().ok()?, MapSettings { width: 10, height: 10, walls: 0.1, boxes: 0.3 }, 700)),
+        [seed, width, height, walls, boxes, moves] => Some((
+            seed.parse().ok()?,
+            MapSettings {
+                width: width.parse().ok()?,
+                height: height.parse().ok()?,
+                walls: walls.parse().ok()?,
+                boxes: boxes.parse().ok()?,
+            },
+            moves.parse().ok()?,
+        )),
+        _ => None,
+    }
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+    let Some((seed, settings, moves)) = parse_args(&args) else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let input = Generator::new(seed).puzzle(&settings, moves);
+    print!("{}", input);
+
+    match check_invariants(&input) {
+        Ok(violations) if violations.is_empty() => ExitCode::SUCCESS,
+        Ok(violations) => {
+            for v in violations {
+                eprintln!("Broken {}", v);
+            }
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("Could not parse what was made: {}", e.diagnostic(&input));
+            ExitCode::FAILURE
+        }
+    }
+}
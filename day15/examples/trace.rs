@@ -0,0 +1,76 @@
+//! Writes every move the robot makes as JSON Lines, and optionally compares it with a reference
+//! trace.
+//!
+//!     cargo run -p day15 --example trace -- day15/tinyexample.txt 2
+//!     cargo run -p day15 --example trace -- day15/tinyexample.txt 2 day15/tinyexample_part2.jsonl
+
+use std::process::ExitCode;
+use day15::{first_difference, to_json_lines, trace_part1, trace_part2};
+
+const USAGE: &str = "\
+Usage: trace <INPUT> <1 | 2> [REFERENCE]
+
+Prints the trace of the walk on the narrow (1) or wide (2) map. Given a reference trace, prints the
+first move where the two disagree instead, and fails if there is one.";
+
+fn read(path: &str) -> Result<String, ExitCode> {
+    std::fs::read_to_string(path).map_err(|e| {
+        eprintln!("Could not read {}: {}", path, e);
+        ExitCode::FAILURE
+    })
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+    let (input, part, reference) = match args.as_slice() {
+        [input, part] => (input, part, None),
+        [input, part, reference] => (input, part, Some(reference)),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    let trace = match part.as_str() {
+        "1" => trace_part1,
+        "2" => trace_part2,
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let text = match read(input) {
+        Ok(t) => t,
+        Err(code) => return code,
+    };
+    let lines = match trace(&text) {
+        Ok(entries) => to_json_lines(&entries),
+        Err(e) => {
+            eprintln!("Could not parse {}: {}", input, e.diagnostic(&text));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Some(reference) = reference else {
+        print!("{}", lines);
+        return ExitCode::SUCCESS;
+    };
+    let expected = match read(reference) {
+        Ok(t) => t,
+        Err(code) => return code,
+    };
+
+    match first_difference(&lines, &expected) {
+        None => {
+            println!("Same as {}, {} moves.", reference, lines.lines().count());
+            ExitCode::SUCCESS
+        }
+        Some(d) => {
+            println!("Move {} is different.", d.line);
+            println!("  ours:      {}", d.ours.as_deref().unwrap_or("(no more moves)"));
+            println!("  reference: {}", d.reference.as_deref().unwrap_or("(no more moves)"));
+            ExitCode::FAILURE
+        }
+    }
+}
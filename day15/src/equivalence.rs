@@ -0,0 +1,108 @@
+//! Property tests that walk random small warehouses with both the narrow engine of part 1 and the
+//! wide one of part 2, and check that neither of them ever gets the warehouse into a state it can't
+//! be in.
+
+use proptest::prelude::*;
+use grid::Direction;
+use crate::generator::check_invariants;
+use crate::part1::Day15;
+
+/// A walled in map `width` by `height` tiles inside the walls, with the robot on the `robot`th
+/// tile, and the moves after it.
+fn puzzle(width: usize, tiles: &[char], robot: usize, commands: &[Direction]) -> String {
+    let mut tiles = tiles.to_vec();
+    tiles[robot] = '@';
+
+    let wall = "#".repeat(width + 2);
+    let rows = tiles.chunks(width).map(|row| format!("#{}#", row.iter().collect::<String>()));
+    let moves = commands.iter()
+        .map(|d| match d {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        })
+        .collect::<String>();
+
+    format!("{}\n{}\n{}\n\n{}\n", wall, rows.collect::<Vec<String>>().join("\n"), wall, moves)
+}
+
+fn tile() -> impl Strategy<Value = char> {
+    prop_oneof![5 => Just('.'), 3 => Just('O'), 1 => Just('#')]
+}
+
+fn direction() -> impl Strategy<Value = Direction> {
+    prop::sample::select(Direction::ALL.to_vec())
+}
+
+/// Maps between 1 by 1 and 8 by 6 tiles inside the walls, and up to 60 moves.
+fn puzzles(directions: impl Strategy<Value = Direction>) -> impl Strategy<Value = String> {
+    let maps = (1..=8usize, 1..=6usize).prop_flat_map(|(width, height)| (
+        Just(width),
+        prop::collection::vec(tile(), width * height),
+        0..width * height,
+    ));
+
+    (maps, prop::collection::vec(directions, 0..60))
+        .prop_map(|((width, tiles, robot), commands)| puzzle(width, &tiles, robot, &commands))
+}
+
+/// Each row of the map as the things on it in order, with the empty tiles left out. On the wide
+/// map a box and the two tiles of a wall, which never moves off its even column, count as one.
+fn rows_in_order(d: &Day15) -> Vec<String> {
+    d.to_string()
+        .lines()
+        .map(|row| {
+            row.chars()
+                .enumerate()
+                .filter(|&(i, c)| match c {
+                    '.' | ']' => false,
+                    '#' => !d.wide || i % 2 == 0,
+                    _ => true,
+                })
+                .map(|(_, c)| if c == '[' { 'O' } else { c })
+                .collect()
+        })
+        .collect()
+}
+
+/// Runs the puzzle through `check_invariants`, failing on the first move that broke something on
+/// either map, and hands back both maps walked to the end.
+fn walk_checked(input: &str) -> Result<(Day15, Day15), TestCaseError> {
+    if let Some(violation) = check_invariants(input).unwrap().first() {
+        return Err(TestCaseError::fail(format!("{}\n{}", violation, input)));
+    }
+
+    let mut narrow = Day15::try_from(input).unwrap();
+    let mut wide = Day15::try_from_part2(input).unwrap();
+    narrow.walk();
+    wide.walk_part2();
+
+    Ok((narrow, wide))
+}
+
+proptest! {
+    #[test]
+    fn engines_keep_invariants(input in puzzles(direction())) {
+        walk_checked(&input)?;
+    }
+
+    /// Moving only sideways, the rows never get in each other's way, and nothing on a row can get
+    /// past anything else on it. The wide robot moves half a box at a time so where exactly things
+    /// end up isn't the same, but on both maps every row has to end up with the same walls, boxes
+    /// and robot in the same order.
+    #[test]
+    fn engines_agree_moving_sideways(input in puzzles(prop::sample::select(vec![Direction::Left, Direction::Right]))) {
+        let (narrow, wide) = walk_checked(&input)?;
+
+        prop_assert_eq!(rows_in_order(&narrow), rows_in_order(&wide));
+        prop_assert_eq!(narrow.robot().vertical, wide.robot().vertical);
+    }
+}
+
+#[test]
+fn test_rows_in_order() {
+    let d = Day15::try_from_part2("####\n#@O#\n#O.#\n####\n\n<\n").unwrap();
+
+    assert_eq!(rows_in_order(&d), vec!["####", "#@O#", "#O#", "####"]);
+}
@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use common::ParseError;
+use grid::{Coordinate, Direction};
+use crate::part1::{Day15, Entity};
+
+/// What the generated maps look like. The size includes the wall that's all around the edge, the
+/// densities are the chance of any other tile being a wall or a box.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MapSettings {
+    pub width: usize,
+    pub height: usize,
+    pub walls: f64,
+    pub boxes: f64,
+}
+
+/// Makes up warehouses and moves. The same seed always makes the same puzzles, on any machine, so
+/// a puzzle that finds a bug can be made again from the seed alone.
+#[derive(Debug, Clone)]
+pub struct Generator {
+    state: u64,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Generator {
+        Generator { state: seed }
+    }
+
+    /// SplitMix64, which is plenty random for this and doesn't need a dependency that might change
+    /// what a seed makes between versions.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+        z ^ (z >> 31)
+    }
+
+    /// A number from 0 up to, but not including, `n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n.max(1) as u64) as usize
+    }
+
+    /// True with a chance of `p`, which is between 0 and 1.
+    fn chance(&mut self, p: f64) -> bool {
+        // the top 53 bits fit in an f64 exactly
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    /// A map in the same format as the puzzle's, walled in all around, with one robot somewhere
+    /// inside. Maps smaller than 3 by 3 are made 3 by 3, so there's room for the robot.
+    pub fn map(&mut self, settings: &MapSettings) -> String {
+        let width = settings.width.max(3);
+        let height = settings.height.max(3);
+        let robot = (self.below(width - 2) + 1, self.below(height - 2) + 1);
+        let mut map = String::with_capacity((width + 1) * height);
+
+        for vertical in 0..height {
+            for horizontal in 0..width {
+                let tile = if vertical == 0 || horizontal == 0 || vertical == height - 1 || horizontal == width - 1 {
+                    '#'
+                } else if (horizontal, vertical) == robot {
+                    '@'
+                } else if self.chance(settings.walls) {
+                    '#'
+                } else if self.chance(settings.boxes) {
+                    'O'
+                } else {
+                    '.'
+                };
+
+                map.push(tile);
+            }
+            map.push('\n');
+        }
+
+        map
+    }
+
+    /// `count` moves, in lines of 70 like the example.
+    pub fn commands(&mut self, count: usize) -> String {
+        let moves = (0..count).map(|_| ['<', '>', '^', 'v'][self.below(4)]).collect::<Vec<char>>();
+
+        moves.chunks(70).map(|line| line.iter().collect::<String>() + "\n").collect()
+    }
+
+    /// A whole puzzle input: a map, an empty line, and the moves.
+    pub fn puzzle(&mut self, settings: &MapSettings, moves: usize) -> String {
+        let map = self.map(settings);
+
+        format!("{}\n{}", map, self.commands(moves))
+    }
+}
+
+/// A move after which the warehouse was no longer in a state it can be in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Whether this was on the widened map.
+    pub wide: bool,
+    /// Index of the command that was run last.
+    pub command: usize,
+    pub message: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let map = if self.wide { "wide" } else { "narrow" };
+
+        write!(f, "after command {} on the {} map: {}", self.command, map, self.message)
+    }
+}
+
+/// Walks both the narrow map and the one `widen_map` makes out of it, and checks after every move
+/// that the number of boxes hasn't changed, the walls haven't moved, and there's exactly one robot
+/// where the robot is supposed to be and not on top of a box. Returns the first move that broke
+/// any of them on either map.
+pub fn check_invariants(input: &str) -> Result<Vec<Violation>, ParseError> {
+    let mut violations = Vec::new();
+
+    for mut d in [Day15::try_from(input)?, Day15::try_from_part2(input)?] {
+        let boxes = d.box_count();
+        let walls = d.walls();
+
+        while d.step().is_some() {
+            if let Some(message) = d.broken_invariant(boxes, &walls) {
+                violations.push(Violation { wide: d.wide, command: d.next_command - 1, message });
+                break;
+            }
+        }
+    }
+
+    Ok(violations)
+}
+
+impl Day15 {
+    /// How many boxes there are, a wide box being a single box.
+    pub(crate) fn box_count(&self) -> usize {
+        self.map.iter().filter(|(_, e)| matches!(e, Entity::Box | Entity::BoxLeft)).count()
+    }
+
+    pub(crate) fn walls(&self) -> HashSet<Coordinate> {
+        self.map.iter().filter(|(_, e)| e == &&Entity::Wall).map(|(c, _)| c).collect()
+    }
+
+    /// What's wrong with the warehouse, if anything, given how many boxes and where the walls were
+    /// at the start.
+    pub(crate) fn broken_invariant(&self, boxes: usize, walls: &HashSet<Coordinate>) -> Option<String> {
+        if self.box_count() != boxes {
+            return Some(format!("there are {} boxes instead of {}", self.box_count(), boxes));
+        }
+
+        if self.walls() != *walls {
+            return Some("the walls have moved".to_string());
+        }
+
+        let robots = self.map.iter().filter(|(_, e)| e == &&Entity::Robot).count();
+        if robots != 1 {
+            return Some(format!("there are {} robots", robots));
+        }
+
+        match self.map.get(self.robot) {
+            Some(Entity::Robot) => {}
+            Some(e) => return Some(format!("the robot is on a tile with {} on it", e)),
+            None => return Some("the robot is off the map".to_string()),
+        }
+
+        for (c, e) in self.map.iter() {
+            let right = self.map.step(c, Direction::Right).and_then(|r| self.map.get(r));
+
+            match (self.wide, e) {
+                (false, Entity::BoxLeft | Entity::BoxRight) | (true, Entity::Box) => {
+                    return Some(format!("there is a {} at {},{}, which this map shouldn't have", e, c.horizontal, c.vertical));
+                }
+                (true, Entity::BoxLeft) if right != Some(&Entity::BoxRight) => {
+                    return Some(format!("the box at {},{} has no right half", c.horizontal, c.vertical));
+                }
+                _ => {}
+            }
+        }
+
+        if self.map.iter().filter(|(_, e)| e == &&Entity::BoxRight).count() != boxes * self.wide as usize {
+            return Some("there are halves of boxes without a left half".to_string());
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTINGS: MapSettings = MapSettings { width: 12, height: 9, walls: 0.1, boxes: 0.3 };
+
+    #[test]
+    fn test_same_seed_same_puzzle() {
+        let a = Generator::new(15).puzzle(&SETTINGS, 200);
+        let b = Generator::new(15).puzzle(&SETTINGS, 200);
+        let c = Generator::new(16).puzzle(&SETTINGS, 200);
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_puzzle_is_valid_input() {
+        let input = Generator::new(1).puzzle(&SETTINGS, 150);
+        let (map, commands) = input.split_once("\n\n").unwrap();
+
+        assert_eq!(map.lines().count(), 9);
+        assert!(map.lines().all(|l| l.len() == 12 && l.starts_with('#') && l.ends_with('#')));
+        assert_eq!(map.matches('@').count(), 1);
+        assert_eq!(commands.lines().map(str::len).collect::<Vec<usize>>(), vec![70, 70, 10]);
+
+        assert!(crate::solve_part1(&input).is_ok());
+        assert!(crate::solve_part2(&input).is_ok());
+    }
+
+    #[test]
+    fn test_check_invariants() {
+        for seed in 0..100 {
+            let settings = MapSettings { width: 4 + seed as usize % 10, height: 4 + seed as usize % 7, ..SETTINGS };
+            let input = Generator::new(seed).puzzle(&settings, 200);
+
+            assert_eq!(check_invariants(&input), Ok(vec![]), "seed {}:\n{}", seed, input);
+        }
+    }
+
+    #[test]
+    fn test_broken_invariant() {
+        let mut d = Day15::try_from_part2("#####\n#@O.#\n#####\n\n>\n").unwrap();
+        let walls = d.walls();
+        assert_eq!(d.broken_invariant(1, &walls), None);
+
+        d.map.set(Coordinate::new(5, 1), Entity::Empty);
+        assert_eq!(d.broken_invariant(1, &walls), Some("the box at 4,1 has no right half".to_string()));
+
+        d.map.set(Coordinate::new(4, 1), Entity::Robot);
+        assert_eq!(d.broken_invariant(0, &walls), Some("there are 2 robots".to_string()));
+    }
+}
@@ -1,16 +1,73 @@
+#[cfg(test)]
+mod equivalence;
+mod generator;
 mod part1;
 mod part2;
+mod shapes;
+mod stepper;
+mod trace;
 
-pub fn solve_part1_example() -> u32 {
-    part1::solve(include_str!("../example.txt"))
+use common::{Answer, ParseError, Solution, SolutionError};
+
+pub use generator::{check_invariants, Generator, MapSettings, Violation};
+pub use grid::{Coordinate, Direction};
+pub use part1::Day15 as Warehouse;
+pub use shapes::ShapedWarehouse;
+pub use trace::{first_difference, to_json_lines, trace_part1, trace_part2, Difference, TraceEntry, TracedTile};
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    fn parse(input: &str) -> Result<(), ParseError> {
+        part1::Day15::try_from(input).map(|_| ())
+    }
+
+    fn part1(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part1(input)?.into())
+    }
+
+    fn part2(input: &str) -> Result<Answer, SolutionError> {
+        Ok(solve_part2(input)?.into())
+    }
 }
 
-pub fn solve_part1() -> u32 {
-    part1::solve(include_str!("../input.txt"))
+pub fn solve_part1(input: &str) -> Result<u32, ParseError> {
+    part1::solve(input)
 }
 
-pub fn solve_part2_example() -> u32 { part2::solve(include_str!("../example.txt")) }
+pub fn solve_part2(input: &str) -> Result<u32, ParseError> {
+    part2::solve(input)
+}
 
-pub fn solve_part2_tinyexample() -> u32 { part2::solve(include_str!("../tinyexample.txt")) }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-pub fn solve_part2() -> u32 { part2::solve(include_str!("../input.txt")) }
\ No newline at end of file
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            solve_part1("#####\n#@O.#\n#####\n\n>>\n<^x\n").err(),
+            Some(ParseError::at(6, 3, "unexpected move 'x'"))
+        );
+        assert_eq!(
+            solve_part2("#####\n#@0.#\n#####\n\n>>\n").err(),
+            Some(ParseError::at(2, 3, "unexpected character '0' on the map"))
+        );
+        assert_eq!(
+            solve_part1("#####\n#.O.#\n#####\n\n>>\n").err(),
+            Some(ParseError::new("there is no robot on the map"))
+        );
+        assert_eq!(
+            solve_part1("#####\n#@O@#\n#####\n\n>").err(),
+            Some(ParseError::at(2, 4, "there is more than one robot on the map"))
+        );
+        assert_eq!(
+            solve_part2("#####\n#@O.#\n#.@.#\n#####\n\n>").err(),
+            Some(ParseError::at(3, 3, "there is more than one robot on the map"))
+        );
+        assert_eq!(
+            solve_part2("#####\n#@O.#\n#####\n").err(),
+            Some(ParseError::new("expected an empty line between the map and the robot's moves"))
+        );
+    }
+}
@@ -1,23 +1,10 @@
 use std::cmp::PartialEq;
-use std::collections::HashMap;
-use std::fmt::{Debug, Display, Formatter};
-
-
-pub(crate) struct BadDay15Error{
-    pub(crate) msg: String
-}
-
-impl Display for BadDay15Error {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Bad input")
-    }
-}
-
-impl Debug for BadDay15Error {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Bad input: {}", self.msg)
-    }
-}
+use std::fmt::{Display, Formatter};
+use common::ParseError;
+use grid::{Coordinate, Direction, Grid};
+use crate::part2::Overwrite;
+use crate::stepper::Step;
+use crate::trace::TraceEntry;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub(crate) enum Entity {
@@ -29,76 +16,61 @@ pub(crate) enum Entity {
     BoxRight
 }
 
-#[derive(Copy, Clone, Debug)]
-pub(crate) enum MoveCommand {
-    Up,
-    Left,
-    Down,
-    Right
-}
-
-#[derive(Hash, Eq, PartialEq, Copy, Clone, Debug)]
-pub(crate) struct Coordinate {
-    pub(crate) horizontal: u32,
-    pub(crate) vertical: u32,
-}
-
-impl Coordinate {
-    pub(crate) fn try_from(horizontal: usize, vertical: usize) -> Coordinate {
-        Coordinate::new(horizontal as u32,vertical as u32)
-    }
-
-    pub(crate) fn new(horizontal: u32, vertical: u32) -> Coordinate {
-        Coordinate{horizontal, vertical}
-    }
+/// The tiles as they are drawn in the puzzle, so a map renders the same way it was read.
+impl Display for Entity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Entity::Box => 'O',
+            Entity::Wall => '#',
+            Entity::Robot => '@',
+            Entity::Empty => '.',
+            Entity::BoxLeft => '[',
+            Entity::BoxRight => ']',
+        };
 
-    pub(crate) fn next(&self, direction: MoveCommand) -> Coordinate {
-        match direction {
-            MoveCommand::Up => {Coordinate::new(self.horizontal, self.vertical-1)}
-            MoveCommand::Left => {Coordinate::new(self.horizontal-1, self.vertical)}
-            MoveCommand::Down => {Coordinate::new(self.horizontal, self.vertical+1)}
-            MoveCommand::Right => {Coordinate::new(self.horizontal+1, self.vertical)}
-        }
+        write!(f, "{}", c)
     }
 }
 
-pub(crate) struct Day15 {
-    pub(crate) map: HashMap<Coordinate, Entity>,
-    pub(crate) commands: Vec<MoveCommand>,
+/// The warehouse, the robot in it, and the moves it's going to make. Moves can be made one at a
+/// time and taken back, see `stepper.rs`.
+pub struct Day15 {
+    pub(crate) map: Grid<Entity>,
+    pub(crate) commands: Vec<Direction>,
     pub(crate) robot: Coordinate,
-    pub(crate) bounds: (u32, u32)
+    /// Whether this is the widened map of part 2, which moves boxes differently.
+    pub(crate) wide: bool,
+    /// Index of the command the next step is going to run.
+    pub(crate) next_command: usize,
+    pub(crate) history: Vec<Step>,
+    /// Every move made since tracing was started, see `trace.rs`.
+    pub(crate) trace: Option<Vec<TraceEntry>>,
 }
 
 impl Day15 {
-    fn try_from(input: &str) -> Result<Day15, BadDay15Error> {
-        let parts = input.trim().split("\n\n").collect::<Vec<_>>();
+    /// The map as it is in the input, for part 1.
+    pub fn try_from(input: &str) -> Result<Day15, ParseError> {
+        let (map_section, commands_section, commands_line) = split_sections(input)?;
 
-        let commands = parse_commands(parts[1])?;
-        let (map, robot_coordinate, bounds) = parse_map(parts[0])?;
+        let commands = parse_commands(commands_section, commands_line)?;
+        let (map, robot_coordinate) = parse_map(map_section)?;
 
-        Ok(Day15::new(map, commands, robot_coordinate, bounds))
+        Ok(Day15::new(map, commands, robot_coordinate, false))
     }
 
-    pub(crate) fn new(
-        map: HashMap<Coordinate, Entity>,
-        commands: Vec<MoveCommand>,
-        robot: Coordinate,
-        bounds: (u32, u32)
-    ) -> Day15 {
-        Day15 { map, commands, robot, bounds }
+    pub(crate) fn new(map: Grid<Entity>, commands: Vec<Direction>, robot: Coordinate, wide: bool) -> Day15 {
+        Day15 { map, commands, robot, wide, next_command: 0, history: Vec::new(), trace: None }
     }
 
-    fn walk(&mut self) {
-        for c in self.commands.clone() {
-            self.execute_command(c);
-        }
+    pub(crate) fn walk(&mut self) {
+        self.run_to(self.commands.len());
     }
 
-    fn count_gps(&self) -> u32 {
+    pub(crate) fn count_gps(&self) -> u32 {
         self.map.iter().fold(0, |acc, (c, e)| {
             let mut res = acc;
             if e == &Entity::Box {
-                res = acc + 100 * c.vertical + c.horizontal
+                res = acc + 100 * c.vertical as u32 + c.horizontal as u32
             }
 
             res
@@ -106,27 +78,32 @@ impl Day15 {
     }
 
 
-    fn execute_command(&mut self, command: MoveCommand) {
+    /// Moves the robot on the narrow map. Returns the tiles that changed, which is nothing if the
+    /// robot couldn't move.
+    pub(crate) fn execute_command(&mut self, command: Direction) -> Vec<Overwrite> {
         // get where the robot is
         let mut current = self.robot;
         let mut previous = Entity::Robot;
-        let mut replacements: HashMap<Coordinate, (Entity, Entity)> = HashMap::new();
-
-        // let's add the robot to the top of the replacements for the current coordinate.
-        replacements.insert(current, (Entity::Robot, Entity::Empty));
+        // in the order they're found going away from the robot, so the position in the list is how
+        // far the tile is from the robot. The robot is at the top for the current coordinate.
+        let mut replacements: Vec<(Coordinate, (Entity, Entity))> = vec![(current, (Entity::Robot, Entity::Empty))];
 
         loop {
-            let next = current.next(command);
+            let Some(next) = self.map.step(current, command) else {
+                // walking off the map is as good as walking into a wall
+                replacements = Vec::new();
+                break;
+            };
 
-            match self.map.get(&next) {
+            match self.map.get(next) {
                 Some(Entity::Box) => {
-                    replacements.insert(next, (Entity::Box, previous));
+                    replacements.push((next, (Entity::Box, previous)));
                     current = next;
                     previous = Entity::Box;
                 }
                 Some(Entity::Wall) => {
                     // nothing happens here, nothing gets replaced, nothing moves
-                    replacements = HashMap::new();
+                    replacements = Vec::new();
                     break;
                 }
                 Some(Entity::Robot) => {
@@ -134,7 +111,7 @@ impl Day15 {
                 }
                 Some(Entity::Empty) => {
                     // we found an empty spot, everything can thus move
-                    replacements.insert(next, (Entity::Empty, previous));
+                    replacements.push((next, (Entity::Empty, previous)));
                     break;
                 }
                 _ => {
@@ -143,88 +120,77 @@ impl Day15 {
             }
         }
 
-        if replacements.is_empty() {
-            return;
-        }
+        let mut previous_tiles: Vec<Overwrite> = Vec::new();
 
-        for (coord, (_from_entity, to_entity)) in replacements.iter() {
-            self.map.insert(*coord, *to_entity);
-            if *to_entity == Entity::Robot {
-                self.robot = *coord;
+        for (depth, (coord, (from_entity, to_entity))) in replacements.into_iter().enumerate() {
+            self.map.set(coord, to_entity);
+            previous_tiles.push(Overwrite { depth: depth as u32, coordinate: coord, from: from_entity, to: to_entity });
+            if to_entity == Entity::Robot {
+                self.robot = coord;
             }
         }
+
+        previous_tiles
     }
 }
 
 
-pub(crate) fn parse_commands(input: &str) -> Result<Vec<MoveCommand>, BadDay15Error> {
-    let mut commands = Vec::new();
+/// Splits the input into the map and the robot's moves, which are separated by an empty line. The
+/// third value is the line number the moves start on.
+pub(crate) fn split_sections(input: &str) -> Result<(&str, &str, usize), ParseError> {
+    let (map, commands) = input.split_once("\n\n")
+        .ok_or(ParseError::new("expected an empty line between the map and the robot's moves"))?;
 
-    // Parse the commands
-    for (idx, c) in input.replace("\n", "").trim().chars().enumerate() {
-        let command = match c {
-            '<' => MoveCommand::Left,
-            '>' => MoveCommand::Right,
-            '^' => MoveCommand::Up,
-            'v' => MoveCommand::Down,
-            _ => {
-                println!("Illegal character found for char {} at idx {}", c, idx);
-
-                return Err(BadDay15Error{msg: format!("Unexpected character '{}'", c)})}
-        };
-
-        commands.push(command);
-    }
-
-    Ok(commands)
+    Ok((map, commands, map.lines().count() + 2))
 }
 
-pub(crate) fn parse_map(input: &str) -> Result<(HashMap<Coordinate, Entity>, Coordinate, (u32, u32)), BadDay15Error> {
-    let mut map: HashMap<Coordinate, Entity> = HashMap::new();
-    let mut robot_coordinate: Option<Coordinate> = None;
-    let mut max_h = 0;
-    let mut max_v = 0;
-
-    for (vertical, l) in input.trim().lines().enumerate() {
-        if vertical > max_v {
-            max_v = vertical;
-        }
-
-        for (horizontal, c) in l.chars().enumerate() {
-            if horizontal > max_h {
-                max_h = horizontal;
-            }
+/// `first_line` is where the moves start in the whole input, so errors can point at the right line.
+pub(crate) fn parse_commands(input: &str, first_line: usize) -> Result<Vec<Direction>, ParseError> {
+    let mut commands = Vec::new();
 
-            let e = match c {
-                '#' => Entity::Wall,
-                '.' => Entity::Empty,
-                'O' => Entity::Box,
-                '@' => {
-                    robot_coordinate = Some(Coordinate::try_from(horizontal, vertical));
-                    Entity::Robot
-                },
-                _ => {
-                    println!("Illegal character found at line {} char {}", vertical, horizontal
-                    );
-                    return Err(BadDay15Error{msg: format!("Unexpected character '{}'", c)})}
+    // Parse the commands
+    for (line, l) in input.lines().enumerate() {
+        for (idx, c) in l.trim_end().chars().enumerate() {
+            let command = match c {
+                '<' => Direction::Left,
+                '>' => Direction::Right,
+                '^' => Direction::Up,
+                'v' => Direction::Down,
+                _ => return Err(ParseError::at(first_line + line, idx + 1, format!("unexpected move '{}'", c))),
             };
 
-            // insert the first one as normal
-            map.insert(Coordinate::try_from(horizontal, vertical), e);
+            commands.push(command);
         }
     }
 
-    if robot_coordinate.is_none() {
-        return Err(BadDay15Error{msg: "No robot found".to_string()})
+    Ok(commands)
+}
+
+/// The parsed map and where the robot starts.
+pub(crate) type ParsedMap = (Grid<Entity>, Coordinate);
+
+pub(crate) fn parse_map(input: &str) -> Result<ParsedMap, ParseError> {
+    let map = Grid::parse(input, |c| match c {
+        '#' => Some(Entity::Wall),
+        '.' => Some(Entity::Empty),
+        'O' => Some(Entity::Box),
+        '@' => Some(Entity::Robot),
+        _ => None,
+    })?;
+
+    let robots = map.iter().filter(|(_, e)| *e == &Entity::Robot).map(|(c, _)| c).collect::<Vec<Coordinate>>();
+    let robot_coordinate = *robots.first().ok_or(ParseError::new("there is no robot on the map"))?;
+    if let Some(c) = robots.get(1) {
+        return Err(ParseError::at(c.vertical + 1, c.horizontal + 1, "there is more than one robot on the map"));
     }
 
-    Ok((map, robot_coordinate.unwrap(), (max_h as u32, max_v as u32)))
+    Ok((map, robot_coordinate))
 }
 
 
-pub(crate) fn solve(input: &str) -> u32 {
-    let mut d = Day15::try_from(input).unwrap();
+pub(crate) fn solve(input: &str) -> Result<u32, ParseError> {
+    let mut d = Day15::try_from(input)?;
     d.walk();
 
-    d.count_gps()
+    Ok(d.count_gps())
 }
\ No newline at end of file
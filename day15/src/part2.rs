@@ -1,94 +1,117 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::vec;
-use crate::part1::{parse_commands, parse_map, BadDay15Error, Coordinate, Day15, Entity, MoveCommand};
-
-
-struct Replacement {
-    depth: u32,
-    coordinate: Coordinate,
-    entity: Entity
+use common::ParseError;
+use grid::{Coordinate, Direction, Grid};
+use crate::part1::{parse_commands, parse_map, split_sections, Day15, Entity};
+
+
+/// A tile that's about to get a new entity. `depth` is how deep in the recursion of `push` the
+/// replacement was found, the shallower ones are applied first.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Replacement {
+    pub(crate) depth: u32,
+    pub(crate) coordinate: Coordinate,
+    pub(crate) entity: Entity
 }
 
 impl Replacement {
-    fn new(depth: u32, coordinate: Coordinate, entity: Entity) -> Self {
+    pub(crate) fn new(depth: u32, coordinate: Coordinate, entity: Entity) -> Self {
         Self { depth, coordinate, entity }
     }
 }
 
+/// A tile a move has overwritten: what was there before and what it got replaced with, in the
+/// order the writes happened. A tile can show up more than once, each with what that write did.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Overwrite {
+    pub(crate) depth: u32,
+    pub(crate) coordinate: Coordinate,
+    pub(crate) from: Entity,
+    pub(crate) to: Entity,
+}
+
 
 impl Day15 {
-    fn try_from_part2(input: &str) -> Result<Day15, BadDay15Error> {
-        let parts = input.trim().split("\n\n").collect::<Vec<_>>();
+    /// The widened map, for part 2.
+    pub fn try_from_part2(input: &str) -> Result<Day15, ParseError> {
+        let (map_section, commands_section, commands_line) = split_sections(input)?;
 
-        let (standard_map, _robot_coordinate, _bad_bounds) = parse_map(parts[0])?;
-        let (wide_map, (widest, tallest), robot) = widen_map(standard_map);
+        let (standard_map, _robot_coordinate) = parse_map(map_section)?;
+        let (wide_map, robot) = widen_map(&standard_map);
 
 
 
 
-        // println!("{}",draw_map(wide_map.clone(), (widest, tallest)));
+        // println!("{}",draw_map(&wide_map));
 
-        let commands = parse_commands(parts[1])?;
+        let commands = parse_commands(commands_section, commands_line)?;
         // println!("commands in order at start:\n{:?}", commands);
 
-        Ok(Day15::new(wide_map, commands, robot, (widest, tallest)))
+        Ok(Day15::new(wide_map, commands, robot, true))
     }
 
-    fn walk_part2(&mut self) {
-        for (i, c) in self.commands.clone().iter().enumerate() {
-            // println!("\
-            // ========================\n\
-            // Iteration {:3}: {:?}\n\
-            // ========================", i, c);
-            // println!("all right, let's start with robot at {:?} {{", self.robot);
-            if let Some(mut replacements) = self.push(Entity::Empty, Entity::Robot, self.robot, *c, 0) {
-                // println!("}} got the following replacements at the end of robots:\n{:?}", replacements);
+    pub(crate) fn walk_part2(&mut self) {
+        self.run_to(self.commands.len());
+    }
 
-                // sort replacements by their coordinate
-                replacements.sort_by(|a, b| a.depth.cmp(&b.depth));
+    /// Moves the robot on the wide map. Returns the tiles that changed, in the order they were
+    /// overwritten, which is nothing if the robot couldn't move.
+    pub(crate) fn execute_command_part2(&mut self, c: Direction) -> Vec<Overwrite> {
+        let mut previous_tiles: Vec<Overwrite> = Vec::new();
 
-                let mut replacements_done: HashSet<Coordinate> = HashSet::new();
+        // println!("all right, let's start with robot at {:?} {{", self.robot);
+        if let Some(mut replacements) = self.push(Entity::Empty, Entity::Robot, self.robot, c, 0) {
+            // println!("}} got the following replacements at the end of robots:\n{:?}", replacements);
 
-                for r in replacements.into_iter() {
-                    if r.entity == Entity::Robot {
-                        self.robot = r.coordinate;
-                    }
+            // sort replacements by their coordinate
+            replacements.sort_by_key(|r| r.depth);
 
-                    if replacements_done.contains(&r.coordinate) {
-                        continue;
-                    }
+            let mut replacements_done: HashSet<Coordinate> = HashSet::new();
 
-                    self.map.insert(r.coordinate, r.entity);
+            for r in replacements.into_iter() {
+                if r.entity == Entity::Robot {
+                    self.robot = r.coordinate;
+                }
 
-                    if r.entity != Entity::Empty {
-                        replacements_done.insert(r.coordinate);
-                    }
+                if replacements_done.contains(&r.coordinate) {
+                    continue;
+                }
+
+                if let Some(previous) = self.map.set(r.coordinate, r.entity) {
+                    previous_tiles.push(Overwrite { depth: r.depth, coordinate: r.coordinate, from: previous, to: r.entity });
+                }
+
+                if r.entity != Entity::Empty {
+                    replacements_done.insert(r.coordinate);
                 }
-            } else {
-                // println!("nah, apparently this was a None");
             }
-            //
-            // println!("map after moving {:?}", c);
-            // println!("{}", draw_map(self.map.clone(), self.bounds));
-            // println!("\n");
+        } else {
+            // println!("nah, apparently this was a None");
         }
+        //
+        // println!("map after moving {:?}", c);
+        // println!("{}", draw_map(&self.map));
+        // println!("\n");
+
+        previous_tiles
     }
 
 
     /// Pass in the previous entity, and the current coordinate. The previous entity is usually what
     /// is on the current coordinate at the current time, so when we move one tile in the direction,
     /// what the current one is going to be replaced with.
-    fn push(&self, replacement: Entity, current: Entity, what: Coordinate, which_way: MoveCommand, depth: u32) -> Option<Vec<Replacement>> {
+    fn push(&self, replacement: Entity, current: Entity, what: Coordinate, which_way: Direction, depth: u32) -> Option<Vec<Replacement>> {
         let _prefix = "  ".repeat(depth as usize);
         // println!("{}d{}: moving {:?} {:?} from {:?}, replacing it with {:?}", prefix, depth, current, which_way, what, replacement);
-        // what is the current coordinate, so we need to get the next one in the which way
-        let next = what.next(which_way);
+        // what is the current coordinate, so we need to get the next one in the which way. The
+        // edge of the map stops a push the same way a wall does.
+        let next = self.map.step(what, which_way)?;
         let mut replacements: Vec<Replacement> = vec![Replacement::new(depth, what, replacement)];
         // println!("{}d{}: replacements to start is {:?}", prefix, depth, replacements);
         // println!("{}d{}: checking the next coordinate {:?}", prefix, depth, next);
 
-        match self.map.get(&next).unwrap() {
+        match self.map[next] {
             Entity::Box => { panic!("we're in part 2, we should only have box left or box right..."); }
             Entity::Wall => {
                 // println!("{}d{}: next tile was a {:?}, returning None", prefix, depth, Entity::Wall);
@@ -105,7 +128,7 @@ impl Day15 {
             Entity::BoxLeft => {
                 // println!("{}d{}: encountered a BoxLeft [", prefix, depth);
                 match which_way {
-                    MoveCommand::Up|MoveCommand::Down => {
+                    Direction::Up|Direction::Down => {
                         // println!("{}d{}: moving towards {:?}", prefix, depth, which_way);
                         // println!("{}d{}: calling push for {:?} to be replaced by {:?}", prefix, depth, Entity::BoxLeft, current);
 
@@ -145,7 +168,7 @@ impl Day15 {
                         }
 
                         // println!("{}d{}: all right, pushing box {:?} was a Some, lets check the other side!", prefix, depth, which_way);
-                        let box_right_coordinate = next.next(MoveCommand::Right);
+                        let box_right_coordinate = self.map.step(next, Direction::Right)?;
 
                         // println!("{}d{}: original coordinate was {:?}, new one is {:?}", prefix, depth,
                         // what, box_right_coordinate);
@@ -180,13 +203,13 @@ impl Day15 {
 
                         Some(replacements)
                     }
-                    MoveCommand::Left => {
+                    Direction::Left => {
                         // means we're coming from the right, box left should never be encountered
                         // this way!
                         panic!("we're coming from the right moving left, encountering a [ should \
                         not have happened!")
                     }
-                    MoveCommand::Right => {
+                    Direction::Right => {
                         // we're encountering a BoxLeft moving Right
                         // ->[
                         // which means we can immediately add this into the replacements
@@ -195,7 +218,7 @@ impl Day15 {
                         replacements.push(Replacement::new(depth, next, current));
                         // println!("{}d{}: added BoxLeft and this coordinate to the replacements", prefix, depth);
 
-                        let skip = next.next(which_way); // skip one
+                        let skip = self.map.step(next, which_way)?; // skip one
                         // println!("{}d{}: skipping to find the ] at coordinate {:?}", prefix, depth, skip);
                         match self.push(Entity::BoxLeft, Entity::BoxRight, skip, which_way, depth + 1) {
                             Some(mut res) => {
@@ -215,7 +238,7 @@ impl Day15 {
             Entity::BoxRight => {
                 // println!("{}d{}: encountered a BoxRight", prefix, depth);
                 match which_way {
-                    MoveCommand::Up|MoveCommand::Down => {
+                    Direction::Up|Direction::Down => {
                         // println!("{}d{}: moving towards {:?}", prefix, depth, which_way);
                         // println!("{}d{}: calling push for {:?} to be replaced by {:?}", prefix, depth, Entity::BoxRight, current);
 
@@ -242,7 +265,7 @@ impl Day15 {
                             return Some(replacements);
                         }
 
-                        let box_left_coordinate = next.next(MoveCommand::Left);
+                        let box_left_coordinate = self.map.step(next, Direction::Left)?;
                         // println!("{}d{}: original coordinate was {:?}, new one is (to the left and vertical dir +1) {:?}", prefix, depth,
                         //          what, box_left_coordinate);
 
@@ -265,14 +288,14 @@ impl Day15 {
 
                         Some(replacements)
                     }
-                    MoveCommand::Left => {
+                    Direction::Left => {
                         // we have encountered a box rith ] while moving left
                         // []<-
                         // means we're coming from the left, so encountering box right ] is expected
                         // println!("{}d{}: we're going to add this BoxRight at {:?} to the replacements", prefix,  depth, next);
                         replacements.push(Replacement::new(depth, next, current));
 
-                        let skip = next.next(which_way); // skip one
+                        let skip = self.map.step(next, which_way)?; // skip one
                         // println!("{}d{}: skipping to find the [ at coordinate {:?}", prefix, depth, skip);
 
                         match self.push(Entity::BoxRight, Entity::BoxLeft, skip, which_way, depth + 1) {
@@ -290,7 +313,7 @@ impl Day15 {
                         }
 
                     }
-                    MoveCommand::Right => {
+                    Direction::Right => {
                         // means we're coming from the right, box left should never be encountered
                         // this way!
                         panic!("we're coming from the left moving right, encountering a ] should \
@@ -301,13 +324,13 @@ impl Day15 {
         }
     }
 
-    fn count_gps_part2(&self) -> u32 {
+    pub(crate) fn count_gps_part2(&self) -> u32 {
         self.map.iter()
             .filter(|&(_k, v)| v == &Entity::BoxLeft)
             .collect::<HashMap<_, _>>()
             .iter()
             .fold(0, |acc, (&k, &_v)| {
-                acc + k.horizontal + 100 * k.vertical
+                acc + k.horizontal as u32 + 100 * k.vertical as u32
         })
     }
 
@@ -360,70 +383,44 @@ impl Day15 {
     // }
 }
 
-fn widen_map(source: HashMap<Coordinate, Entity>) -> (HashMap<Coordinate, Entity>, (u32, u32), Coordinate) {
-    let mut widest = 0;
-    let mut tallest = 0;
-
+fn widen_map(source: &Grid<Entity>) -> (Grid<Entity>, Coordinate) {
     let mut robot = Coordinate::new(0, 0);
-    let mut wide_map: HashMap<Coordinate, Entity> = HashMap::new();
-    for (c, e) in source.clone().into_iter() {
-        if c.horizontal > widest { widest = c.horizontal }
-        if c.vertical > tallest { tallest = c.vertical }
+    let mut tiles: Vec<Entity> = Vec::new();
 
+    for (c, e) in source.iter() {
         match e {
             Entity::Robot => {
                 // '@' -> '@.'
-                wide_map.insert(Coordinate::new(c.horizontal * 2, c.vertical), e);
                 robot = Coordinate::new(c.horizontal * 2, c.vertical);
-                wide_map.insert(Coordinate::new(c.horizontal * 2 + 1, c.vertical), Entity::Empty);
+                tiles.extend([Entity::Robot, Entity::Empty]);
             }
             Entity::Box => {
                 // 'O' -> '[]'
-                wide_map.insert(Coordinate::new(c.horizontal * 2, c.vertical), Entity::BoxLeft);
-                wide_map.insert(Coordinate::new(c.horizontal * 2 + 1, c.vertical), Entity::BoxRight);
+                tiles.extend([Entity::BoxLeft, Entity::BoxRight]);
             }
             _ => {
                 // '.' -> '..'
                 // '#' -> '##'
-                wide_map.insert(Coordinate::new(c.horizontal * 2, c.vertical), e);
-                wide_map.insert(Coordinate::new(c.horizontal * 2 + 1, c.vertical), e);
+                tiles.extend([*e, *e]);
             }
         }
     }
 
-    (wide_map, (widest*2+1, tallest), robot)
+    (Grid::from_tiles(source.width() * 2, tiles), robot)
 }
 
-fn draw_map(map: HashMap<Coordinate, Entity>, bounds: (u32, u32)) -> String {
-    let mut s = "".to_string();
-
-    for vertical in 0..=bounds.1 {
-        for horizontal in 0..=bounds.0 {
-            let tile = match map.get(&Coordinate::new(horizontal, vertical)).unwrap() {
-                Entity::Box => { "O" }
-                Entity::Wall => { "#" }
-                Entity::Robot => { "@" }
-                Entity::Empty => { "." }
-                Entity::BoxLeft => { "[" }
-                Entity::BoxRight => { "]" }
-            };
-
-            s += tile
-        }
-
-        s += "\n";
-    }
-
-    s
+#[allow(dead_code)]
+fn draw_map(map: &Grid<Entity>) -> String {
+    format!("{}\n", map)
 }
 
-
 /// Returns a sort function that will put the vecs in application order depending on which way
 /// the push was going. For off-axis ordering it's always top to bottom or left to right given
 /// same in-axis values.
-fn sort_function(direction: MoveCommand) -> fn(&Replacement, &Replacement) -> Ordering {
+#[allow(dead_code)]
+fn sort_function(direction: Direction) -> fn(&Replacement, &Replacement) -> Ordering {
     match direction {
-        MoveCommand::Up => {
+        Direction::Up => {
             |a: &Replacement, b: &Replacement| -> Ordering {
                 // we're going up, so the coordinate with the lower vertical wins
                 if a.coordinate.vertical == b.coordinate.vertical {
@@ -433,7 +430,7 @@ fn sort_function(direction: MoveCommand) -> fn(&Replacement, &Replacement) -> Or
                 a.coordinate.vertical.cmp(&b.coordinate.vertical)
             }
         }
-        MoveCommand::Left => {
+        Direction::Left => {
             |a: &Replacement, b: &Replacement| -> Ordering {
                 // we're going left, so the coordinate with the lower horizontal wins
                 if a.coordinate.horizontal == b.coordinate.horizontal {
@@ -443,7 +440,7 @@ fn sort_function(direction: MoveCommand) -> fn(&Replacement, &Replacement) -> Or
                 a.coordinate.horizontal.cmp(&b.coordinate.horizontal)
             }
         }
-        MoveCommand::Down => {
+        Direction::Down => {
             |a: &Replacement, b: &Replacement| -> Ordering {
 
                 // we're going down, so the coordinate with the higher vertical wins
@@ -454,7 +451,7 @@ fn sort_function(direction: MoveCommand) -> fn(&Replacement, &Replacement) -> Or
                 b.coordinate.vertical.cmp(&a.coordinate.vertical)
             }
         }
-        MoveCommand::Right => {
+        Direction::Right => {
             |a: &Replacement, b: &Replacement| -> Ordering {
                 // we're going right, so the coordinate with the higher horizontal wins
                 if a.coordinate.horizontal == b.coordinate.horizontal {
@@ -468,8 +465,8 @@ fn sort_function(direction: MoveCommand) -> fn(&Replacement, &Replacement) -> Or
 }
 
 
-pub(crate) fn solve(input: &str) -> u32 {
-    let mut d = Day15::try_from_part2(input).unwrap();
+pub(crate) fn solve(input: &str) -> Result<u32, ParseError> {
+    let mut d = Day15::try_from_part2(input)?;
     // println!("created d");
 
     d.walk_part2();
@@ -477,7 +474,7 @@ pub(crate) fn solve(input: &str) -> u32 {
 
     // println!("okay, so counting gps");
 
-    d.count_gps_part2()
+    Ok(d.count_gps_part2())
 }
 
 #[cfg(test)]
@@ -502,7 +499,7 @@ mod tests {
         ##..@[].##\n\
         ##########\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -523,7 +520,7 @@ mod tests {
         ##.[]@..##\n\
         ##########\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -548,7 +545,7 @@ mod tests {
         ##......##\n\
         ##########\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -573,7 +570,7 @@ mod tests {
         ##..[]..##\n\
         ##########\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -598,7 +595,7 @@ mod tests {
         ##........##\n\
         ############\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -623,7 +620,7 @@ mod tests {
         ##........##\n\
         ############\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -650,7 +647,7 @@ mod tests {
         ##........##\n\
         ############\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -685,7 +682,7 @@ mod tests {
         ##........##\n\
         ############\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -720,7 +717,7 @@ mod tests {
         ##........##\n\
         ############\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
 
@@ -750,7 +747,7 @@ mod tests {
         ##..[][][]..##\n\
         ##############\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -777,7 +774,7 @@ mod tests {
         ##........##\n\
         ############\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -804,7 +801,7 @@ mod tests {
         ##........##\n\
         ############\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -831,7 +828,7 @@ mod tests {
         ##........##\n\
         ############\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -860,7 +857,7 @@ mod tests {
         ##........##\n\
         ############\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -889,7 +886,7 @@ mod tests {
         ##....[]..##\n\
         ############\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     #[test]
@@ -918,12 +915,12 @@ mod tests {
         ##.@........##\n\
         ##############\n";
 
-        assert_eq!(want_map, draw_map(d.map, d.bounds));
+        assert_eq!(want_map, draw_map(&d.map));
     }
 
     // #[test]
     // fn test_sorter_up(){
-    //     let up_sorter = sort_function(MoveCommand::Up);
+    //     let up_sorter = sort_function(Direction::Up);
     //
     //     let mut input: Vec<Replacement> = vec![
     //         (Coordinate::new(0,0), Entity::Empty),
@@ -956,7 +953,7 @@ mod tests {
     //
     // #[test]
     // fn test_sorter_down(){
-    //     let down_sorter = sort_function(MoveCommand::Down);
+    //     let down_sorter = sort_function(Direction::Down);
     //
     //     let mut input: Vec<(Coordinate, Entity)> = vec![
     //         (Coordinate::new(0,0), Entity::Empty),
@@ -989,7 +986,7 @@ mod tests {
     //
     // #[test]
     // fn test_sorter_left(){
-    //     let left_sorter = sort_function(MoveCommand::Left);
+    //     let left_sorter = sort_function(Direction::Left);
     //
     //     let mut input: Vec<(Coordinate, Entity)> = vec![
     //         (Coordinate::new(0,0), Entity::Empty),
@@ -1022,7 +1019,7 @@ mod tests {
     //
     // #[test]
     // fn test_sorter_right(){
-    //     let right_sorter = sort_function(MoveCommand::Right);
+    //     let right_sorter = sort_function(Direction::Right);
     //
     //     let mut input: Vec<(Coordinate, Entity)> = vec![
     //         (Coordinate::new(0,0), Entity::Empty),
@@ -0,0 +1,364 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use common::ParseError;
+use grid::{Coordinate, Direction, Grid};
+use crate::part1::{parse_commands, parse_map, split_sections, Entity};
+
+/// What's on a tile of a warehouse where boxes can be any shape. A box tile says which box it's
+/// part of.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Wall,
+    Robot,
+    Box(usize),
+}
+
+/// A box, as the tiles it covers and the character each of them is drawn with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Shape {
+    cells: Vec<(Coordinate, char)>,
+}
+
+impl Shape {
+    /// The GPS coordinate the same way `count_gps_part2` works it out: distance from the top edge
+    /// of the map to the top of the box, and from the left edge to the left of the box.
+    fn gps(&self) -> u32 {
+        let top = self.cells.iter().map(|(c, _)| c.vertical).min().unwrap_or(0);
+        let left = self.cells.iter().map(|(c, _)| c.horizontal).min().unwrap_or(0);
+
+        100 * top as u32 + left as u32
+    }
+}
+
+/// A warehouse where a box can cover any set of tiles, so it can be a wider box than part 2's, or
+/// any polyomino. Pushing a box pushes every box any of its tiles runs into, and nothing moves if
+/// any of them would run into a wall.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapedWarehouse {
+    map: Grid<Cell>,
+    boxes: Vec<Shape>,
+    robot: Coordinate,
+    commands: Vec<Direction>,
+}
+
+impl ShapedWarehouse {
+    /// The puzzle input with every tile widened `factor` times, the same way part 2 doubles them.
+    /// A box becomes a single box `factor` tiles wide, so a factor of 1 is the map of part 1 and 2
+    /// is the map of part 2.
+    pub fn widened(input: &str, factor: usize) -> Result<ShapedWarehouse, ParseError> {
+        if factor == 0 {
+            return Err(ParseError::new("the map can't be widened 0 times"));
+        }
+
+        let (map_section, commands_section, commands_line) = split_sections(input)?;
+        let (narrow, _robot) = parse_map(map_section)?;
+        let commands = parse_commands(commands_section, commands_line)?;
+
+        let mut map = Grid::new(narrow.width() * factor, narrow.height(), Cell::Empty);
+        let mut boxes = Vec::new();
+        let mut robot = Coordinate::new(0, 0);
+
+        for (c, e) in narrow.iter() {
+            let first = Coordinate::new(c.horizontal * factor, c.vertical);
+            let tiles = (0..factor).map(|i| Coordinate::new(first.horizontal + i, first.vertical));
+
+            match e {
+                Entity::Wall => tiles.for_each(|t| map[t] = Cell::Wall),
+                Entity::Robot => {
+                    // the robot doesn't get wider, the rest of its tiles are empty
+                    map[first] = Cell::Robot;
+                    robot = first;
+                }
+                Entity::Box => {
+                    let cells = tiles.enumerate().map(|(i, t)| (t, wide_box_char(i, factor))).collect();
+                    add_box(&mut map, &mut boxes, cells);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(ShapedWarehouse { map, boxes, robot, commands })
+    }
+
+    /// A map where the boxes are drawn as they are. `O` is a box of one tile, `[` and `]` with any
+    /// number of `=` between them on the same row is a wide box, and a group of touching tiles with
+    /// the same letter is a box of that shape. Two boxes next to each other need different letters,
+    /// otherwise they're the same box.
+    pub fn try_from(input: &str) -> Result<ShapedWarehouse, ParseError> {
+        let (map_section, commands_section, commands_line) = split_sections(input)?;
+        let commands = parse_commands(commands_section, commands_line)?;
+
+        let drawn = Grid::parse(map_section, |c| match c {
+            '#' | '.' | '@' | 'O' | '[' | '=' | ']' => Some(c),
+            _ if c.is_ascii_alphabetic() => Some(c),
+            _ => None,
+        })?;
+
+        let mut map = Grid::new(drawn.width(), drawn.height(), Cell::Empty);
+        let mut boxes: Vec<Shape> = Vec::new();
+        let mut robot = None;
+
+        for (c, &tile) in drawn.iter() {
+            match tile {
+                '#' => map[c] = Cell::Wall,
+                '@' if robot.is_some() => return Err(error_at(c, "there is more than one robot on the map")),
+                '@' => {
+                    map[c] = Cell::Robot;
+                    robot = Some(c);
+                }
+                '.' => {}
+                // these are picked up with the tiles they're part of
+                _ if map[c] != Cell::Empty => {}
+                'O' => add_box(&mut map, &mut boxes, vec![(c, 'O')]),
+                '[' => add_box(&mut map, &mut boxes, wide_box(&drawn, c)?),
+                '=' | ']' => return Err(error_at(c, format!("'{}' without a '[' before it", tile))),
+                _ => add_box(&mut map, &mut boxes, lettered_box(&drawn, c)),
+            }
+        }
+
+        let robot = robot.ok_or(ParseError::new("there is no robot on the map"))?;
+
+        Ok(ShapedWarehouse { map, boxes, robot, commands })
+    }
+
+    pub fn robot(&self) -> Coordinate {
+        self.robot
+    }
+
+    pub fn box_count(&self) -> usize {
+        self.boxes.len()
+    }
+
+    /// Runs every command.
+    pub fn walk(&mut self) {
+        for i in 0..self.commands.len() {
+            self.execute_command(self.commands[i]);
+        }
+    }
+
+    /// Moves the robot, and everything it pushes. Returns whether anything moved.
+    pub fn execute_command(&mut self, direction: Direction) -> bool {
+        let Some(moving) = self.push(direction) else {
+            return false;
+        };
+
+        // everything is lifted off the map first, so boxes moving into each other's old tiles don't
+        // overwrite one another
+        for &id in moving.iter() {
+            for (c, _) in self.boxes[id].cells.iter() {
+                self.map[*c] = Cell::Empty;
+            }
+        }
+
+        for &id in moving.iter() {
+            for (c, _) in self.boxes[id].cells.iter_mut() {
+                // push already made sure none of these go off the map
+                *c = self.map.step(*c, direction).unwrap_or(*c);
+                self.map[*c] = Cell::Box(id);
+            }
+        }
+
+        self.map[self.robot] = Cell::Empty;
+        self.robot = self.map.step(self.robot, direction).unwrap_or(self.robot);
+        self.map[self.robot] = Cell::Robot;
+
+        true
+    }
+
+    /// Works out which boxes move if the robot goes `direction`, or `None` if something runs into a
+    /// wall or the edge of the map. Every tile that's going to move has to have room in front of
+    /// it, or another tile that's going to move.
+    fn push(&self, direction: Direction) -> Option<Vec<usize>> {
+        let mut moving: Vec<usize> = Vec::new();
+        let mut seen: HashSet<usize> = HashSet::new();
+        let mut tiles = vec![self.robot];
+
+        while let Some(tile) = tiles.pop() {
+            let next = self.map.step(tile, direction)?;
+
+            match self.map[next] {
+                Cell::Wall => return None,
+                Cell::Box(id) if seen.insert(id) => {
+                    moving.push(id);
+                    tiles.extend(self.boxes[id].cells.iter().map(|(c, _)| *c));
+                }
+                _ => {}
+            }
+        }
+
+        Some(moving)
+    }
+
+    /// The sum of the GPS coordinates of every box, measured to the top left of the box.
+    pub fn gps(&self) -> u32 {
+        self.boxes.iter().map(Shape::gps).sum()
+    }
+}
+
+/// Puts a new box on the map, its id is where it is in `boxes`.
+fn add_box(map: &mut Grid<Cell>, boxes: &mut Vec<Shape>, cells: Vec<(Coordinate, char)>) {
+    for (c, _) in cells.iter() {
+        map[*c] = Cell::Box(boxes.len());
+    }
+
+    boxes.push(Shape { cells });
+}
+
+/// How the `i`th tile of a box `width` tiles wide is drawn.
+fn wide_box_char(i: usize, width: usize) -> char {
+    match (i, width) {
+        (_, 1) => 'O',
+        (0, _) => '[',
+        (i, w) if i == w - 1 => ']',
+        _ => '=',
+    }
+}
+
+fn error_at(c: Coordinate, message: impl Into<String>) -> ParseError {
+    ParseError::at(c.vertical + 1, c.horizontal + 1, message)
+}
+
+/// The tiles of a box that starts with a `[` at `start`, up to and including the `]`.
+fn wide_box(drawn: &Grid<char>, start: Coordinate) -> Result<Vec<(Coordinate, char)>, ParseError> {
+    let mut cells = vec![(start, '[')];
+    let mut current = start;
+
+    loop {
+        match drawn.step(current, Direction::Right).map(|c| (c, drawn[c])) {
+            Some((c, '=')) => {
+                cells.push((c, '='));
+                current = c;
+            }
+            Some((c, ']')) => {
+                cells.push((c, ']'));
+                return Ok(cells);
+            }
+            _ => return Err(error_at(start, "'[' without a ']' after it")),
+        }
+    }
+}
+
+/// Every tile with the same letter as `start` that can be reached from it without leaving the
+/// letter.
+fn lettered_box(drawn: &Grid<char>, start: Coordinate) -> Vec<(Coordinate, char)> {
+    let letter = drawn[start];
+    let mut seen: HashSet<Coordinate> = HashSet::from([start]);
+    let mut queue = vec![start];
+    let mut cells = Vec::new();
+
+    while let Some(c) = queue.pop() {
+        cells.push((c, letter));
+
+        for n in drawn.neighbours(c) {
+            if drawn[n] == letter && seen.insert(n) {
+                queue.push(n);
+            }
+        }
+    }
+
+    cells
+}
+
+/// The map the same way it would be read in.
+impl Display for ShapedWarehouse {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut drawn = self.map.map(|cell| match cell {
+            Cell::Empty | Cell::Box(_) => '.',
+            Cell::Wall => '#',
+            Cell::Robot => '@',
+        });
+
+        for shape in self.boxes.iter() {
+            for &(c, ch) in shape.cells.iter() {
+                drawn[c] = ch;
+            }
+        }
+
+        write!(f, "{}", drawn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{solve_part1, solve_part2};
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../example.txt");
+    const TINY: &str = include_str!("../tinyexample.txt");
+
+    #[test]
+    fn test_widened_matches_both_parts() {
+        for input in [EXAMPLE, TINY] {
+            let mut narrow = ShapedWarehouse::widened(input, 1).unwrap();
+            let mut wide = ShapedWarehouse::widened(input, 2).unwrap();
+            narrow.walk();
+            wide.walk();
+
+            assert_eq!(narrow.gps(), solve_part1(input).unwrap());
+            assert_eq!(wide.gps(), solve_part2(input).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_widened_three_times() {
+        let mut d = ShapedWarehouse::widened("#####\n#@O.#\n#####\n\n>>>\n", 3).unwrap();
+        assert_eq!(d.to_string(), "###############\n###@..[=]...###\n###############");
+
+        d.walk();
+        assert_eq!(d.to_string(), "###############\n###...@[=]..###\n###############");
+        assert_eq!(d.gps(), 100 + 7);
+        assert!(ShapedWarehouse::widened("#####\n#@O.#\n#####\n\n>\n", 0).is_err());
+    }
+
+    #[test]
+    fn test_polyomino_push() {
+        // the L is pushed up by its foot, and takes the box on top of its other arm along
+        let mut d = ShapedWarehouse::try_from("\
+#######
+#.....#
+#...O.#
+#.LLL.#
+#.L...#
+#.@...#
+#######
+
+^>^
+").unwrap();
+        assert_eq!(d.box_count(), 2);
+
+        assert!(d.execute_command(Direction::Up));
+        assert_eq!(d.to_string(), "\
+#######
+#...O.#
+#.LLL.#
+#.L...#
+#.@...#
+#.....#
+#######");
+        assert_eq!(d.gps(), 100 + 4 + 200 + 2);
+
+        // the box on top is against the wall now, so it holds the L back
+        assert!(!d.execute_command(Direction::Up));
+        assert!(d.execute_command(Direction::Right));
+        assert!(d.execute_command(Direction::Up));
+        assert_eq!(d.robot(), Coordinate::new(3, 3));
+    }
+
+    #[test]
+    fn test_try_from_reads_wide_boxes() {
+        let mut d = ShapedWarehouse::try_from("########\n#@[=]O.#\n########\n\n>>\n").unwrap();
+        assert_eq!(d.box_count(), 2);
+
+        d.walk();
+        assert_eq!(d.to_string(), "########\n#.@[=]O#\n########");
+        assert_eq!(
+            ShapedWarehouse::try_from("#####\n#@[.#\n#####\n\n>\n").err(),
+            Some(ParseError::at(2, 3, "'[' without a ']' after it"))
+        );
+        assert_eq!(
+            ShapedWarehouse::try_from("#####\n#@.@#\n#####\n\n>\n").err(),
+            Some(ParseError::at(2, 4, "there is more than one robot on the map"))
+        );
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt::{Display, Formatter};
+use grid::{Coordinate, Direction};
+use crate::part1::Day15;
+use crate::part2::Overwrite;
+
+/// What a single move changed, so it can be taken back.
+pub(crate) struct Step {
+    /// The tiles the move changed, in the order they were overwritten.
+    previous_tiles: Vec<Overwrite>,
+    robot: Coordinate,
+    /// Whether this was the next command in the list, rather than a move made up on the spot.
+    from_commands: bool,
+}
+
+impl Day15 {
+    /// Runs the next command. Returns whether the robot moved, or `None` if there are no commands
+    /// left.
+    pub fn step(&mut self) -> Option<bool> {
+        let command = *self.commands.get(self.next_command)?;
+        self.next_command += 1;
+
+        Some(self.apply(command, true))
+    }
+
+    /// Runs up to `n` of the next commands, and returns how many there were.
+    pub fn step_n(&mut self, n: usize) -> usize {
+        (0..n).take_while(|_| self.step().is_some()).count()
+    }
+
+    /// Steps or undoes until the command at `index` is the next one to run. Anything past the end
+    /// of the list runs every command.
+    pub fn run_to(&mut self, index: usize) {
+        let index = index.min(self.commands.len());
+
+        while self.next_command > index {
+            if !self.undo() {
+                break;
+            }
+        }
+
+        while self.next_command < index {
+            self.step();
+        }
+    }
+
+    /// Moves the robot in the given direction, whatever the next command would be. The move is
+    /// remembered so it can be undone like any other. Returns whether the robot moved.
+    pub fn move_robot(&mut self, direction: Direction) -> bool {
+        self.apply(direction, false)
+    }
+
+    /// Takes back the last move, whether it was a command or not. Returns false if there's nothing
+    /// left to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(step) = self.history.pop() else {
+            return false;
+        };
+
+        for r in step.previous_tiles.into_iter().rev() {
+            self.map.set(r.coordinate, r.from);
+        }
+
+        self.robot = step.robot;
+        if step.from_commands {
+            self.next_command -= 1;
+        }
+
+        true
+    }
+
+    /// The sum of the GPS coordinates of every box, counted the way the part this map is for does.
+    pub fn gps(&self) -> u32 {
+        match self.wide {
+            true => self.count_gps_part2(),
+            false => self.count_gps(),
+        }
+    }
+
+    pub fn commands(&self) -> &[Direction] {
+        &self.commands
+    }
+
+    /// Index of the command the next step is going to run.
+    pub fn next_command(&self) -> usize {
+        self.next_command
+    }
+
+    pub fn robot(&self) -> Coordinate {
+        self.robot
+    }
+
+    fn apply(&mut self, direction: Direction, from_commands: bool) -> bool {
+        let robot = self.robot;
+        let previous_tiles = match self.wide {
+            true => self.execute_command_part2(direction),
+            false => self.execute_command(direction),
+        };
+        let moved = !previous_tiles.is_empty();
+
+        let index = from_commands.then(|| self.next_command - 1);
+        self.record(index, direction, &previous_tiles);
+        self.history.push(Step { previous_tiles, robot, from_commands });
+
+        moved
+    }
+}
+
+/// The map the same way the puzzle draws it.
+impl Display for Day15 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL: &str = "\
+#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^
+";
+
+    #[test]
+    fn test_step_and_undo() {
+        for mut d in [Day15::try_from(SMALL).unwrap(), Day15::try_from_part2(SMALL).unwrap()] {
+            let start = d.to_string();
+
+            assert_eq!(d.step(), Some(true));
+            let after_one = d.to_string();
+            assert_ne!(start, after_one);
+
+            assert_eq!(d.step_n(100), d.commands().len() - 1);
+            assert_eq!(d.step(), None);
+            let end = d.to_string();
+
+            d.run_to(1);
+            assert_eq!(d.next_command(), 1);
+            assert_eq!(d.to_string(), after_one);
+
+            d.run_to(usize::MAX);
+            assert_eq!(d.to_string(), end);
+
+            while d.undo() {}
+            assert_eq!(d.next_command(), 0);
+            assert_eq!(d.to_string(), start);
+        }
+    }
+
+    #[test]
+    fn test_move_robot() {
+        let mut d = Day15::try_from(SMALL).unwrap();
+
+        // there's a wall right of the robot
+        assert!(!d.move_robot(Direction::Right));
+        assert!(d.move_robot(Direction::Left));
+        assert_eq!(d.robot(), Coordinate::new(4, 3));
+        assert_eq!(d.next_command(), 0);
+
+        assert!(d.undo());
+        assert!(d.undo());
+        assert!(!d.undo());
+        assert_eq!(d.robot(), Coordinate::new(5, 3));
+        assert_eq!(d.gps(), 100 * 3 + 3 + 100 * 3 + 4 + 100 * 4 + 3);
+    }
+}
@@ -0,0 +1,296 @@
+use std::collections::{BTreeSet, HashSet};
+use common::ParseError;
+use grid::{Coordinate, Direction};
+use crate::part1::{Day15, Entity};
+use crate::part2::Overwrite;
+
+/// One tile a move changed. `depth` is how deep in the push the change was found: on the narrow map
+/// that's how many tiles away from the robot it is, on the wide one it's the recursion depth of
+/// `push`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TracedTile {
+    pub at: Coordinate,
+    pub from: char,
+    pub to: char,
+    pub depth: u32,
+}
+
+/// Everything a single move did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    /// Which command in the list this was, `None` for moves made with `move_robot`.
+    pub index: Option<usize>,
+    pub command: Direction,
+    pub blocked: bool,
+    /// Where the robot is after the move.
+    pub robot: Coordinate,
+    /// Where the boxes that moved were before the move, top to bottom, left to right. A wide box is
+    /// where its left half is.
+    pub boxes_moved: Vec<Coordinate>,
+    /// The tiles in the order they were overwritten.
+    pub tiles: Vec<TracedTile>,
+}
+
+impl TraceEntry {
+    /// The entry on a single line of JSON. Coordinates are `[horizontal, vertical]`, tiles are the
+    /// characters the puzzle draws them with.
+    pub fn to_json(&self) -> String {
+        let index = self.index.map_or("null".to_string(), |i| i.to_string());
+        let boxes = self.boxes_moved.iter().map(json_coordinate).collect::<Vec<String>>();
+        let tiles = self.tiles.iter()
+            .map(|t| format!(
+                "{{\"at\":{},\"from\":\"{}\",\"to\":\"{}\",\"depth\":{}}}",
+                json_coordinate(&t.at), t.from, t.to, t.depth
+            ))
+            .collect::<Vec<String>>();
+
+        format!(
+            "{{\"index\":{},\"command\":\"{:?}\",\"blocked\":{},\"robot\":{},\"boxes_moved\":[{}],\"tiles\":[{}]}}",
+            index,
+            self.command,
+            self.blocked,
+            json_coordinate(&self.robot),
+            boxes.join(","),
+            tiles.join(","),
+        )
+    }
+}
+
+fn json_coordinate(c: &Coordinate) -> String {
+    format!("[{},{}]", c.horizontal, c.vertical)
+}
+
+/// The entries as JSON Lines, one move per line.
+pub fn to_json_lines(entries: &[TraceEntry]) -> String {
+    entries.iter().map(|e| e.to_json() + "\n").collect()
+}
+
+/// Where two traces first disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    /// 1 based, which is also the number of the move, counting from one.
+    pub line: usize,
+    /// `None` if this trace ran out of lines before the other one.
+    pub ours: Option<String>,
+    pub reference: Option<String>,
+}
+
+/// Compares two JSON Lines traces line by line, and returns the first line that's not the same.
+/// Trailing whitespace on a line doesn't count.
+pub fn first_difference(ours: &str, reference: &str) -> Option<Difference> {
+    let mut ours = ours.lines().map(str::trim_end);
+    let mut reference = reference.lines().map(str::trim_end);
+    let mut line = 0;
+
+    loop {
+        line += 1;
+
+        match (ours.next(), reference.next()) {
+            (None, None) => return None,
+            (a, b) if a == b => continue,
+            (a, b) => {
+                return Some(Difference {
+                    line,
+                    ours: a.map(str::to_string),
+                    reference: b.map(str::to_string),
+                })
+            }
+        }
+    }
+}
+
+impl Day15 {
+    /// Starts recording every move from here on, throwing away anything recorded so far.
+    pub fn start_trace(&mut self) {
+        self.trace = Some(Vec::new());
+    }
+
+    /// Stops recording, and hands back what was recorded. Empty if tracing wasn't on.
+    pub fn take_trace(&mut self) -> Vec<TraceEntry> {
+        self.trace.take().unwrap_or_default()
+    }
+
+    /// Adds the move that's just been made to the trace, if there is one.
+    pub(crate) fn record(&mut self, index: Option<usize>, command: Direction, previous_tiles: &[Overwrite]) {
+        let Some(trace) = self.trace.as_mut() else {
+            return;
+        };
+
+        let mut seen: HashSet<Coordinate> = HashSet::new();
+        let mut boxes: BTreeSet<(usize, usize)> = BTreeSet::new();
+
+        for r in previous_tiles {
+            // a tile can be overwritten more than once in the same move, the first time has what
+            // was there before the move
+            if seen.insert(r.coordinate) && matches!(r.from, Entity::Box | Entity::BoxLeft) {
+                boxes.insert((r.coordinate.vertical, r.coordinate.horizontal));
+            }
+        }
+
+        let tiles = previous_tiles.iter()
+            .map(|r| TracedTile {
+                at: r.coordinate,
+                from: entity_char(r.from),
+                to: entity_char(r.to),
+                depth: r.depth,
+            })
+            .collect();
+
+        trace.push(TraceEntry {
+            index,
+            command,
+            blocked: previous_tiles.is_empty(),
+            robot: self.robot,
+            boxes_moved: boxes.into_iter().map(|(v, h)| Coordinate::new(h, v)).collect(),
+            tiles,
+        });
+    }
+}
+
+fn entity_char(e: Entity) -> char {
+    e.to_string().chars().next().unwrap_or('?')
+}
+
+/// Walks the narrow map of part 1 with tracing on.
+pub fn trace_part1(input: &str) -> Result<Vec<TraceEntry>, ParseError> {
+    let mut d = Day15::try_from(input)?;
+
+    d.start_trace();
+    d.walk();
+
+    Ok(d.take_trace())
+}
+
+/// Walks the wide map of part 2 with tracing on.
+pub fn trace_part2(input: &str) -> Result<Vec<TraceEntry>, ParseError> {
+    let mut d = Day15::try_from_part2(input)?;
+
+    d.start_trace();
+    d.walk_part2();
+
+    Ok(d.take_trace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TINY: &str = include_str!("../tinyexample.txt");
+
+    #[test]
+    fn test_trace_part1() {
+        let trace = trace_part1("#####\n#@O.#\n#...#\n#####\n\n>>v\n").unwrap();
+
+        assert_eq!(trace.len(), 3);
+        assert_eq!(trace[0].to_json(), "\
+{\"index\":0,\"command\":\"Right\",\"blocked\":false,\"robot\":[2,1],\"boxes_moved\":[[2,1]],\"tiles\":[\
+{\"at\":[1,1],\"from\":\"@\",\"to\":\".\",\"depth\":0},\
+{\"at\":[2,1],\"from\":\"O\",\"to\":\"@\",\"depth\":1},\
+{\"at\":[3,1],\"from\":\".\",\"to\":\"O\",\"depth\":2}]}");
+        assert!(trace[1].blocked);
+        assert!(trace[1].tiles.is_empty());
+        assert_eq!(trace[2].boxes_moved, vec![]);
+        assert_eq!(trace[2].robot, Coordinate::new(2, 2));
+    }
+
+    /// The map after each move of the part 2 example, copied from the puzzle.
+    const TINY_STEPS: [&str; 12] = [
+        "##############\n##......##..##\n##..........##\n##....[][]@.##\n##....[]....##\n##..........##\n##############\n",
+        "##############\n##......##..##\n##..........##\n##...[][]@..##\n##....[]....##\n##..........##\n##############\n",
+        "##############\n##......##..##\n##..........##\n##...[][]...##\n##....[].@..##\n##..........##\n##############\n",
+        "##############\n##......##..##\n##..........##\n##...[][]...##\n##....[]....##\n##.......@..##\n##############\n",
+        "##############\n##......##..##\n##..........##\n##...[][]...##\n##....[]....##\n##......@...##\n##############\n",
+        "##############\n##......##..##\n##..........##\n##...[][]...##\n##....[]....##\n##.....@....##\n##############\n",
+        "##############\n##......##..##\n##...[][]...##\n##....[]....##\n##.....@....##\n##..........##\n##############\n",
+        "##############\n##......##..##\n##...[][]...##\n##....[]....##\n##.....@....##\n##..........##\n##############\n",
+        "##############\n##......##..##\n##...[][]...##\n##....[]....##\n##....@.....##\n##..........##\n##############\n",
+        "##############\n##......##..##\n##...[][]...##\n##....[]....##\n##...@......##\n##..........##\n##############\n",
+        "##############\n##......##..##\n##...[][]...##\n##...@[]....##\n##..........##\n##..........##\n##############\n",
+        "##############\n##...[].##..##\n##...@.[]...##\n##....[]....##\n##..........##\n##..........##\n##############\n",
+    ];
+
+    /// Writes the traced tiles onto the map one after the other, the way the move did.
+    fn replay(map: &str, entry: &TraceEntry) -> String {
+        let mut rows: Vec<Vec<char>> = map.lines().map(|l| l.chars().collect()).collect();
+
+        for t in &entry.tiles {
+            assert_eq!(rows[t.at.vertical][t.at.horizontal], t.from, "{:?}", t);
+            rows[t.at.vertical][t.at.horizontal] = t.to;
+        }
+
+        rows.into_iter().map(|r| r.into_iter().collect::<String>() + "\n").collect()
+    }
+
+    #[test]
+    fn test_trace_part2_follows_the_puzzle() {
+        let trace = trace_part2(TINY).unwrap();
+
+        // where the robot is after each move, and the boxes that moved, read off the drawings
+        let robots = [(9, 3), (9, 4), (9, 5), (8, 5), (7, 5), (7, 4), (7, 4), (6, 4), (5, 4), (5, 3), (5, 2)];
+        let boxes_moved: [&[(usize, usize)]; 11] = [
+            &[(6, 3), (8, 3)], &[], &[], &[], &[], &[(5, 3), (7, 3), (6, 4)], &[], &[], &[], &[], &[(5, 2)],
+        ];
+
+        assert_eq!(trace.len(), 11);
+        for (i, entry) in trace.iter().enumerate() {
+            assert_eq!(replay(TINY_STEPS[i], entry), TINY_STEPS[i + 1], "move {}", i + 1);
+            assert_eq!(entry.blocked, i == 6, "move {}", i + 1);
+            assert_eq!(entry.robot, Coordinate::new(robots[i].0, robots[i].1), "move {}", i + 1);
+            assert_eq!(
+                entry.boxes_moved,
+                boxes_moved[i].iter().map(|&(h, v)| Coordinate::new(h, v)).collect::<Vec<_>>(),
+                "move {}", i + 1
+            );
+        }
+    }
+
+    #[test]
+    fn test_trace_tile_written_twice() {
+        // the last move pushes a pyramid of boxes up, and [7,2] first gets emptied by the box that
+        // was on it and then filled by the one coming up from below
+        let input = "#######\n#@..O.#\n#..O.O#\n#.OOO.#\n#O.O.O#\n#O...O#\n#######\n\n>v<<<v^><v<vv<v>v>v<v<>v>^\n";
+        let mut d = Day15::try_from_part2(input).unwrap();
+        d.start_trace();
+
+        let mut map = format!("{}\n", d);
+        while d.step().is_some() {
+            let trace = d.trace.as_ref().unwrap();
+            map = replay(&map, &trace[trace.len() - 1]);
+            assert_eq!(map, format!("{}\n", d), "move {}", trace.len());
+        }
+
+        let last = d.take_trace().pop().unwrap();
+        let writes = last.tiles.iter()
+            .filter(|t| t.at == Coordinate::new(7, 2))
+            .map(|t| (t.from, t.to))
+            .collect::<Vec<_>>();
+        assert_eq!(writes, vec![(']', '.'), ('.', '[')]);
+    }
+
+    #[test]
+    fn test_trace_is_opt_in() {
+        let mut d = Day15::try_from(TINY).unwrap();
+        d.walk();
+        assert!(d.take_trace().is_empty());
+
+        d.start_trace();
+        d.undo();
+        d.move_robot(Direction::Up);
+        let trace = d.take_trace();
+        assert_eq!(trace.len(), 1);
+        assert_eq!(trace[0].index, None);
+    }
+
+    #[test]
+    fn test_first_difference() {
+        assert_eq!(first_difference("a\nb\n", "a\nb  \n"), None);
+        assert_eq!(
+            first_difference("a\nb\nc\n", "a\nx\nc\n"),
+            Some(Difference { line: 2, ours: Some("b".to_string()), reference: Some("x".to_string()) })
+        );
+        assert_eq!(
+            first_difference("a\n", "a\nb\n"),
+            Some(Difference { line: 2, ours: None, reference: Some("b".to_string()) })
+        );
+    }
+}
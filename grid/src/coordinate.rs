@@ -0,0 +1,53 @@
+use std::fmt::{Display, Formatter};
+use crate::Direction;
+
+/// A position on a grid. Horizontal grows to the right, vertical grows downwards, and the top left
+/// corner is (0, 0).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Coordinate {
+    pub horizontal: usize,
+    pub vertical: usize,
+}
+
+impl Coordinate {
+    pub const fn new(horizontal: usize, vertical: usize) -> Coordinate {
+        Coordinate { horizontal, vertical }
+    }
+
+    /// The coordinate the given number of steps away, unless that would go past the top or the left
+    /// edge. Whether it's past the bottom or the right edge is up to the grid, see `Grid::offset`.
+    pub fn offset(&self, horizontal: isize, vertical: isize) -> Option<Coordinate> {
+        Some(Coordinate {
+            horizontal: self.horizontal.checked_add_signed(horizontal)?,
+            vertical: self.vertical.checked_add_signed(vertical)?,
+        })
+    }
+
+    /// The coordinate next to this one in the given direction, with the same caveat as `offset`.
+    pub fn step(&self, direction: Direction) -> Option<Coordinate> {
+        let (horizontal, vertical) = direction.delta();
+
+        self.offset(horizontal, vertical)
+    }
+}
+
+impl Display for Coordinate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.horizontal, self.vertical)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step() {
+        let c = Coordinate::new(0, 3);
+
+        assert_eq!(c.step(Direction::Up), Some(Coordinate::new(0, 2)));
+        assert_eq!(c.step(Direction::Right), Some(Coordinate::new(1, 3)));
+        assert_eq!(c.step(Direction::Left), None);
+        assert_eq!(c.offset(2, -4), None);
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, IndexMut};
+use common::ParseError;
+use crate::{Coordinate, Direction};
+
+/// The four diagonal steps, clockwise starting with up and right.
+const DIAGONALS: [(isize, isize); 4] = [(1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+/// A rectangle of tiles stored row by row in a single vector. Everything that takes a coordinate
+/// checks it against the bounds, so walking off the edge gives back a `None` rather than a tile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    tiles: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid where every tile is `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid { width, height, tiles: vec![fill; width * height] }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Tiles are in reading order: the first row left to right, then the second row, and so on.
+    ///
+    /// Panics if the number of tiles is not a multiple of the width.
+    pub fn from_tiles(width: usize, tiles: Vec<T>) -> Grid<T> {
+        let height = match width {
+            0 => 0,
+            _ => tiles.len() / width,
+        };
+
+        assert_eq!(width * height, tiles.len(), "{} tiles don't make rows of {}", tiles.len(), width);
+
+        Grid { width, height, tiles }
+    }
+
+    /// Builds a grid out of lines of text, one character per tile. `tile` turns a character into a
+    /// tile, and returns `None` for characters that have no business being on the map. Empty lines
+    /// are skipped, every other line has to be as long as the first one.
+    pub fn parse<F>(input: &str, mut tile: F) -> Result<Grid<T>, ParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut width: Option<usize> = None;
+        let mut tiles = Vec::new();
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut length = 0;
+            for (column, c) in line.chars().enumerate() {
+                let t = tile(c).ok_or(ParseError::at(
+                    i + 1,
+                    column + 1,
+                    format!("unexpected character '{}' on the map", c),
+                ))?;
+
+                tiles.push(t);
+                length += 1;
+            }
+
+            match width {
+                None => width = Some(length),
+                Some(w) if w != length => {
+                    return Err(ParseError::at(
+                        i + 1,
+                        w.min(length) + 1,
+                        format!("expected {} tiles on this line, found {}", w, length),
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok(Grid::from_tiles(width.unwrap_or(0), tiles))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coordinate: Coordinate) -> bool {
+        coordinate.horizontal < self.width && coordinate.vertical < self.height
+    }
+
+    pub fn get(&self, coordinate: Coordinate) -> Option<&T> {
+        self.index_of(coordinate).map(|i| &self.tiles[i])
+    }
+
+    pub fn get_mut(&mut self, coordinate: Coordinate) -> Option<&mut T> {
+        self.index_of(coordinate).map(|i| &mut self.tiles[i])
+    }
+
+    /// Puts `value` at the coordinate and returns what was there before, or `None` if the
+    /// coordinate is not on the grid, in which case nothing changes.
+    pub fn set(&mut self, coordinate: Coordinate, value: T) -> Option<T> {
+        let i = self.index_of(coordinate)?;
+
+        Some(std::mem::replace(&mut self.tiles[i], value))
+    }
+
+    /// The coordinate the given number of steps away, if it's still on the grid.
+    pub fn offset(&self, coordinate: Coordinate, horizontal: isize, vertical: isize) -> Option<Coordinate> {
+        coordinate.offset(horizontal, vertical).filter(|c| self.contains(*c))
+    }
+
+    /// The coordinate next to this one in the given direction, if it's still on the grid.
+    pub fn step(&self, coordinate: Coordinate, direction: Direction) -> Option<Coordinate> {
+        coordinate.step(direction).filter(|c| self.contains(*c))
+    }
+
+    /// The up to four coordinates that share an edge with this one, clockwise starting with up.
+    pub fn neighbours(&self, coordinate: Coordinate) -> impl Iterator<Item = Coordinate> + '_ {
+        Direction::ALL.into_iter().filter_map(move |d| self.step(coordinate, d))
+    }
+
+    /// The up to eight coordinates that share an edge or a corner with this one.
+    pub fn neighbours8(&self, coordinate: Coordinate) -> impl Iterator<Item = Coordinate> + '_ {
+        Direction::ALL.into_iter()
+            .map(|d| d.delta())
+            .chain(DIAGONALS)
+            .filter_map(move |(h, v)| self.offset(coordinate, h, v))
+    }
+
+    /// Every coordinate on the grid in reading order.
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinate> {
+        let width = self.width;
+
+        (0..self.tiles.len()).map(move |i| Coordinate::new(i % width, i / width))
+    }
+
+    /// Every tile with its coordinate, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinate, &T)> {
+        self.coordinates().zip(self.tiles.iter())
+    }
+
+    /// The coordinate of the first tile, in reading order, that matches.
+    pub fn position<P>(&self, predicate: P) -> Option<Coordinate>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.tiles.iter().position(predicate).map(|i| Coordinate::new(i % self.width, i / self.width))
+    }
+
+    /// A grid of the same size with every tile transformed.
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid { width: self.width, height: self.height, tiles: self.tiles.iter().map(f).collect() }
+    }
+
+    fn index_of(&self, coordinate: Coordinate) -> Option<usize> {
+        match self.contains(coordinate) {
+            true => Some(coordinate.vertical * self.width + coordinate.horizontal),
+            false => None,
+        }
+    }
+}
+
+impl<T> Index<Coordinate> for Grid<T> {
+    type Output = T;
+
+    /// Panics if the coordinate is not on the grid, use `get` when that can happen.
+    fn index(&self, coordinate: Coordinate) -> &T {
+        match self.index_of(coordinate) {
+            Some(i) => &self.tiles[i],
+            None => panic!("{} is outside of the {}x{} grid", coordinate, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Coordinate> for Grid<T> {
+    fn index_mut(&mut self, coordinate: Coordinate) -> &mut T {
+        match self.index_of(coordinate) {
+            Some(i) => &mut self.tiles[i],
+            None => panic!("{} is outside of the {}x{} grid", coordinate, self.width, self.height),
+        }
+    }
+}
+
+/// Every tile is written with its own `Display`, rows are separated by newlines.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.tiles.chunks(self.width.max(1)).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            for tile in row {
+                write!(f, "{}", tile)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>, ParseError> {
+        Grid::parse(input, |c| c.to_digit(10))
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("\n012\n345\n\n").unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Coordinate::new(2, 0)), Some(&2));
+        assert_eq!(grid[Coordinate::new(0, 1)], 3);
+        assert_eq!(grid.get(Coordinate::new(3, 0)), None);
+        assert_eq!(grid.to_string(), "012\n345");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(digits("012\n3x5\n"), Err(ParseError::at(2, 2, "unexpected character 'x' on the map")));
+        assert_eq!(digits("012\n34\n"), Err(ParseError::at(2, 3, "expected 3 tiles on this line, found 2")));
+        assert_eq!(digits("012\n3456\n"), Err(ParseError::at(2, 4, "expected 3 tiles on this line, found 4")));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, '.');
+
+        assert_eq!(grid.neighbours(Coordinate::new(1, 1)).collect::<Vec<_>>(), vec![
+            Coordinate::new(1, 0),
+            Coordinate::new(2, 1),
+            Coordinate::new(1, 2),
+            Coordinate::new(0, 1),
+        ]);
+        assert_eq!(grid.neighbours(Coordinate::new(0, 0)).collect::<Vec<_>>(), vec![
+            Coordinate::new(1, 0),
+            Coordinate::new(0, 1),
+        ]);
+        assert_eq!(grid.neighbours8(Coordinate::new(1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8(Coordinate::new(2, 2)).collect::<Vec<_>>(), vec![
+            Coordinate::new(2, 1),
+            Coordinate::new(1, 2),
+            Coordinate::new(1, 1),
+        ]);
+    }
+
+    #[test]
+    fn test_set_and_find() {
+        let mut grid = Grid::new(4, 2, '.');
+
+        assert_eq!(grid.set(Coordinate::new(3, 1), '#'), Some('.'));
+        assert_eq!(grid.set(Coordinate::new(4, 1), '#'), None);
+        assert_eq!(grid.position(|&c| c == '#'), Some(Coordinate::new(3, 1)));
+        assert_eq!(grid.iter().filter(|(_, &c)| c == '.').count(), 7);
+        assert_eq!(grid.step(Coordinate::new(3, 1), Direction::Right), None);
+        assert_eq!(grid.step(Coordinate::new(3, 1), Direction::Up), Some(Coordinate::new(3, 0)));
+    }
+}
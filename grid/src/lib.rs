@@ -0,0 +1,7 @@
+mod coordinate;
+mod direction;
+mod grid;
+
+pub use coordinate::Coordinate;
+pub use direction::Direction;
+pub use grid::Grid;
@@ -0,0 +1,146 @@
+//! Checks every day against the known answers in `answers.toml`.
+
+use std::path::PathBuf;
+use common::Input;
+use toml::{Table, Value};
+use crate::days;
+
+const MANIFEST: &str = include_str!("../answers.toml");
+
+/// What happened to the entries for the input files that were checked.
+#[derive(Debug, Default)]
+struct Report {
+    checked: usize,
+    skipped: Vec<String>,
+    wrong: Vec<String>,
+}
+
+/// Runs every part in the manifest whose input file is one of `files`. Entries for inputs that are
+/// not in the inputs directory are skipped rather than failed, the real inputs are not committed
+/// everywhere.
+fn check(manifest: &Table, inputs: &Input, files: &[&str]) -> Report {
+    let mut report = Report::default();
+
+    for (day_key, day_entries) in manifest {
+        let day = match day_key.strip_prefix("day").and_then(|d| d.parse::<u8>().ok()) {
+            Some(d) => d,
+            None => {
+                report.wrong.push(format!("{}: expected a key like day07", day_key));
+                continue;
+            }
+        };
+        let Some(day_entries) = day_entries.as_table() else {
+            report.wrong.push(format!("{}: expected a table of input files", day_key));
+            continue;
+        };
+
+        for (file, parts) in day_entries {
+            if !files.contains(&file.as_str()) {
+                continue;
+            }
+
+            let Some(parts) = parts.as_table() else {
+                report.wrong.push(format!("{} {}: expected a table of parts", day_key, file));
+                continue;
+            };
+
+            let text = match inputs.read(day, file) {
+                Ok(t) => t,
+                Err(e) => {
+                    report.skipped.push(e.to_string());
+                    continue;
+                }
+            };
+
+            for (part_key, expected) in parts {
+                let label = format!("day {} {} on {}", day, part_key, file);
+                let want = match expected {
+                    Value::Integer(n) => n.to_string(),
+                    Value::String(s) => s.clone(),
+                    other => {
+                        report.wrong.push(format!("{}: expected a number or a string, found {}", label, other));
+                        continue;
+                    }
+                };
+                let part = match part_key.as_str() {
+                    "part1" => 1,
+                    "part2" => 2,
+                    _ => {
+                        report.wrong.push(format!("{}: expected part1 or part2", label));
+                        continue;
+                    }
+                };
+
+                report.checked += 1;
+                // anything that isn't the real input is one of the puzzle's examples
+                let example = file != "input.txt";
+                match days::find(day).and_then(|d| d.solve(part, &text, example)) {
+                    Ok(answer) if answer.to_string() == want => {}
+                    Ok(answer) => report.wrong.push(format!("{}: expected {}, got {}", label, want, answer)),
+                    Err(e) => report.wrong.push(format!("{}: expected {}, got an error: {}", label, want, e)),
+                }
+            }
+        }
+    }
+
+    report
+}
+
+fn check_repository(files: &[&str]) {
+    let manifest: Table = MANIFEST.parse().expect("answers.toml is not valid TOML");
+    let inputs = Input::Directory(PathBuf::from(env!("CARGO_MANIFEST_DIR")));
+
+    let report = check(&manifest, &inputs, files);
+    for s in &report.skipped {
+        println!("skipped: {}", s);
+    }
+
+    assert!(
+        report.wrong.is_empty(),
+        "{} of {} answers are wrong:\n{}",
+        report.wrong.len(),
+        report.checked,
+        report.wrong.join("\n"),
+    );
+}
+
+#[test]
+fn test_example_answers() {
+    check_repository(&["example.txt", "tinyexample.txt"]);
+}
+
+/// Some of the days take a long while without optimisations, run this one with
+/// `cargo test --release answers -- --ignored`.
+#[test]
+#[ignore]
+fn test_input_answers() {
+    check_repository(&["input.txt"]);
+}
+
+#[test]
+fn test_report() {
+    let manifest: Table = r#"
+        [day07."example.txt"]
+        part1 = 3749
+        part2 = "1"
+
+        [day07."missing.txt"]
+        part1 = 1
+
+        [day07."input.txt"]
+        part1 = 1
+
+        [dayseven."example.txt"]
+        part1 = 3749
+    "#.parse().unwrap();
+    let inputs = Input::Directory(PathBuf::from(env!("CARGO_MANIFEST_DIR")));
+
+    let report = check(&manifest, &inputs, &["example.txt", "missing.txt"]);
+
+    assert_eq!(report.checked, 2);
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.wrong, vec![
+        "day 7 part2 on example.txt: expected 1, got 11387".to_string(),
+        "dayseven: expected a key like day07".to_string(),
+    ]);
+}
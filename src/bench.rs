@@ -0,0 +1,252 @@
+use std::fmt::{Display, Formatter};
+use std::hint::black_box;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use common::Input;
+use crate::cli::{BenchOptions, CliError, Options, Selection};
+use crate::days::{self, Day, DAYS};
+
+/// What the timings get written as, picked by the extension of the output file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Format {
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub(crate) fn from_path(path: &Path) -> Option<Format> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Some(Format::Json),
+            Some("csv") => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// The part of a day being timed. Both parts parse the input on their own, so their times include
+/// what parse shows on its own.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Stage {
+    Parse,
+    Part(u8),
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Part(p) => write!(f, "part{}", p),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Timing {
+    pub(crate) day: u8,
+    pub(crate) stage: Stage,
+    pub(crate) iterations: usize,
+    pub(crate) median: Duration,
+    pub(crate) min: Duration,
+}
+
+impl Timing {
+    pub(crate) fn from_samples(day: u8, stage: Stage, mut samples: Vec<Duration>) -> Timing {
+        samples.sort();
+
+        let middle = samples.len() / 2;
+        let median = match samples.len() {
+            0 => Duration::ZERO,
+            n if n % 2 == 0 => (samples[middle - 1] + samples[middle]) / 2,
+            _ => samples[middle],
+        };
+
+        Timing {
+            day,
+            stage,
+            iterations: samples.len(),
+            median,
+            min: samples.first().copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// Calls `f` `warmup` times without looking, then `iterations` more times timing each call. The
+/// first error stops the measurement.
+fn measure<T, F>(f: F, warmup: u32, iterations: u32) -> Result<Vec<Duration>, CliError>
+where
+    F: Fn() -> Result<T, CliError>,
+{
+    for _ in 0..warmup {
+        black_box(f()?);
+    }
+
+    let mut samples = Vec::with_capacity(iterations as usize);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        black_box(f()?);
+        samples.push(start.elapsed());
+    }
+
+    Ok(samples)
+}
+
+/// Times every selected day, prints the table, and writes the file if one was asked for.
+pub(crate) fn run(options: &Options, bench: &BenchOptions, input: &Input) -> Result<(), CliError> {
+    let mut timings: Vec<Timing> = Vec::new();
+
+    match options.selection {
+        Selection::Day(d) => {
+            let result = bench_day(days::find(d)?, options, bench, input, &mut timings);
+            // whatever got measured before the error is still worth showing
+            print_results(&timings, bench)?;
+            result?;
+        }
+        Selection::All => {
+            for day in DAYS.iter() {
+                if let Err(e) = bench_day(day, options, bench, input, &mut timings) {
+                    println!("{}", e);
+                }
+            }
+
+            print_results(&timings, bench)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn bench_day(day: &Day, options: &Options, bench: &BenchOptions, input: &Input, timings: &mut Vec<Timing>) -> Result<(), CliError> {
+    let parts = match options.part {
+        Some(p) => vec![p],
+        None => vec![1, 2],
+    };
+    let file_name = match options.example {
+        true => "example.txt",
+        false => "input.txt",
+    };
+
+    // no point reading an example that none of the parts can use
+    if options.example {
+        day.check_example(parts[0])?;
+    }
+
+    let text = input.read(day.number, file_name)?;
+
+    let samples = measure(|| day.parse(&text), bench.warmup, bench.iterations)?;
+    timings.push(Timing::from_samples(day.number, Stage::Parse, samples));
+
+    for part in parts {
+        if options.example {
+            day.check_example(part)?;
+        }
+
+        let samples = measure(|| day.solve(part, &text, options.example), bench.warmup, bench.iterations)?;
+        timings.push(Timing::from_samples(day.number, Stage::Part(part), samples));
+    }
+
+    Ok(())
+}
+
+fn print_results(timings: &[Timing], bench: &BenchOptions) -> Result<(), CliError> {
+    if timings.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    print!("{}", table(timings));
+
+    if let Some((path, format)) = &bench.output {
+        let contents = match format {
+            Format::Json => to_json(timings),
+            Format::Csv => to_csv(timings),
+        };
+
+        std::fs::write(path, contents).map_err(|e| CliError::Output(path.clone(), e.to_string()))?;
+        println!("\nTimings written to {}", path.display());
+    }
+
+    Ok(())
+}
+
+pub(crate) fn table(timings: &[Timing]) -> String {
+    let mut s = format!("{:>3}  {:<6} {:>12} {:>12}\n", "Day", "Stage", "Median", "Min");
+
+    for t in timings {
+        s += &format!(
+            "{:>3}  {:<6} {:>12} {:>12}\n",
+            t.day,
+            t.stage.to_string(),
+            format!("{:.2?}", t.median),
+            format!("{:.2?}", t.min),
+        );
+    }
+
+    s
+}
+
+/// One object per day and stage, with the times in nanoseconds.
+pub(crate) fn to_json(timings: &[Timing]) -> String {
+    let rows = timings.iter()
+        .map(|t| format!(
+            "  {{\"day\": {}, \"stage\": \"{}\", \"iterations\": {}, \"median_ns\": {}, \"min_ns\": {}}}",
+            t.day, t.stage, t.iterations, t.median.as_nanos(), t.min.as_nanos(),
+        ))
+        .collect::<Vec<String>>();
+
+    format!("[\n{}\n]\n", rows.join(",\n"))
+}
+
+/// A header line, then one line per day and stage, with the times in nanoseconds.
+pub(crate) fn to_csv(timings: &[Timing]) -> String {
+    let mut s = "day,stage,iterations,median_ns,min_ns\n".to_string();
+
+    for t in timings {
+        s += &format!("{},{},{},{},{}\n", t.day, t.stage, t.iterations, t.median.as_nanos(), t.min.as_nanos());
+    }
+
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&v| Duration::from_micros(v)).collect()
+    }
+
+    #[test]
+    fn test_from_samples() {
+        let odd = Timing::from_samples(3, Stage::Parse, micros(&[9, 2, 5]));
+        assert_eq!((odd.median, odd.min, odd.iterations), (Duration::from_micros(5), Duration::from_micros(2), 3));
+
+        let even = Timing::from_samples(3, Stage::Part(1), micros(&[8, 2, 4, 10]));
+        assert_eq!((even.median, even.min), (Duration::from_micros(6), Duration::from_micros(2)));
+    }
+
+    #[test]
+    fn test_files() {
+        let timings = vec![
+            Timing::from_samples(1, Stage::Parse, micros(&[3])),
+            Timing::from_samples(1, Stage::Part(2), micros(&[12, 10])),
+        ];
+
+        assert_eq!(to_csv(&timings), "\
+day,stage,iterations,median_ns,min_ns
+1,parse,1,3000,3000
+1,part2,2,11000,10000
+");
+        assert_eq!(to_json(&timings), r#"[
+  {"day": 1, "stage": "parse", "iterations": 1, "median_ns": 3000, "min_ns": 3000},
+  {"day": 1, "stage": "part2", "iterations": 2, "median_ns": 11000, "min_ns": 10000}
+]
+"#);
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(Format::from_path(Path::new("out/bench.json")), Some(Format::Json));
+        assert_eq!(Format::from_path(Path::new("bench.csv")), Some(Format::Csv));
+        assert_eq!(Format::from_path(Path::new("bench")), None);
+    }
+}
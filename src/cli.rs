@@ -0,0 +1,357 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use common::{Input, InputError};
+use crate::bench::Format;
+
+pub(crate) const USAGE: &str = "\
+Usage: AdventOfCode2024 [--day <N> | --all [--jobs <N>]] [--part <1|2>] [--example] [--input <FILE> | --inputs <DIR>]
+                        [--bench [--warmup <N>] [--iterations <N>] [--output <FILE>]] [--play]
+
+Options:
+    -d, --day <N>         Run a single day, 1 to 25.
+    -a, --all             Run every day that has a solution.
+    -j, --jobs <N>        With --all, solve on N threads and print a summary once everything is done.
+    -p, --part <1|2>      Only run the given part. Both parts run when omitted.
+    -e, --example         Use example.txt instead of input.txt.
+    -i, --input <FILE>    Read the input for --day from this file, or from stdin if it's -.
+        --inputs <DIR>    Read dayNN/input.txt and dayNN/example.txt from this directory.
+                          Defaults to $AOC_INPUTS, or the current directory.
+    -b, --bench           Time parsing and each part instead of printing the answers.
+        --warmup <N>      Untimed runs before measuring. Defaults to 3.
+        --iterations <N>  Timed runs, the table shows their median and minimum. Defaults to 10.
+        --output <FILE>   Also write the timings to FILE, as JSON or CSV going by its extension.
+        --play            With --day 15, move the robot around the warehouse with the arrow keys.
+                          --part 2 plays on the widened map.
+    -h, --help            Print this message.";
+
+/// Which days the runner was asked to solve.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Selection {
+    Day(u8),
+    All,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Options {
+    pub(crate) selection: Selection,
+    pub(crate) part: Option<u8>,
+    pub(crate) example: bool,
+    /// Only set if --input or --inputs was passed.
+    pub(crate) input: Option<Input>,
+    /// Only set if --jobs was passed.
+    pub(crate) jobs: Option<usize>,
+    /// Only set if --bench was passed.
+    pub(crate) bench: Option<BenchOptions>,
+    pub(crate) play: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct BenchOptions {
+    pub(crate) warmup: u32,
+    pub(crate) iterations: u32,
+    pub(crate) output: Option<(PathBuf, Format)>,
+}
+
+const DEFAULT_WARMUP: u32 = 3;
+const DEFAULT_ITERATIONS: u32 = 10;
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum CliError {
+    Help,
+    NoSelection,
+    ConflictingSelection,
+    MissingValue(String),
+    UnknownArgument(String),
+    BadDay(String),
+    BadPart(String),
+    InputNeedsDay,
+    ConflictingInput,
+    BadCount(String, String),
+    BenchOnly(String),
+    JobsNeedAll,
+    JobsWithBench,
+    NotPlayable,
+    PlayWithBench,
+    UnknownFormat(PathBuf),
+    UnknownDay(u8),
+    NoExample(u8, u8),
+    Input(InputError),
+    /// The day's input could not be parsed. Holds the rendered diagnostic.
+    BadInput(u8, String),
+    /// The input was fine, but the part couldn't be solved, like when a number gets too big.
+    Unsolved(u8, u8, String),
+    /// The benchmark results could not be written to the file.
+    Output(PathBuf, String),
+    Terminal(String),
+}
+
+impl From<InputError> for CliError {
+    fn from(e: InputError) -> Self {
+        CliError::Input(e)
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Help => write!(f, "{}", USAGE),
+            CliError::NoSelection => write!(f, "Pick a day with --day <N>, or run everything with --all."),
+            CliError::ConflictingSelection => write!(f, "--day and --all can not be used together."),
+            CliError::MissingValue(flag) => write!(f, "{} needs a value.", flag),
+            CliError::UnknownArgument(arg) => write!(f, "Unknown argument '{}'.", arg),
+            CliError::BadDay(value) => write!(f, "'{}' is not a day, expected a number between 1 and 25.", value),
+            CliError::BadPart(value) => write!(f, "'{}' is not a part, expected 1 or 2.", value),
+            CliError::InputNeedsDay => write!(f, "--input reads a single day's input, it needs --day."),
+            CliError::ConflictingInput => write!(f, "--input can not be used together with --inputs or --example."),
+            CliError::BadCount(flag, value) => write!(f, "'{}' is not a valid value for {}, expected a number.", value, flag),
+            CliError::BenchOnly(flag) => write!(f, "{} only works together with --bench.", flag),
+            CliError::JobsNeedAll => write!(f, "--jobs runs every day at once, it needs --all."),
+            CliError::JobsWithBench => write!(f, "--jobs can not be used together with --bench, the timings would get in each other's way."),
+            CliError::NotPlayable => write!(f, "--play only works with --day 15."),
+            CliError::PlayWithBench => write!(f, "--play can not be used together with --bench."),
+            CliError::UnknownFormat(path) => write!(f, "Can't tell which format to write '{}' in, use a .json or .csv file.", path.display()),
+            CliError::UnknownDay(day) => write!(f, "There is no solution for day {} yet.", day),
+            CliError::NoExample(day, part) => write!(f, "Day {} part {} does not have an example input.", day, part),
+            CliError::Input(e) => write!(f, "{}", e),
+            CliError::BadInput(day, diagnostic) => write!(f, "Could not parse the input for day {}: {}", day, diagnostic),
+            CliError::Unsolved(day, part, message) => write!(f, "Could not solve day {} part {}: {}", day, part, message),
+            CliError::Output(path, e) => write!(f, "Could not write the timings to '{}': {}", path.display(), e),
+            CliError::Terminal(e) => write!(f, "Something went wrong with the terminal: {}", e),
+        }
+    }
+}
+
+/// Parses the arguments, without the program name, into options for the runner.
+pub(crate) fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, CliError> {
+    let mut day: Option<u8> = None;
+    let mut all = false;
+    let mut part: Option<u8> = None;
+    let mut example = false;
+    let mut file: Option<Input> = None;
+    let mut dir: Option<Input> = None;
+    let mut jobs: Option<usize> = None;
+    let mut bench = false;
+    let mut warmup: Option<u32> = None;
+    let mut iterations: Option<u32> = None;
+    let mut output: Option<PathBuf> = None;
+    let mut play = false;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(CliError::Help),
+            "-a" | "--all" => all = true,
+            "-e" | "--example" => example = true,
+            "-d" | "--day" => {
+                let value = args.next().ok_or(CliError::MissingValue(arg.clone()))?;
+                day = Some(parse_day(&value)?);
+            }
+            "-p" | "--part" => {
+                let value = args.next().ok_or(CliError::MissingValue(arg.clone()))?;
+                part = Some(parse_part(&value)?);
+            }
+            "-i" | "--input" => {
+                let value = args.next().ok_or(CliError::MissingValue(arg.clone()))?;
+                file = Some(Input::from_arg(&value));
+            }
+            "--inputs" => {
+                let value = args.next().ok_or(CliError::MissingValue(arg.clone()))?;
+                dir = Some(Input::Directory(PathBuf::from(value)));
+            }
+            "-j" | "--jobs" => {
+                let value = args.next().ok_or(CliError::MissingValue(arg.clone()))?;
+                jobs = Some(parse_count(&arg, &value, 1)? as usize);
+            }
+            "-b" | "--bench" => bench = true,
+            "--play" => play = true,
+            "--warmup" => {
+                let value = args.next().ok_or(CliError::MissingValue(arg.clone()))?;
+                warmup = Some(parse_count(&arg, &value, 0)?);
+            }
+            "--iterations" => {
+                let value = args.next().ok_or(CliError::MissingValue(arg.clone()))?;
+                iterations = Some(parse_count(&arg, &value, 1)?);
+            }
+            "--output" => {
+                let value = args.next().ok_or(CliError::MissingValue(arg.clone()))?;
+                output = Some(PathBuf::from(value));
+            }
+            _ => return Err(CliError::UnknownArgument(arg)),
+        }
+    }
+
+    let selection = match (day, all) {
+        (Some(_), true) => return Err(CliError::ConflictingSelection),
+        (Some(d), false) => Selection::Day(d),
+        (None, true) => Selection::All,
+        (None, false) => return Err(CliError::NoSelection),
+    };
+
+    let input = match (file, dir) {
+        (Some(_), _) if selection == Selection::All => return Err(CliError::InputNeedsDay),
+        (Some(_), Some(_)) => return Err(CliError::ConflictingInput),
+        (Some(_), None) if example => return Err(CliError::ConflictingInput),
+        (f, d) => f.or(d),
+    };
+
+    match jobs {
+        Some(_) if selection != Selection::All => return Err(CliError::JobsNeedAll),
+        Some(_) if bench => return Err(CliError::JobsWithBench),
+        _ => {}
+    }
+
+    match play {
+        true if selection != Selection::Day(15) => return Err(CliError::NotPlayable),
+        true if bench => return Err(CliError::PlayWithBench),
+        _ => {}
+    }
+
+    let bench = match bench {
+        true => {
+            let output = match output {
+                Some(path) => {
+                    let format = Format::from_path(&path).ok_or(CliError::UnknownFormat(path.clone()))?;
+                    Some((path, format))
+                }
+                None => None,
+            };
+
+            Some(BenchOptions {
+                warmup: warmup.unwrap_or(DEFAULT_WARMUP),
+                iterations: iterations.unwrap_or(DEFAULT_ITERATIONS),
+                output,
+            })
+        }
+        false => {
+            let flag = [
+                (warmup.is_some(), "--warmup"),
+                (iterations.is_some(), "--iterations"),
+                (output.is_some(), "--output"),
+            ].into_iter().find_map(|(set, flag)| set.then_some(flag));
+
+            if let Some(flag) = flag {
+                return Err(CliError::BenchOnly(flag.to_string()));
+            }
+
+            None
+        }
+    };
+
+    Ok(Options { selection, part, example, input, jobs, bench, play })
+}
+
+fn parse_day(value: &str) -> Result<u8, CliError> {
+    match value.parse::<u8>() {
+        Ok(d) if (1..=25).contains(&d) => Ok(d),
+        _ => Err(CliError::BadDay(value.to_string())),
+    }
+}
+
+/// A number of runs, which has to be at least `min`.
+fn parse_count(flag: &str, value: &str, min: u32) -> Result<u32, CliError> {
+    match value.parse::<u32>() {
+        Ok(n) if n >= min => Ok(n),
+        _ => Err(CliError::BadCount(flag.to_string(), value.to_string())),
+    }
+}
+
+fn parse_part(value: &str) -> Result<u8, CliError> {
+    match value {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(CliError::BadPart(value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(input: &str) -> Vec<String> {
+        input.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_day_and_part() {
+        assert_eq!(parse_args(args("--day 7 --part 2")), Ok(Options {
+            selection: Selection::Day(7),
+            part: Some(2),
+            example: false,
+            input: None,
+            jobs: None,
+            bench: None,
+            play: false,
+        }));
+    }
+
+    #[test]
+    fn test_parse_all_example() {
+        assert_eq!(parse_args(args("-a -e")), Ok(Options {
+            selection: Selection::All,
+            part: None,
+            example: true,
+            input: None,
+            jobs: None,
+            bench: None,
+            play: false,
+        }));
+    }
+
+    #[test]
+    fn test_parse_inputs() {
+        assert_eq!(parse_args(args("--day 15 --input day15/tinyexample.txt")).map(|o| o.input), Ok(Some(
+            Input::File(PathBuf::from("day15/tinyexample.txt"))
+        )));
+        assert_eq!(parse_args(args("--day 1 -i -")).map(|o| o.input), Ok(Some(Input::Stdin)));
+        assert_eq!(parse_args(args("--all --inputs /tmp/aoc")).map(|o| o.input), Ok(Some(
+            Input::Directory(PathBuf::from("/tmp/aoc"))
+        )));
+        assert_eq!(parse_args(args("--all --input -")), Err(CliError::InputNeedsDay));
+        assert_eq!(parse_args(args("--day 2 --input a.txt --inputs b")), Err(CliError::ConflictingInput));
+        assert_eq!(parse_args(args("--day 2 --input a.txt --example")), Err(CliError::ConflictingInput));
+    }
+
+    #[test]
+    fn test_parse_jobs() {
+        assert_eq!(parse_args(args("--all --jobs 4")).map(|o| o.jobs), Ok(Some(4)));
+        assert_eq!(parse_args(args("--all -j 0")), Err(CliError::BadCount("-j".to_string(), "0".to_string())));
+        assert_eq!(parse_args(args("--day 3 --jobs 4")), Err(CliError::JobsNeedAll));
+        assert_eq!(parse_args(args("--all --jobs 4 --bench")), Err(CliError::JobsWithBench));
+    }
+
+    #[test]
+    fn test_parse_play() {
+        assert_eq!(parse_args(args("--day 15 --part 2 --play")).map(|o| o.play), Ok(true));
+        assert_eq!(parse_args(args("--day 14 --play")), Err(CliError::NotPlayable));
+        assert_eq!(parse_args(args("--all --play")), Err(CliError::NotPlayable));
+        assert_eq!(parse_args(args("--day 15 --play --bench")), Err(CliError::PlayWithBench));
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        assert_eq!(parse_args(args("--all --bench")).map(|o| o.bench), Ok(Some(BenchOptions {
+            warmup: 3,
+            iterations: 10,
+            output: None,
+        })));
+        assert_eq!(parse_args(args("-d 5 -b --warmup 0 --iterations 50 --output bench.csv")).map(|o| o.bench), Ok(Some(BenchOptions {
+            warmup: 0,
+            iterations: 50,
+            output: Some((PathBuf::from("bench.csv"), Format::Csv)),
+        })));
+        assert_eq!(parse_args(args("-d 5 -b --iterations 0")), Err(CliError::BadCount("--iterations".to_string(), "0".to_string())));
+        assert_eq!(parse_args(args("-d 5 -b --output bench.txt")), Err(CliError::UnknownFormat(PathBuf::from("bench.txt"))));
+        assert_eq!(parse_args(args("-d 5 --warmup 2")), Err(CliError::BenchOnly("--warmup".to_string())));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_args(args("")), Err(CliError::NoSelection));
+        assert_eq!(parse_args(args("--day 3 --all")), Err(CliError::ConflictingSelection));
+        assert_eq!(parse_args(args("--day")), Err(CliError::MissingValue("--day".to_string())));
+        assert_eq!(parse_args(args("--day 26")), Err(CliError::BadDay("26".to_string())));
+        assert_eq!(parse_args(args("--day seven")), Err(CliError::BadDay("seven".to_string())));
+        assert_eq!(parse_args(args("--day 1 --part 3")), Err(CliError::BadPart("3".to_string())));
+        assert_eq!(parse_args(args("--fast")), Err(CliError::UnknownArgument("--fast".to_string())));
+    }
+}
@@ -0,0 +1,135 @@
+use common::{Answer, ParseError, Solution, SolutionError};
+use crate::cli::CliError;
+
+type Parse = fn(&str) -> Result<(), ParseError>;
+type Part = fn(&str) -> Result<Answer, SolutionError>;
+
+/// Entry points for a single day. Not every day's example input works for both parts.
+pub(crate) struct Day {
+    pub(crate) number: u8,
+    parse: Parse,
+    part1: Part,
+    part2: Part,
+    example_part1: Part,
+    example_part2: Part,
+    part1_example: bool,
+    part2_example: bool,
+}
+
+impl Day {
+    /// Only parses the input, the parts do this again on their own.
+    pub(crate) fn parse(&self, input: &str) -> Result<(), CliError> {
+        (self.parse)(input).map_err(|e| CliError::BadInput(self.number, e.diagnostic(input)))
+    }
+
+    /// Solves one part, of the example if `example` is set. If the input can't be parsed, the error
+    /// carries the diagnostic pointing at the offending line.
+    pub(crate) fn solve(&self, part: u8, input: &str, example: bool) -> Result<Answer, CliError> {
+        let result = match (part, example) {
+            (1, false) => (self.part1)(input),
+            (2, false) => (self.part2)(input),
+            (1, true) => (self.example_part1)(input),
+            (2, true) => (self.example_part2)(input),
+            _ => return Err(CliError::BadPart(part.to_string())),
+        };
+
+        result.map_err(|e| match e {
+            SolutionError::Parse(e) => CliError::BadInput(self.number, e.diagnostic(input)),
+            SolutionError::Solve(message) => CliError::Unsolved(self.number, part, message),
+        })
+    }
+
+    pub(crate) fn check_example(&self, part: u8) -> Result<(), CliError> {
+        match (part, self.part1_example, self.part2_example) {
+            (1, true, _) | (2, _, true) => Ok(()),
+            _ => Err(CliError::NoExample(self.number, part)),
+        }
+    }
+}
+
+const fn day<S: Solution>(number: u8, part1_example: bool, part2_example: bool) -> Day {
+    Day {
+        number,
+        parse: S::parse,
+        part1: S::part1,
+        part2: S::part2,
+        example_part1: S::example_part1,
+        example_part2: S::example_part2,
+        part1_example,
+        part2_example,
+    }
+}
+
+pub(crate) static DAYS: [Day; 15] = [
+    day::<day01::Day01>(1, false, false),
+    day::<day02::Day02>(2, false, false),
+    day::<day03::Day03<'static>>(3, false, false),
+    day::<day04::Day04>(4, false, false),
+    day::<day05::Day05>(5, false, false),
+    day::<day06::Day06>(6, true, true),
+    day::<day07::Day07>(7, true, true),
+    day::<day08::Day08>(8, true, true),
+    day::<day09::Day09>(9, true, true),
+    day::<day10::Day10>(10, true, true),
+    day::<day11::Day11>(11, true, false),
+    day::<day12::Day12>(12, true, true),
+    day::<day13::Day13>(13, true, true),
+    day::<day14::Day14>(14, true, false),
+    day::<day15::Day15>(15, true, true),
+];
+
+/// Looks up the entry points for the given day.
+pub(crate) fn find(day: u8) -> Result<&'static Day, CliError> {
+    DAYS.iter()
+        .find(|d| d.number == day)
+        .ok_or(CliError::UnknownDay(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use common::Input;
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(7).map(|d| d.number).ok(), Some(7));
+        assert!(matches!(find(16), Err(CliError::UnknownDay(16))));
+    }
+
+    #[test]
+    fn test_solve_example() {
+        let day = find(7).unwrap();
+        let input = Input::Directory(PathBuf::from(env!("CARGO_MANIFEST_DIR")))
+            .read(7, "example.txt")
+            .unwrap();
+
+        assert_eq!(day.solve(1, &input, true), Ok(Answer::Unsigned(3749)));
+        assert_eq!(day.solve(2, &input, true), Ok(Answer::Unsigned(11387)));
+    }
+
+    #[test]
+    fn test_solve_bad_input() {
+        let day = find(7).unwrap();
+
+        assert_eq!(day.solve(1, "190: 10 19\n83: 17 x5\n", false), Err(CliError::BadInput(7, "\
+line 2, column 8: expected a number, found 'x5'
+  |
+2 | 83: 17 x5
+  |        ^".to_string())));
+    }
+
+    #[test]
+    fn test_solve_overflow() {
+        // the input is fine, the stone just gets too big
+        assert_eq!(find(11).unwrap().solve(1, "9999999999999999999\n", false), Err(CliError::Unsolved(11, 1, "\
+the stone with 9999999999999999999 on it turns into one with too big a number to fit in 64 bits".to_string())));
+    }
+
+    #[test]
+    fn test_missing_example() {
+        assert_eq!(find(1).unwrap().check_example(1), Err(CliError::NoExample(1, 1)));
+        assert_eq!(find(11).unwrap().check_example(2), Err(CliError::NoExample(11, 2)));
+        assert_eq!(find(11).unwrap().check_example(1), Ok(()));
+    }
+}
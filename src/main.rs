@@ -1,95 +1,98 @@
-fn main() {
+#[cfg(test)]
+mod answers;
+mod bench;
+mod cli;
+mod days;
+mod parallel;
+mod play;
+
+use std::process::ExitCode;
+use common::Input;
+use crate::cli::{CliError, Options, Selection};
+use crate::days::{Day, DAYS};
+
+fn main() -> ExitCode {
+    let options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(o) => o,
+        Err(CliError::Help) => {
+            println!("{}", cli::USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
 
     println!("You're reading the Advent of Code 2024 solutions by Gabor Javorszky.");
     println!("Find the source code at https://github.com/javorszky/adventofcode2024.");
 
-    // Day 01
-    // let d1 = day01::Day01::new();
-    // println!("\nDay 1 part 1: {}.",  d1.solve());
-    // println!("Day 1 part 2: {}.",  d1.solve_two());
-
-    // Day 02
-    // let d2 = day02::Day02::new();
-    // println!("\nDay 2 part 1: {}.", d2.part1());
-    // let (_, result) = d2.part2();
-    // println!("Day 2 part 2: {}.", result);
-    //
-    // // Day 03
-    // let d3 = day03::Day03::new();
-    // println!("\nDay 3 part 1: sum of valid mul products is {}.", d3.part1());
-    //
-    // println!("Day 3 part 2: sum of valid and enabled mul products is {}.", d3.part2());
-    //
-    // // Day 4
-    // println!("\nDay 4 part 1: number of XMAS words: {}.", day04::part1());
-    // println!("Day 4 part 2: number of pair of MAS words in diagonal X shape: {}.", day04::part2());
-    //
-    // Day 5
-    // println!("\nDay 5 part 1: sum of middle numbers of valid lines is {}.", day05::part1());
-    // println!("Day 5 part 2: after fixing the invalid lines, their middles is {}.", day05::part2());
-
-    // Day 6
-    // println!("\nDay 6 part 1: Guard visited {} places", day06::solve_part1());
-    // println!("Day 6 part 2 x: We can place {} obstacles to create a loop", day06::solve_part2());
-
-    // Day 7
-    // println!("\nDay 7 part 1 example: Sum of calibration results: {}", day07::solve_part1_example());
-    // println!("\nDay 7 part 1: Sum of calibration results: {}", day07::solve_part1());
-    //
-    // println!("Day 7 part 2 example: Sum of calibration results with concat: {}", day07::solve_part2_example());
-    // println!("Day 7 part 2: Sum of calibration results with concat: {}", day07::solve_part2());
-
-    // Day 8
-    // println!("\nDay 8 part 1 example: {}", day08::solve_part1_example());
-    // println!("Day 8 part 1: There are {} unique antinodes on the map.", day08::solve_part1());
-    //
-    // println!("Day 8 part 2 example: {}", day08::solve_part2_example());
-    // println!("Day 8 part 2: There are {} unique infinitely resonant antinodes on the map.", day08::solve_part2());
-
-    // Day 9
-    // println!("\nDay 9 part 1 example: {}", day09::solve_part1_example());
-    // println!("\nDay 9 part 1: The sum of index * IDs after compacting is {}", day09::solve_part1());
-    // println!("Day 9 part 2 example: {}", day09::solve_part2_example());
-    // println!("Day 9 part 2: Checksum after moving entire files: {}", day09::solve_part2());
-
-    // Day 10
-    // println!("\nDay 10 part 1 example: {}", day10::solve_part1_example());
-    // println!("Day 10 part 1: Sum of trail scores is {}", day10::solve_part1());
-    //
-    // println!("Day 10 part 2 example: {}", day10::solve_part2_example());
-    // println!("Day 10 part 2: The number of distinct paths is {}", day10::solve_part2());
-
-    // Day 11
-    // println!("\nDay 11 part 1 example: {}", day11::solve_part1_example());
-    // println!("Day 11 part 1: There are {} stones after 25 blinks.", day11::solve_part1());
-    //
-    // println!("Day 11 part 2: There are {} stones after 75 blinks.", day11::solve_part2());
-
-    // Day 12
-    // println!("\nDay 12 part 1 example: {}", day12::solve_part1_example());
-    // println!("Day 12 part 1: Total price for perimeter * area for plots is {}", day12::solve_part1());
-    //
-    // println!("Day 12 part 2 example: {}", day12::solve_part2_example());
-    // println!("Day 12 part 2: The new price using bulk pricing is {}", day12::solve_part2());
-
-    // Day 13
-    // println!("\nDay 13 part 1 example: {}", day13::solve_part1_example());
-    // println!("\nDay 13 part 1: Fewest tokens to win all prizes is {}", day13::solve_part1());
-
-    // println!("Day 13 part 2 example: {}", day13::solve_part2_example());
-    // println!("Day 13 part 2: Once properly parsed, the fewest tokens is {}", day13::solve_part2());
-
-    // Day 14
-    // println!("\nDay 14 part 1 example: {}", day14::solve_part1_example());
-    // println!("\nDay 14 part 1: Safety score will be {} after 100 seconds on a 101 wide and 103 tall room", day14::solve_part1());
-    //
-    // println!("Day 14 part 2: {}", day14::solve_part2());
-
-    // Day 15
-    // println!("\nDay 15 part 1 example: {}", day15::solve_part1_example());
-    println!("\nDay 15 part 1: The total sum of GPS coordinates of boxes is {}", day15::solve_part1());
-
-    // println!("\nDay 15 part 2 example: {}", day15::solve_part2_example());
-    // println!("\nDay 15 part 2 tiny example: {}", day15::solve_part2_tinyexample());
-    println!("Day 15 part 2: Total sum of GPS coordinates for W I D E  B O I S is {}", day15::solve_part2());
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(options: &Options) -> Result<(), CliError> {
+    let input = options.input.clone().unwrap_or_else(Input::from_env);
+
+    if let Some(bench) = &options.bench {
+        return bench::run(options, bench, &input);
+    }
+
+    if options.play {
+        return play::run(options, &input);
+    }
+
+    if let Some(jobs) = options.jobs {
+        return parallel::run(options, &input, jobs);
+    }
+
+    match options.selection {
+        Selection::Day(d) => run_day(days::find(d)?, options, &input),
+        Selection::All => {
+            for day in DAYS.iter() {
+                // a missing example or input for one day should not stop the rest from running
+                if let Err(e) = run_day(day, options, &input) {
+                    println!("{}", e);
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn run_day(day: &Day, options: &Options, input: &Input) -> Result<(), CliError> {
+    let parts = match options.part {
+        Some(p) => vec![p],
+        None => vec![1, 2],
+    };
+    let (file_name, label) = match options.example {
+        true => ("example.txt", " example"),
+        false => ("input.txt", ""),
+    };
+
+    // stdin can only be read once, so both parts share the same text
+    let mut text: Option<String> = None;
+
+    println!();
+    for part in parts {
+        if options.example {
+            day.check_example(part)?;
+        }
+
+        if text.is_none() {
+            text = Some(input.read(day.number, file_name)?);
+        }
+
+        let answer = day.solve(part, text.as_deref().unwrap_or_default(), options.example)?;
+
+        println!("Day {} part {}{}: {}", day.number, part, label, answer);
+    }
+
+    Ok(())
 }
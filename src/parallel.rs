@@ -0,0 +1,203 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use common::{Answer, Input};
+use crate::cli::{CliError, Options};
+use crate::days::{Day, DAYS};
+
+/// A part of a day to solve.
+type Task = (&'static Day, u8);
+
+/// How a single part went.
+#[derive(Debug)]
+pub(crate) enum Outcome {
+    Solved(Answer),
+    Failed(CliError),
+    Panicked(String),
+}
+
+#[derive(Debug)]
+pub(crate) struct PartResult {
+    pub(crate) day: u8,
+    pub(crate) part: u8,
+    pub(crate) outcome: Outcome,
+    pub(crate) elapsed: Duration,
+}
+
+/// Runs every part of every day on `jobs` threads, then prints what came out in day order. A part
+/// that fails or panics is reported with the rest, it doesn't stop the others.
+pub(crate) fn run(options: &Options, input: &Input, jobs: usize) -> Result<(), CliError> {
+    let parts = match options.part {
+        Some(p) => vec![p],
+        None => vec![1, 2],
+    };
+    let (tasks, skipped) = tasks(&parts, options.example);
+
+    let start = Instant::now();
+    let results = run_tasks(&tasks, jobs, |day, part| solve(day, part, options, input));
+
+    println!();
+    print!("{}", summary(&results));
+    println!("\nRan {} parts on {} threads in {:.2?}.", results.len(), jobs, start.elapsed());
+    if skipped > 0 {
+        println!("Skipped {} parts that don't have an example input.", skipped);
+    }
+
+    Ok(())
+}
+
+/// Every part of every day to run, and how many were left out because there's no example input
+/// for them to run on.
+fn tasks(parts: &[u8], example: bool) -> (Vec<Task>, usize) {
+    let (tasks, skipped): (Vec<Task>, Vec<Task>) = DAYS.iter()
+        .flat_map(|day| parts.iter().map(move |&part| (day, part)))
+        .partition(|(day, part)| !example || day.check_example(*part).is_ok());
+
+    (tasks, skipped.len())
+}
+
+fn solve(day: &Day, part: u8, options: &Options, input: &Input) -> Result<Answer, CliError> {
+    let file_name = match options.example {
+        true => "example.txt",
+        false => "input.txt",
+    };
+
+    let text = input.read(day.number, file_name)?;
+
+    day.solve(part, &text, options.example)
+}
+
+/// Hands the tasks out to `jobs` threads as they free up. The results come back in the same order
+/// as the tasks.
+fn run_tasks<F>(tasks: &[(&Day, u8)], jobs: usize, solve: F) -> Vec<PartResult>
+where
+    F: Fn(&Day, u8) -> Result<Answer, CliError> + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<PartResult>>> = Mutex::new((0..tasks.len()).map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(&(day, part)) = tasks.get(i) else {
+                    break;
+                };
+
+                let start = Instant::now();
+                let outcome = match panic::catch_unwind(AssertUnwindSafe(|| solve(day, part))) {
+                    Ok(Ok(answer)) => Outcome::Solved(answer),
+                    Ok(Err(e)) => Outcome::Failed(e),
+                    Err(payload) => Outcome::Panicked(panic_message(payload)),
+                };
+                let result = PartResult { day: day.number, part, outcome, elapsed: start.elapsed() };
+
+                results.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(result);
+            });
+        }
+    });
+
+    results.into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn first_line(s: &str) -> &str {
+    s.lines().next().unwrap_or_default()
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(s) => *s,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(s) => s.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
+/// One line per part, then how many of them did not produce an answer. Errors that take more than
+/// one line, like a parse error with the line of input it's on, only get their first line.
+pub(crate) fn summary(results: &[PartResult]) -> String {
+    let mut s = format!("{:>3}  {:>4}  {:>10}  {}\n", "Day", "Part", "Time", "Answer");
+
+    for r in results {
+        let answer = match &r.outcome {
+            Outcome::Solved(a) => a.to_string(),
+            Outcome::Failed(e) => format!("failed: {}", first_line(&e.to_string())),
+            Outcome::Panicked(m) => format!("panicked: {}", first_line(m)),
+        };
+
+        s += &format!("{:>3}  {:>4}  {:>10}  {}\n", r.day, r.part, format!("{:.2?}", r.elapsed), answer);
+    }
+
+    let failed = results.iter().filter(|r| !matches!(r.outcome, Outcome::Solved(_))).count();
+    if failed > 0 {
+        s += &format!("\n{} of {} parts did not produce an answer.\n", failed, results.len());
+    }
+
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::days::find;
+    use super::*;
+
+    #[test]
+    fn test_run_tasks_keeps_order() {
+        let tasks = vec![(find(3).unwrap(), 1), (find(1).unwrap(), 2), (find(2).unwrap(), 1), (find(1).unwrap(), 1)];
+
+        let results = run_tasks(&tasks, 3, |day, part| match (day.number, part) {
+            (1, 2) => panic!("boom"),
+            (2, _) => Err(CliError::UnknownDay(2)),
+            (d, _) => Ok(Answer::Unsigned(d as u64)),
+        });
+
+        let order = results.iter().map(|r| (r.day, r.part)).collect::<Vec<(u8, u8)>>();
+        assert_eq!(order, vec![(3, 1), (1, 2), (2, 1), (1, 1)]);
+        assert!(matches!(results[0].outcome, Outcome::Solved(Answer::Unsigned(3))));
+        assert!(matches!(&results[1].outcome, Outcome::Panicked(m) if m == "boom"));
+        assert!(matches!(results[2].outcome, Outcome::Failed(CliError::UnknownDay(2))));
+    }
+
+    #[test]
+    fn test_summary() {
+        let results = vec![
+            PartResult { day: 7, part: 1, outcome: Outcome::Solved(Answer::Unsigned(3749)), elapsed: Duration::from_millis(2) },
+            PartResult { day: 7, part: 2, outcome: Outcome::Panicked("boom".to_string()), elapsed: Duration::from_millis(1) },
+            PartResult {
+                day: 9,
+                part: 1,
+                outcome: Outcome::Failed(find(9).unwrap().solve(1, "12x\n", false).unwrap_err()),
+                elapsed: Duration::from_millis(3),
+            },
+        ];
+
+        assert_eq!(summary(&results), "\
+Day  Part        Time  Answer
+  7     1      2.00ms  3749
+  7     2      1.00ms  panicked: boom
+  9     1      3.00ms  failed: Could not parse the input for day 9: line 1, column 3: expected a digit, found 'x'
+
+2 of 3 parts did not produce an answer.
+");
+    }
+
+    #[test]
+    fn test_tasks_with_examples() {
+        let (all, skipped) = tasks(&[1, 2], false);
+        assert_eq!((all.len(), skipped), (2 * DAYS.len(), 0));
+
+        // days 1 to 5 have no examples, and days 11 and 14 only have one for part 1
+        let (examples, skipped) = tasks(&[1, 2], true);
+        assert_eq!(skipped, 12);
+        assert_eq!(examples.len(), 2 * DAYS.len() - 12);
+        assert!(examples.iter().all(|(day, part)| day.check_example(*part).is_ok()));
+    }
+}
@@ -0,0 +1,154 @@
+use std::io::{self, Write};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{cursor, execute, queue, terminal};
+use common::Input;
+use day15::{Direction, Warehouse};
+use crate::cli::{CliError, Options};
+
+const KEYS: &str = "arrow keys or wasd: move   n: next command   u: undo   q: quit";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Action {
+    Move(Direction),
+    Step,
+    Undo,
+    Quit,
+}
+
+/// Lets the robot of day 15 be moved around the warehouse by hand. Part 2 plays on the widened
+/// map.
+pub(crate) fn run(options: &Options, input: &Input) -> Result<(), CliError> {
+    let file_name = match options.example {
+        true => "example.txt",
+        false => "input.txt",
+    };
+
+    let text = input.read(15, file_name)?;
+    let warehouse = match options.part {
+        Some(2) => Warehouse::try_from_part2(&text),
+        _ => Warehouse::try_from(&text),
+    };
+    let mut warehouse = warehouse.map_err(|e| CliError::BadInput(15, e.diagnostic(&text)))?;
+
+    play(&mut warehouse).map_err(|e| CliError::Terminal(e.to_string()))?;
+
+    println!("\n{}\n\nGPS sum: {}", warehouse, warehouse.gps());
+
+    Ok(())
+}
+
+fn play(warehouse: &mut Warehouse) -> io::Result<()> {
+    let mut out = io::stdout();
+
+    terminal::enable_raw_mode()?;
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let result = event_loop(warehouse, &mut out);
+
+    // put the terminal back the way it was even if drawing failed halfway
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    result
+}
+
+fn event_loop(warehouse: &mut Warehouse, out: &mut impl Write) -> io::Result<()> {
+    loop {
+        draw(warehouse, out)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+
+        match action(key) {
+            Some(Action::Move(direction)) => {
+                warehouse.move_robot(direction);
+            }
+            Some(Action::Step) => {
+                warehouse.step();
+            }
+            Some(Action::Undo) => {
+                warehouse.undo();
+            }
+            Some(Action::Quit) => return Ok(()),
+            None => {}
+        }
+    }
+}
+
+fn action(key: KeyEvent) -> Option<Action> {
+    if key.kind != KeyEventKind::Press {
+        return None;
+    }
+
+    match key.code {
+        KeyCode::Up | KeyCode::Char('w') => Some(Action::Move(Direction::Up)),
+        KeyCode::Right | KeyCode::Char('d') => Some(Action::Move(Direction::Right)),
+        KeyCode::Down | KeyCode::Char('s') => Some(Action::Move(Direction::Down)),
+        KeyCode::Left | KeyCode::Char('a') => Some(Action::Move(Direction::Left)),
+        KeyCode::Char('n') => Some(Action::Step),
+        KeyCode::Char('u') | KeyCode::Backspace => Some(Action::Undo),
+        // raw mode swallows ctrl-c, so it has to be handled here
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Some(Action::Quit),
+        KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+        _ => None,
+    }
+}
+
+fn draw(warehouse: &Warehouse, out: &mut impl Write) -> io::Result<()> {
+    queue!(out, cursor::MoveTo(0, 0), terminal::Clear(terminal::ClearType::All))?;
+
+    // raw mode doesn't move back to the start of the line on a newline
+    for line in warehouse.to_string().lines() {
+        write!(out, "{}\r\n", line)?;
+    }
+
+    let next = match warehouse.commands().get(warehouse.next_command()) {
+        Some(direction) => format!("{:?}", direction),
+        None => "none left".to_string(),
+    };
+
+    write!(
+        out,
+        "\r\nGPS sum: {}   command {} of {}, next: {}\r\n{}\r\n",
+        warehouse.gps(),
+        warehouse.next_command(),
+        warehouse.commands().len(),
+        next,
+        KEYS,
+    )?;
+
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_action() {
+        assert_eq!(action(press(KeyCode::Left)), Some(Action::Move(Direction::Left)));
+        assert_eq!(action(press(KeyCode::Char('w'))), Some(Action::Move(Direction::Up)));
+        assert_eq!(action(press(KeyCode::Char('n'))), Some(Action::Step));
+        assert_eq!(action(press(KeyCode::Backspace)), Some(Action::Undo));
+        assert_eq!(action(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)), Some(Action::Quit));
+        assert_eq!(action(press(KeyCode::Char('c'))), None);
+    }
+
+    #[test]
+    fn test_draw() {
+        let mut warehouse = Warehouse::try_from("####\n#@O#\n####\n\n>>\n").unwrap();
+        warehouse.step();
+
+        let mut out: Vec<u8> = Vec::new();
+        draw(&warehouse, &mut out).unwrap();
+        let screen = String::from_utf8(out).unwrap();
+
+        assert!(screen.contains("#@O#\r\n"));
+        assert!(screen.contains("GPS sum: 102   command 1 of 2, next: Right\r\n"));
+    }
+}